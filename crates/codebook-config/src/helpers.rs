@@ -1,6 +1,29 @@
+use log::warn;
 use std::env;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
+/// Environment variable that overrides the cache directory, wherever it is
+/// configured.
+pub(crate) const CACHE_DIR_ENV: &str = "CODEBOOK_CACHE_DIR";
+
+/// The cache directory to use: `CODEBOOK_CACHE_DIR` if set, else the
+/// `cache_dir` setting (already resolved), else the platform default.
+pub(crate) fn resolve_cache_dir(configured: Option<PathBuf>) -> PathBuf {
+    cache_dir_from(env::var_os(CACHE_DIR_ENV), configured)
+}
+
+fn cache_dir_from(env_value: Option<OsString>, configured: Option<PathBuf>) -> PathBuf {
+    env_value
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            let path = PathBuf::from(value);
+            expand_env_and_tilde(&path).unwrap_or(path)
+        })
+        .or(configured)
+        .unwrap_or_else(default_cache_dir)
+}
+
 pub(crate) fn default_cache_dir() -> PathBuf {
     #[cfg(windows)]
     {
@@ -78,12 +101,77 @@ pub(crate) fn expand_tilde<P: AsRef<Path>>(path_user_input: P) -> Option<PathBuf
     }
 }
 
+/// Expand environment variables (`$VAR`, `${VAR}` and `%VAR%`), then `~` as
+/// in [`expand_tilde`], so a config can name paths that differ per machine.
+/// An undefined variable is left as written, with a warning.
+pub(crate) fn expand_env_and_tilde<P: AsRef<Path>>(path_user_input: P) -> Option<PathBuf> {
+    let p = path_user_input.as_ref();
+    match p.to_str() {
+        Some(path) if path.contains(['$', '%']) => expand_tilde(expand_env_vars(path)),
+        _ => expand_tilde(p),
+    }
+}
+
+fn expand_env_vars(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(i) = rest.find(['$', '%']) {
+        out.push_str(&rest[..i]);
+        let sigil = &rest[i..i + 1];
+        let after = &rest[i + 1..];
+        // The name and the text the reference spans after the sigil
+        let reference = match sigil {
+            "$" => match after.strip_prefix('{') {
+                Some(braced) => braced
+                    .find('}')
+                    .map(|end| (&braced[..end], end + 2))
+                    .filter(|(name, _)| is_var_name(name)),
+                None => {
+                    let end = after
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                        .unwrap_or(after.len());
+                    Some((&after[..end], end)).filter(|(name, _)| is_var_name(name))
+                }
+            },
+            _ => after
+                .find('%')
+                .map(|end| (&after[..end], end + 1))
+                .filter(|(name, _)| is_var_name(name)),
+        };
+        match reference {
+            Some((name, len)) => {
+                match env::var(name) {
+                    Ok(value) => out.push_str(&value),
+                    Err(_) => {
+                        warn!(
+                            "Environment variable {name} is not set, leaving it unexpanded in {input}"
+                        );
+                        out.push_str(&rest[i..i + 1 + len]);
+                    }
+                }
+                rest = &after[len..];
+            }
+            None => {
+                out.push_str(sigil);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_var_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     #[cfg(not(windows))]
-    use std::ffi::OsString;
-    #[cfg(not(windows))]
     use std::sync::{Mutex, MutexGuard};
 
     #[cfg(not(windows))]
@@ -158,6 +246,26 @@ mod tests {
         drop(guard);
     }
 
+    #[test]
+    fn cache_dir_env_wins_over_config() {
+        let configured = PathBuf::from("/work/.cache/codebook");
+        assert_eq!(
+            cache_dir_from(Some("/ci/cache".into()), Some(configured.clone())),
+            PathBuf::from("/ci/cache")
+        );
+        assert_eq!(
+            cache_dir_from(Some("".into()), Some(configured.clone())),
+            configured
+        );
+        assert_eq!(cache_dir_from(None, None), default_cache_dir());
+
+        let home = dirs::home_dir().expect("home directory must be available for the test");
+        assert_eq!(
+            cache_dir_from(Some("~/ci-cache".into()), None),
+            home.join("ci-cache")
+        );
+    }
+
     #[test]
     fn expand_tilde_resolves_home_directory() {
         let home = dirs::home_dir().expect("home directory must be available for the test");
@@ -208,4 +316,48 @@ mod tests {
 
         assert_eq!(expand_tilde(&path), Some(path));
     }
+
+    #[test]
+    fn expand_env_and_tilde_expands_variables() {
+        unsafe { env::set_var("CODEBOOK_TEST_EXPAND_DIR", "/opt/team") };
+
+        for input in [
+            "$CODEBOOK_TEST_EXPAND_DIR/codebook.toml",
+            "${CODEBOOK_TEST_EXPAND_DIR}/codebook.toml",
+            "%CODEBOOK_TEST_EXPAND_DIR%/codebook.toml",
+        ] {
+            assert_eq!(
+                expand_env_and_tilde(input),
+                Some(PathBuf::from("/opt/team/codebook.toml")),
+                "{input}"
+            );
+        }
+        assert_eq!(
+            expand_env_and_tilde("${CODEBOOK_TEST_EXPAND_DIR}dicts/$CODEBOOK_TEST_EXPAND_DIR"),
+            Some(PathBuf::from("/opt/teamdicts//opt/team"))
+        );
+    }
+
+    #[test]
+    fn expand_env_and_tilde_expands_tilde_after_variables() {
+        let home = dirs::home_dir().expect("home directory must be available for the test");
+        unsafe { env::set_var("CODEBOOK_TEST_EXPAND_TILDE", "~/dotfiles") };
+
+        assert_eq!(
+            expand_env_and_tilde("$CODEBOOK_TEST_EXPAND_TILDE/codebook.toml"),
+            Some(home.join("dotfiles/codebook.toml"))
+        );
+    }
+
+    #[test]
+    fn expand_env_and_tilde_leaves_undefined_and_malformed_references() {
+        for input in [
+            "$CODEBOOK_TEST_UNDEFINED_VAR/codebook.toml",
+            "${CODEBOOK_TEST_UNDEFINED_VAR}/codebook.toml",
+            "%CODEBOOK_TEST_UNDEFINED_VAR%/codebook.toml",
+            "100%/$/${unclosed/${}/%not a var%/$1",
+        ] {
+            assert_eq!(expand_env_and_tilde(input), Some(PathBuf::from(input)));
+        }
+    }
 }
@@ -0,0 +1,100 @@
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::warn;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// Gitignore-syntax file of paths to skip, read from the project config's
+/// directory and any directory below it.
+pub(crate) const IGNORE_FILE: &str = ".codebookignore";
+
+/// Change-detection stamp of an ignore file; None if it doesn't exist.
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+/// Parsed `.codebookignore` files by directory. Each lookup stats the files
+/// it needs and re-parses the ones that changed, so edits apply without a
+/// config reload.
+#[derive(Debug, Default)]
+pub(crate) struct IgnoreFiles {
+    matchers: RwLock<HashMap<PathBuf, (Stamp, Gitignore)>>,
+}
+
+impl IgnoreFiles {
+    /// Whether `relative`, a path under `root`, is ignored by a
+    /// `.codebookignore` in `root` or a directory between it and the path.
+    /// The deepest file with a matching rule decides, and within a file the
+    /// last matching rule wins, so `!pattern` re-includes a path.
+    pub(crate) fn is_ignored(&self, root: &Path, relative: &Path) -> bool {
+        let mut components = Vec::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(c) => components.push(c),
+                Component::CurDir => {}
+                // Absolute or escaping paths aren't under root
+                _ => return false,
+            }
+        }
+        let Some((_, parents)) = components.split_last() else {
+            return false;
+        };
+
+        let absolute: PathBuf = std::iter::once(root.as_os_str())
+            .chain(components.iter().copied())
+            .collect();
+        let is_dir = absolute.is_dir();
+        let mut dirs = vec![root.to_path_buf()];
+        for parent in parents {
+            let next = dirs[dirs.len() - 1].join(parent);
+            dirs.push(next);
+        }
+
+        for dir in dirs.iter().rev() {
+            let matched = self.with_matcher(dir, |matcher| {
+                match matcher.matched_path_or_any_parents(&absolute, is_dir) {
+                    Match::Ignore(_) => Some(true),
+                    Match::Whitelist(_) => Some(false),
+                    Match::None => None,
+                }
+            });
+            if let Some(ignored) = matched {
+                return ignored;
+            }
+        }
+        false
+    }
+
+    /// Run `f` with the (possibly empty) matcher for `dir`'s ignore file.
+    fn with_matcher<R>(&self, dir: &Path, f: impl FnOnce(&Gitignore) -> R) -> R {
+        let path = dir.join(IGNORE_FILE);
+        let stamp: Stamp = fs::metadata(&path)
+            .ok()
+            .map(|m| (m.modified().ok(), m.len()));
+        if let Some((cached, matcher)) = self.matchers.read().unwrap().get(dir)
+            && *cached == stamp
+        {
+            return f(matcher);
+        }
+
+        let matcher = if stamp.is_some() {
+            let mut builder = GitignoreBuilder::new(dir);
+            if let Some(e) = builder.add(&path) {
+                warn!("Problem reading {}: {e}", path.display());
+            }
+            builder.build().unwrap_or_else(|e| {
+                warn!("Ignoring invalid {}: {e}", path.display());
+                Gitignore::empty()
+            })
+        } else {
+            Gitignore::empty()
+        };
+        let result = f(&matcher);
+        self.matchers
+            .write()
+            .unwrap()
+            .insert(dir.to_path_buf(), (stamp, matcher));
+        result
+    }
+}
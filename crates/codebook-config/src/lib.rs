@@ -1,22 +1,35 @@
 pub mod helpers;
+mod ignore_file;
 pub mod settings;
 mod watched_file;
-use crate::helpers::expand_tilde;
-pub use crate::settings::ConfigSettings;
+use crate::helpers::expand_env_and_tilde;
+use crate::ignore_file::IgnoreFiles;
+use crate::settings::resolve_config_path;
+pub use crate::settings::{CheckScope, ConfigSettings, CustomDictionary, LanguageSettings};
 use crate::watched_file::WatchedFile;
 use log::debug;
 use log::info;
 use regex::Regex;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::env;
 use std::fmt::Debug;
 use std::fs;
-use std::io;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 static CACHE_DIR: &str = "codebook";
 static GLOBAL_CONFIG_FILE: &str = "codebook.toml";
 static USER_CONFIG_FILES: [&str; 2] = ["codebook.toml", ".codebook.toml"];
+/// Personal settings kept next to the project config and merged over it,
+/// meant to be gitignored so one person's words don't land in the repo.
+static LOCAL_CONFIG_FILE: &str = ".codebook.local.toml";
+/// Host files that may embed Codebook settings: a `[tool.codebook]` table in
+/// `pyproject.toml`, or a `"codebook"` key in `package.json`. Checked after
+/// USER_CONFIG_FILES, so a dedicated config in the same directory wins.
+static EMBEDDED_CONFIG_FILES: [&str; 2] = ["pyproject.toml", "package.json"];
 
 /// Errors from loading or saving Codebook configuration.
 #[derive(Debug, thiserror::Error)]
@@ -28,6 +41,11 @@ pub enum ConfigError {
         path: PathBuf,
         source: toml::de::Error,
     },
+    #[error("failed to parse config file {path}: {source}")]
+    ParseJson {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
     #[error("failed to write config file {path}: {source}")]
     Write { path: PathBuf, source: io::Error },
     #[error("failed to serialize config: {0}")]
@@ -40,20 +58,52 @@ pub enum ConfigError {
 ///
 /// The `add_*` methods only mutate in-memory settings and report whether the
 /// entry was newly added; persisting is a separate, fallible step (see
-/// `CodebookConfigFile::save`).
+/// `CodebookConfigFile::save`). Each starts from the config file as it is on
+/// disk, so one that exists but can't be loaded isn't overwritten: the batch
+/// `add_words` methods return that error, the others add nothing.
 pub trait CodebookConfig: Sync + Send + Debug {
     fn add_word(&self, word: &str) -> bool;
     fn add_word_global(&self, word: &str) -> bool;
+    /// Add many words at once, returning how many were newly added. Cheaper
+    /// than calling `add_word` for each, which re-sorts the list every time.
+    fn add_words(&self, words: &[&str]) -> Result<usize, ConfigError>;
+    fn add_words_global(&self, words: &[&str]) -> Result<usize, ConfigError>;
     fn add_ignore(&self, file: &str) -> bool;
     fn add_include(&self, file: &str) -> bool;
     fn get_dictionary_ids(&self) -> Vec<String>;
+    fn detect_languages(&self) -> Vec<String>;
     fn should_ignore_path(&self, path: &Path) -> bool;
     fn should_include_path(&self, path: &Path) -> bool;
     fn is_allowed_word(&self, word: &str) -> bool;
     fn should_flag_word(&self, word: &str) -> bool;
     fn get_ignore_patterns(&self) -> Vec<Regex>;
+    fn get_keep_together_patterns(&self) -> Vec<Regex>;
     fn get_min_word_length(&self) -> usize;
+    fn get_min_word_length_code(&self) -> usize;
+    fn get_min_word_length_text(&self) -> usize;
+    fn max_suggestion_distance(&self, word: &str) -> usize;
+    fn strict_dictionaries(&self) -> bool;
+    fn offline(&self) -> bool;
+    fn skip_binary(&self) -> bool;
+    fn skip_minified(&self) -> bool;
+    fn disable_file_marker(&self) -> Option<String>;
+    fn split_camel_case(&self) -> bool;
+    fn hyphenated_compounds(&self) -> bool;
+    fn learn_project_symbols(&self) -> bool;
+    fn sentence_case_suggestions(&self) -> bool;
+    fn flag_confusables(&self) -> bool;
     fn should_check_tag(&self, tag: &str) -> bool;
+    fn check_strings(&self, language_ids: &[&str]) -> bool;
+    fn language_override(&self, path: &Path) -> Option<String>;
+    /// Local Hunspell dictionaries, with paths resolved to usable ones
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary>;
+    /// A hash of everything that decides which words are known: the
+    /// dictionary IDs, custom dictionaries and `words`/`flag_words`/
+    /// `allowed_contractions` lists.
+    /// It changes whenever any of them do, so caches built from them can
+    /// tell they're stale after a `reload`. Stable within a process only.
+    fn dictionaries_fingerprint(&self) -> u64;
+    fn frequency_list(&self) -> Option<PathBuf>;
     fn cache_dir(&self) -> &Path;
 
     /// Resolve settings with overrides applied for a specific file path.
@@ -63,6 +113,31 @@ pub trait CodebookConfig: Sync + Send + Debug {
     }
 }
 
+/// Whether two loaded configs hold the same settings. Compiled regexes and
+/// globs have no equality, so compare the serialized form, which writes them
+/// back as their source strings.
+fn same_settings(a: Option<&ConfigSettings>, b: Option<&ConfigSettings>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => match (toml::Value::try_from(a), toml::Value::try_from(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Hash the inputs of `CodebookConfig::dictionaries_fingerprint`.
+fn dictionaries_fingerprint(settings: &ConfigSettings, custom: &[CustomDictionary]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    settings.dictionary_ids().hash(&mut hasher);
+    custom.hash(&mut hasher);
+    settings.words.hash(&mut hasher);
+    settings.flag_words.hash(&mut hasher);
+    settings.allowed_contractions.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Internal mutable state
 #[derive(Debug)]
 struct ConfigInner {
@@ -70,6 +145,18 @@ struct ConfigInner {
     project_config: WatchedFile<ConfigSettings>,
     /// Global config file watcher
     global_config: WatchedFile<ConfigSettings>,
+    /// Personal `.codebook.local.toml` watcher, merged over the project config
+    local_config: WatchedFile<ConfigSettings>,
+    /// Settings given on the command line, merged over the files. Never saved.
+    cli_overrides: Option<ConfigSettings>,
+    /// `words` and `flag_words` read from the configs' `words_file` and
+    /// `flag_words_file`. Kept apart from the files' own settings so saving
+    /// a config never copies them into it.
+    word_lists: ConfigSettings,
+    /// The files `word_lists` was read from, each with its words, and
+    /// whether it's a `flag_words_file`. Watched so editing a list takes
+    /// effect without touching the config that names it.
+    word_list_files: Vec<(WatchedFile<Vec<String>>, bool)>,
     /// Current snapshot
     snapshot: Arc<ConfigSettings>,
 }
@@ -80,6 +167,14 @@ pub struct CodebookConfigFile {
     inner: RwLock<ConfigInner>,
     /// Directory for caching
     pub cache_dir: PathBuf,
+    /// Directory the config was loaded for (the workspace root). Relative
+    /// file paths passed to path matching are relative to it.
+    start_dir: Option<PathBuf>,
+    /// Directory containing the project config. Path globs are written
+    /// relative to it, wherever the config was found.
+    config_dir: Option<PathBuf>,
+    /// `.codebookignore` files under config_dir
+    ignore_files: IgnoreFiles,
 }
 
 impl Default for CodebookConfigFile {
@@ -87,12 +182,19 @@ impl Default for CodebookConfigFile {
         let inner = ConfigInner {
             project_config: WatchedFile::new(None),
             global_config: WatchedFile::new(None),
+            local_config: WatchedFile::new(None),
+            cli_overrides: None,
+            word_lists: ConfigSettings::default(),
+            word_list_files: Vec::new(),
             snapshot: Arc::new(ConfigSettings::default()),
         };
 
         Self {
             inner: RwLock::new(inner),
-            cache_dir: helpers::default_cache_dir(),
+            cache_dir: helpers::resolve_cache_dir(None),
+            start_dir: None,
+            config_dir: None,
+            ignore_files: IgnoreFiles::default(),
         }
     }
 }
@@ -107,6 +209,11 @@ impl CodebookConfigFile {
     ///
     /// When `project_config_path` is provided, auto-discovery is skipped: the override
     /// path is used even if the file does not yet exist (it will be created on save).
+    ///
+    /// Path globs (`ignore_paths`, `include_paths`, `overrides`, `language_overrides`)
+    /// are evaluated relative to the directory containing the project config, not
+    /// `current_dir`. Relative file paths given to the matching methods are taken
+    /// as relative to `current_dir` and rebased onto the config's directory first.
     pub fn load_with_overrides(
         current_dir: Option<&Path>,
         global_config_path: Option<PathBuf>,
@@ -136,14 +243,18 @@ impl CodebookConfigFile {
             .unwrap_or_else(|_| start_dir.to_path_buf());
         let start_dir = start_dir.as_path();
 
-        let config = Self::default();
+        let mut config = Self {
+            start_dir: Some(start_dir.to_path_buf()),
+            ..Self::default()
+        };
         let mut inner = config.inner.write().unwrap();
 
-        // First, try to load global config. Expand tildes here so loading and
-        // saving agree on the real path; a `~` taken literally would make the
-        // existing config invisible and let a later save overwrite it.
+        // First, try to load global config. Expand `~` and variables here so
+        // loading and saving agree on the real path; a `~` taken literally
+        // would make the existing config invisible and let a later save
+        // overwrite it.
         let global_config_path = match global_config_override {
-            Some(path) => Some(expand_tilde(&path).unwrap_or(path)),
+            Some(path) => Some(expand_env_and_tilde(&path).unwrap_or(path)),
             None => Self::find_global_config_path(),
         };
 
@@ -166,7 +277,9 @@ impl CodebookConfigFile {
 
         // Then resolve the project config path: use the override if provided,
         // otherwise search up from start_dir.
-        let project_path = match project_config_override.map(|p| expand_tilde(&p).unwrap_or(p)) {
+        let project_path = match project_config_override
+            .map(|p| expand_env_and_tilde(&p).unwrap_or(p))
+        {
             Some(override_path) => {
                 if override_path.exists() {
                     Some(override_path)
@@ -200,10 +313,42 @@ impl CodebookConfigFile {
             inner.project_config = WatchedFile::new(Some(default_path));
         }
 
+        // The personal config always sits next to the project config, even
+        // when neither exists yet, so adding a personal word has a place to go
+        if let Some(dir) = inner.project_config.path().and_then(Path::parent) {
+            let local_path = dir.join(LOCAL_CONFIG_FILE);
+            let local_config = WatchedFile::new(Some(local_path.clone()));
+            if local_path.exists() {
+                inner.local_config =
+                    local_config.load(|path| Self::load_settings_from_file(path))?;
+                debug!("Loaded personal config from {}", local_path.display());
+            } else {
+                inner.local_config = local_config;
+            }
+        }
+
         // Calculate initial effective settings
+        Self::load_word_lists(&mut inner);
         Self::rebuild_snapshot(&mut inner);
 
+        let config_dir = inner
+            .project_config
+            .path()
+            .and_then(Path::parent)
+            .map(|dir| {
+                if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    dir
+                }
+            })
+            .map(|dir| dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
         drop(inner);
+        config.config_dir = config_dir;
+        // Read once: the dictionary manager is built with it
+        config.cache_dir = helpers::resolve_cache_dir(
+            config.configured_path(|settings| settings.cache_dir.as_deref()),
+        );
         Ok(config)
     }
     /// Find the platform-specific global config directory and file path
@@ -240,36 +385,88 @@ impl CodebookConfigFile {
 
     /// Find project configuration by searching up from the current directory
     fn find_project_config(start_dir: &Path) -> Option<PathBuf> {
-        let config_files = USER_CONFIG_FILES;
+        start_dir.ancestors().find_map(Self::project_config_in)
+    }
 
-        // Start from the given directory and walk up to root
-        let mut current_dir = Some(start_dir.to_path_buf());
+    /// The file names `project_config_in` looks for, for watching them.
+    pub fn project_config_names() -> impl Iterator<Item = &'static str> {
+        USER_CONFIG_FILES.into_iter().chain(EMBEDDED_CONFIG_FILES)
+    }
 
-        while let Some(dir) = current_dir {
-            // Try each possible config filename in the current directory
-            for config_name in &config_files {
-                let config_path = dir.join(config_name);
-                if config_path.is_file() {
-                    return Some(config_path);
-                }
+    /// The project config in `dir` itself, without searching up: a
+    /// `codebook.toml` or `.codebook.toml`, else a host file like
+    /// `pyproject.toml` with a Codebook section.
+    pub fn project_config_in(dir: &Path) -> Option<PathBuf> {
+        // Try each possible config filename in the directory
+        for config_name in &USER_CONFIG_FILES {
+            let config_path = dir.join(config_name);
+            if config_path.is_file() {
+                return Some(config_path);
             }
-
-            // Move to parent directory
-            current_dir = dir.parent().map(PathBuf::from);
         }
 
-        None
+        // Then host files, which only count when they embed our settings.
+        // One that fails to parse is selected anyway so the error surfaces
+        // at load instead of the search silently skipping past it.
+        EMBEDDED_CONFIG_FILES
+            .iter()
+            .map(|host_name| dir.join(host_name))
+            .find(|host_path| {
+                host_path.is_file() && !matches!(Self::load_embedded_settings(host_path), Ok(None))
+            })
     }
 
     /// Load settings from a file
     fn load_settings_from_file<P: AsRef<Path>>(path: P) -> Result<ConfigSettings, ConfigError> {
         let path = path.as_ref();
-        let content = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+        if is_embedded_config(path) {
+            // The table may have been removed since the file was selected;
+            // that leaves the project without settings, not with an error.
+            return Ok(Self::load_embedded_settings(path)?.unwrap_or_default());
+        }
+        let content = Self::read_config(path)?;
+
+        toml::from_str(&content).map_err(|source| ConfigError::Parse {
             path: path.to_path_buf(),
             source,
-        })?;
+        })
+    }
 
-        toml::from_str(&content).map_err(|source| ConfigError::Parse {
+    /// Load the settings embedded in a host file like `pyproject.toml`.
+    /// Returns None when the host file has no Codebook section.
+    fn load_embedded_settings(path: &Path) -> Result<Option<ConfigSettings>, ConfigError> {
+        let content = Self::read_config(path)?;
+        let parse_err = |source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        };
+        let json_err = |source| ConfigError::ParseJson {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let mut document: serde_json::Value =
+                serde_json::from_str(&content).map_err(json_err)?;
+            match document.get_mut("codebook").map(serde_json::Value::take) {
+                Some(section) => serde_json::from_value(section).map(Some).map_err(json_err),
+                None => Ok(None),
+            }
+        } else {
+            let mut document: toml::Table = toml::from_str(&content).map_err(parse_err)?;
+            let section = document.remove("tool").and_then(|tool| match tool {
+                toml::Value::Table(mut tool) => tool.remove("codebook"),
+                _ => None,
+            });
+            match section {
+                Some(section) => section.try_into().map(Some).map_err(parse_err),
+                None => Ok(None),
+            }
+        }
+    }
+
+    fn read_config(path: &Path) -> Result<String, ConfigError> {
+        fs::read_to_string(path).map_err(|source| ConfigError::Read {
             path: path.to_path_buf(),
             source,
         })
@@ -308,6 +505,22 @@ impl CodebookConfigFile {
     /// Never fails: a config that can no longer be loaded keeps its last good
     /// content (see WatchedFile::reload_if_changed).
     pub fn reload(&self) -> bool {
+        // With autosave this runs on nearly every keystroke; a stat under the
+        // read lock skips the clone, read and parse when no file changed.
+        {
+            let inner = self.inner.read().unwrap();
+            if !inner.global_config.has_changed()
+                && !inner.project_config.has_changed()
+                && !inner.local_config.has_changed()
+                && !inner
+                    .word_list_files
+                    .iter()
+                    .any(|(file, _)| file.has_changed())
+            {
+                return false;
+            }
+        }
+
         let mut inner = self.inner.write().unwrap();
         let mut changed = false;
 
@@ -319,8 +532,8 @@ impl CodebookConfigFile {
 
         if global_changed {
             debug!("Global config reloaded");
+            changed |= !same_settings(inner.global_config.content(), new_global.content());
             inner.global_config = new_global;
-            changed = true;
         }
 
         // Check and reload project config if changed
@@ -331,38 +544,210 @@ impl CodebookConfigFile {
 
         if project_changed {
             debug!("Project config reloaded");
+            changed |= !same_settings(inner.project_config.content(), new_project.content());
             inner.project_config = new_project;
-            changed = true;
         }
 
-        // Recalculate effective settings if anything changed
+        // Check and reload personal config if changed
+        let (new_local, local_changed) = inner
+            .local_config
+            .clone()
+            .reload_if_changed(|path| Self::load_settings_from_file(path));
+
+        if local_changed {
+            debug!("Personal config reloaded");
+            changed |= !same_settings(inner.local_config.content(), new_local.content());
+            inner.local_config = new_local;
+        }
+
+        // Recalculate effective settings (and recompile nothing) unless a
+        // setting actually changed: a save that rewrites the same settings
+        // keeps the current snapshot and its compiled patterns.
         if changed {
+            Self::load_word_lists(&mut inner);
             Self::rebuild_snapshot(&mut inner);
+        } else if inner
+            .word_list_files
+            .iter()
+            .any(|(file, _)| file.has_changed())
+        {
+            debug!("Word list reloaded");
+            let previous = std::mem::take(&mut inner.word_lists);
+            Self::load_word_lists(&mut inner);
+            changed = !same_settings(Some(&previous), Some(&inner.word_lists));
+            if changed {
+                Self::rebuild_snapshot(&mut inner);
+            }
         }
 
         changed
     }
 
-    /// Save the project configuration to its file
-    pub fn save(&self) -> Result<(), ConfigError> {
+    /// Merge settings given on the command line (extra `dictionaries`,
+    /// `words`, `flag_words`, ...) over the loaded config, using the same
+    /// rules as merging the project config over the global one. They live in
+    /// memory only: `save` never writes them, and they survive reloads.
+    /// Calling this again adds to the earlier overrides.
+    pub fn merge_cli_overrides(&self, overrides: ConfigSettings) {
         let mut inner = self.inner.write().unwrap();
-        let watched = &inner.project_config;
-        Self::save_watched(watched, "project")?;
-        inner.project_config = inner.project_config.clone().restamped();
-        Ok(())
+        match &mut inner.cli_overrides {
+            Some(existing) => existing.merge(overrides),
+            None => inner.cli_overrides = Some(overrides),
+        }
+        Self::rebuild_snapshot(&mut inner);
+    }
+
+    /// The settings in effect, as TOML. A comment above each setting names
+    /// the layers that set it, lowest precedence first; for lists they are
+    /// all merged, for anything else the last one wins.
+    pub fn effective_toml(&self) -> Result<String, ConfigError> {
+        let inner = self.inner.read().unwrap();
+        let use_global = inner
+            .project_config
+            .content()
+            .is_none_or(|project| project.use_global);
+        let mut layers = Vec::new();
+        for (label, watched, in_effect) in [
+            ("global config", &inner.global_config, use_global),
+            ("project config", &inner.project_config, true),
+            ("personal config", &inner.local_config, true),
+        ] {
+            if let Some(settings) = watched.content().filter(|_| in_effect) {
+                let label = match watched.path() {
+                    Some(path) => format!("{label} {}", path.display()),
+                    None => label.to_string(),
+                };
+                layers.push((label, settings));
+            }
+        }
+        layers.push(("words files".to_string(), &inner.word_lists));
+        if let Some(cli) = &inner.cli_overrides {
+            layers.push(("command line".to_string(), cli));
+        }
+        let as_table = |settings: &ConfigSettings| -> Result<toml::Table, ConfigError> {
+            match toml::Value::try_from(settings)? {
+                toml::Value::Table(table) => Ok(table),
+                _ => Ok(toml::Table::new()),
+            }
+        };
+        let layers = layers
+            .into_iter()
+            .map(|(label, settings)| Ok((label, as_table(settings)?)))
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        let effective = as_table(&inner.snapshot)?;
+        drop(inner);
+
+        if effective.is_empty() {
+            return Ok("# Nothing set: every setting has its default value\n".to_string());
+        }
+        // Plain keys go first: after a `[table]` header, any key would be
+        // read as part of that table
+        let is_table = |value: &toml::Value| match value {
+            toml::Value::Table(_) => true,
+            toml::Value::Array(items) => !items.is_empty() && items.iter().all(|v| v.is_table()),
+            _ => false,
+        };
+        let (tables, plain): (Vec<_>, Vec<_>) =
+            effective.into_iter().partition(|(_, v)| is_table(v));
+        let mut out = String::new();
+        for (key, value) in plain.into_iter().chain(tables) {
+            let sources: Vec<&str> = layers
+                .iter()
+                .filter(|(_, table)| table.contains_key(&key))
+                .map(|(label, _)| label.as_str())
+                .collect();
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            for source in sources {
+                out.push_str(&format!("# from {source}\n"));
+            }
+            let mut single = toml::Table::new();
+            single.insert(key, value);
+            out.push_str(&toml::to_string_pretty(&single)?);
+        }
+        Ok(out)
+    }
+
+    /// Save the project configuration to its file.
+    ///
+    /// Settings loaded from a host file like `pyproject.toml` are never
+    /// written back into it (that would drop its comments and formatting);
+    /// they move to a `codebook.toml` next to it, which takes precedence
+    /// from then on.
+    pub fn save(&self) -> Result<(), ConfigError> {
+        {
+            let mut inner = self.inner.write().unwrap();
+            if let Some(host_path) = inner.project_config.path()
+                && is_embedded_config(host_path)
+            {
+                let dedicated = host_path.with_file_name(USER_CONFIG_FILES[0]);
+                info!(
+                    "Moving project configuration from {} to {}",
+                    host_path.display(),
+                    dedicated.display()
+                );
+                let settings = inner.project_config.content().cloned().unwrap_or_default();
+                inner.project_config =
+                    WatchedFile::new(Some(dedicated)).with_content_value(settings);
+            }
+        }
+        self.save_file(|inner| &mut inner.project_config, "project")
     }
 
     /// Save the global configuration to its file
     pub fn save_global(&self) -> Result<(), ConfigError> {
+        self.save_file(|inner| &mut inner.global_config, "global")
+    }
+
+    /// Save the personal configuration to `.codebook.local.toml`
+    pub fn save_local(&self) -> Result<(), ConfigError> {
+        self.save_file(|inner| &mut inner.local_config, "personal")
+    }
+
+    /// Write one config file's current content to disk, then restamp it so
+    /// the write isn't seen as an external change. The write happens outside
+    /// the `inner` lock, under the file's `save_lock`, so saves of a file
+    /// shared between configs (like the global one) write one at a time.
+    fn save_file(
+        &self,
+        file: fn(&mut ConfigInner) -> &mut WatchedFile<ConfigSettings>,
+        label: &str,
+    ) -> Result<(), ConfigError> {
+        let path = file(&mut self.inner.write().unwrap())
+            .path()
+            .map(Path::to_path_buf);
+        let lock = path.as_deref().map(save_lock);
+        let _saving = lock.as_deref().map(|lock| lock.lock().unwrap());
+        let watched = file(&mut self.inner.write().unwrap()).clone();
+        Self::save_watched(&watched, label)?;
         let mut inner = self.inner.write().unwrap();
-        let watched = &inner.global_config;
-        Self::save_watched(watched, "global")?;
-        inner.global_config = inner.global_config.clone().restamped();
+        let watched = file(&mut inner);
+        *watched = watched.clone().restamped();
         Ok(())
     }
 
-    /// Write a watched config's content to its file. The caller must restamp
-    /// the watched file afterwards so the write isn't seen as an external change.
+    /// Add words to the personal config's allowlist, returning how many were
+    /// newly added. Like `add_words`, this only changes memory until
+    /// `save_local`.
+    pub fn add_words_local(&self, words: &[&str]) -> Result<usize, ConfigError> {
+        let mut added = 0;
+        self.update_settings(
+            |inner| &mut inner.local_config,
+            |settings| {
+                added = settings.insert_words(words);
+                added > 0
+            },
+        )?;
+        Ok(added)
+    }
+
+    /// Write a watched config's content to its file. The content goes to a
+    /// uniquely named temporary file that is then renamed over the config,
+    /// so a reader never sees a half-written file. A symlinked config is written through
+    /// to its target, and the file keeps its permissions. The caller must
+    /// restamp the watched file afterwards so the write isn't seen as an
+    /// external change.
     fn save_watched(watched: &WatchedFile<ConfigSettings>, label: &str) -> Result<(), ConfigError> {
         let Some(path) = watched.path() else {
             return Ok(());
@@ -380,8 +765,44 @@ impl CodebookConfigFile {
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(write_err)?;
         }
-        fs::write(path, content).map_err(write_err)
+        // Renaming over a symlink would replace the link itself
+        let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let permissions = fs::metadata(&target).ok().map(|m| m.permissions());
+        let dir = target.parent().unwrap_or(Path::new("."));
+        // The temporary file is removed if anything fails before `persist`
+        let mut temp = tempfile::NamedTempFile::new_in(dir).map_err(write_err)?;
+        temp.write_all(content.as_bytes()).map_err(write_err)?;
+        if let Some(permissions) = permissions {
+            temp.as_file()
+                .set_permissions(permissions)
+                .map_err(write_err)?;
+        }
+        temp.persist(&target).map_err(|e| write_err(e.error))?;
+        Ok(())
+    }
+
+    /// A path setting resolved relative to the config file that sets it. The
+    /// personal setting wins, then the project's.
+    fn configured_path(&self, field: impl Fn(&ConfigSettings) -> Option<&Path>) -> Option<PathBuf> {
+        let inner = self.inner.read().unwrap();
+        let project = inner.project_config.content();
+        if let Some(path) = inner
+            .local_config
+            .content()
+            .and_then(&field)
+            .or_else(|| project.and_then(&field))
+        {
+            return Some(resolve_config_path(path, self.config_dir.as_deref()));
+        }
+        if project.is_none_or(|p| p.use_global)
+            && let Some(path) = inner.global_config.content().and_then(&field)
+        {
+            let global_dir = inner.global_config.path().and_then(Path::parent);
+            return Some(resolve_config_path(path, global_dir));
+        }
+        None
     }
+
     /// Clean the cache directory
     pub fn clean_cache(&self) {
         let dir_path = self.cache_dir.clone();
@@ -425,6 +846,17 @@ impl CodebookConfigFile {
             .map(|p| p.to_path_buf())
     }
 
+    /// Get path to the personal `.codebook.local.toml`, whether or not it
+    /// exists yet
+    pub fn local_config_path(&self) -> Option<PathBuf> {
+        self.inner
+            .read()
+            .unwrap()
+            .local_config
+            .path()
+            .map(|p| p.to_path_buf())
+    }
+
     /// Get path to global config if it exists
     pub fn global_config_path(&self) -> Option<PathBuf> {
         self.inner
@@ -435,53 +867,178 @@ impl CodebookConfigFile {
             .map(|p| p.to_path_buf())
     }
 
+    /// Rebase a file path onto the project config's directory, which path
+    /// globs are written against. Paths outside that directory are returned
+    /// unchanged, so absolute-path globs still work for them.
+    pub fn config_relative_path<'a>(&self, path: &'a Path) -> Cow<'a, Path> {
+        let (Some(start_dir), Some(config_dir)) = (&self.start_dir, &self.config_dir) else {
+            return Cow::Borrowed(path);
+        };
+        if path.is_relative() && start_dir == config_dir {
+            return Cow::Borrowed(path);
+        }
+        let absolute = start_dir.join(path);
+        if let Ok(relative) = absolute.strip_prefix(config_dir) {
+            return Cow::Owned(relative.to_path_buf());
+        }
+        if let Ok(canonical) = absolute.canonicalize()
+            && let Ok(relative) = canonical.strip_prefix(config_dir)
+        {
+            return Cow::Owned(relative.to_path_buf());
+        }
+        Cow::Borrowed(path)
+    }
+
     fn rebuild_snapshot(inner: &mut ConfigInner) {
-        let effective =
+        let mut effective =
             Self::calculate_effective_settings(&inner.project_config, &inner.global_config);
+        if let Some(local) = inner.local_config.content() {
+            effective.merge(local.clone());
+        }
+        effective.merge(inner.word_lists.clone());
+        if let Some(cli) = &inner.cli_overrides {
+            effective.merge(cli.clone());
+        }
         inner.snapshot = Arc::new(effective);
     }
 
+    /// Read the `words_file` and `flag_words_file` of every config in
+    /// effect, each relative to the config that names it. The global
+    /// config's are skipped when the project sets `use_global = false`.
+    fn load_word_lists(inner: &mut ConfigInner) {
+        let use_global = inner
+            .project_config
+            .content()
+            .is_none_or(|project| project.use_global);
+        let mut files = Vec::new();
+        for (watched, in_effect) in [
+            (&inner.global_config, use_global),
+            (&inner.project_config, true),
+            (&inner.local_config, true),
+        ] {
+            let Some(settings) = watched.content().filter(|_| in_effect) else {
+                continue;
+            };
+            let dir = watched.path().and_then(Path::parent);
+            for (path, flag) in [
+                (&settings.words_file, false),
+                (&settings.flag_words_file, true),
+            ] {
+                let Some(path) = path else {
+                    continue;
+                };
+                let Ok(file) = WatchedFile::new(Some(resolve_config_path(path, dir)))
+                    .load(|path| Ok::<_, std::convert::Infallible>(read_word_list(path)));
+                files.push((file, flag));
+            }
+        }
+        let mut lists = ConfigSettings::default();
+        for (file, flag) in &files {
+            let words = file.content().cloned().unwrap_or_default();
+            if *flag {
+                lists.flag_words.extend(words);
+            } else {
+                lists.words.extend(words);
+            }
+        }
+        lists.sort_and_dedup();
+        inner.word_lists = lists;
+        inner.word_list_files = files;
+    }
+
     fn update_project_settings<F>(&self, update: F) -> bool
     where
         F: FnOnce(&mut ConfigSettings) -> bool,
     {
-        let mut inner = self.inner.write().unwrap();
-        let mut settings = inner
-            .project_config
-            .content()
-            .cloned()
-            .unwrap_or_else(ConfigSettings::default);
+        Self::changed_or_warn(self.update_settings(|inner| &mut inner.project_config, update))
+    }
 
-        if !update(&mut settings) {
-            return false;
-        }
+    fn update_global_settings<F>(&self, update: F) -> bool
+    where
+        F: FnOnce(&mut ConfigSettings) -> bool,
+    {
+        Self::changed_or_warn(self.update_settings(|inner| &mut inner.global_config, update))
+    }
 
-        inner.project_config = inner.project_config.clone().with_content_value(settings);
-        Self::rebuild_snapshot(&mut inner);
-        true
+    /// Whether an update that can only report a bool changed anything,
+    /// logging why if it couldn't be applied.
+    fn changed_or_warn(result: Result<bool, ConfigError>) -> bool {
+        result.unwrap_or_else(|e| {
+            log::warn!("Not updating config: {e}");
+            false
+        })
     }
 
-    fn update_global_settings<F>(&self, update: F) -> bool
+    /// Apply `update` to the settings of one config file and rebuild the
+    /// snapshot if it changed anything.
+    fn update_settings<F>(
+        &self,
+        file: fn(&mut ConfigInner) -> &mut WatchedFile<ConfigSettings>,
+        update: F,
+    ) -> Result<bool, ConfigError>
     where
         F: FnOnce(&mut ConfigSettings) -> bool,
     {
         let mut inner = self.inner.write().unwrap();
-        let mut settings = inner
-            .global_config
+        let watched = file(&mut inner);
+        // Start from what's on disk, so saving doesn't drop what another
+        // config sharing the file (like the global one) saved meanwhile, or
+        // overwrite a file that was edited into one that doesn't load
+        let reloaded;
+        (*watched, reloaded) = watched
+            .clone()
+            .try_reload_if_changed(|path| Self::load_settings_from_file(path))?;
+        let mut settings = watched
             .content()
             .cloned()
             .unwrap_or_else(ConfigSettings::default);
 
         if !update(&mut settings) {
-            return false;
+            if reloaded {
+                Self::rebuild_snapshot(&mut inner);
+            }
+            return Ok(false);
         }
 
-        inner.global_config = inner.global_config.clone().with_content_value(settings);
+        *watched = watched.clone().with_content_value(settings);
         Self::rebuild_snapshot(&mut inner);
-        true
+        Ok(true)
+    }
+}
+
+/// The lock held while saving the config file at `path`, by canonical path.
+/// Shared by every config in the process, since roots can share a file.
+fn save_lock(path: &Path) -> Arc<Mutex<()>> {
+    static LOCKS: LazyLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+        LazyLock::new(Default::default);
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    LOCKS.lock().unwrap().entry(path).or_default().clone()
+}
+
+/// The words in a `words_file`, one per line, skipping blank lines and `#`
+/// comments. A file that can't be read adds nothing, with a warning.
+fn read_word_list(path: &Path) -> Vec<String> {
+    match fs::read_to_string(path) {
+        Ok(list) => list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect(),
+        Err(e) => {
+            log::warn!("Failed to read word list {}: {e}", path.display());
+            Vec::new()
+        }
     }
 }
 
+/// Whether a config path is a host file embedding Codebook settings.
+fn is_embedded_config(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| EMBEDDED_CONFIG_FILES.contains(&name))
+}
+
 impl CodebookConfig for CodebookConfigFile {
     /// Add a word to the project configs allowlist
     fn add_word(&self, word: &str) -> bool {
@@ -492,6 +1049,32 @@ impl CodebookConfig for CodebookConfigFile {
         self.update_global_settings(|settings| settings.insert_word(word))
     }
 
+    /// Add words to the project configs allowlist
+    fn add_words(&self, words: &[&str]) -> Result<usize, ConfigError> {
+        let mut added = 0;
+        self.update_settings(
+            |inner| &mut inner.project_config,
+            |settings| {
+                added = settings.insert_words(words);
+                added > 0
+            },
+        )?;
+        Ok(added)
+    }
+
+    /// Add words to the global configs allowlist
+    fn add_words_global(&self, words: &[&str]) -> Result<usize, ConfigError> {
+        let mut added = 0;
+        self.update_settings(
+            |inner| &mut inner.global_config,
+            |settings| {
+                added = settings.insert_words(words);
+                added > 0
+            },
+        )?;
+        Ok(added)
+    }
+
     /// Add a file to the ignore list
     fn add_ignore(&self, file: &str) -> bool {
         self.update_project_settings(|settings| settings.insert_ignore(file))
@@ -508,16 +1091,26 @@ impl CodebookConfig for CodebookConfigFile {
         snapshot.dictionary_ids()
     }
 
+    /// Dictionaries to guess a misspelled word's language from
+    fn detect_languages(&self) -> Vec<String> {
+        self.snapshot().detect_languages.clone()
+    }
+
     /// Check if a path is included based on the effective configuration
     fn should_include_path(&self, path: &Path) -> bool {
         let snapshot = self.snapshot();
-        snapshot.should_include_path(path)
+        snapshot.should_include_path(&self.config_relative_path(path))
     }
 
     /// Check if a path should be ignored based on the effective configuration
+    /// or a `.codebookignore` file
     fn should_ignore_path(&self, path: &Path) -> bool {
-        let snapshot = self.snapshot();
-        snapshot.should_ignore_path(path)
+        let relative = self.config_relative_path(path);
+        self.snapshot().should_ignore_path(&relative)
+            || self
+                .config_dir
+                .as_deref()
+                .is_some_and(|root| self.ignore_files.is_ignored(root, &relative))
     }
 
     /// Check if a word is in the effective allowlist
@@ -538,15 +1131,135 @@ impl CodebookConfig for CodebookConfigFile {
         self.snapshot().ignore_patterns.clone()
     }
 
+    /// Get the patterns for text checked as a single word
+    fn get_keep_together_patterns(&self) -> Vec<Regex> {
+        self.snapshot().keep_together.clone()
+    }
+
     /// Get the minimum word length which should be checked
     fn get_min_word_length(&self) -> usize {
         self.snapshot().min_word_length()
     }
 
+    /// Get the minimum length for words in identifiers
+    fn get_min_word_length_code(&self) -> usize {
+        self.snapshot().min_word_length_code()
+    }
+
+    /// Get the minimum length for words in comments, strings and text
+    fn get_min_word_length_text(&self) -> usize {
+        self.snapshot().min_word_length_text()
+    }
+
+    /// Most edits a suggestion for `word` may be away from it
+    fn max_suggestion_distance(&self, word: &str) -> usize {
+        self.snapshot().max_suggestion_distance(word)
+    }
+
+    /// Whether a dictionary failing to load is a hard error
+    fn strict_dictionaries(&self) -> bool {
+        self.snapshot().strict_dictionaries()
+    }
+
+    /// Whether dictionaries may only come from the cache
+    fn offline(&self) -> bool {
+        self.snapshot().offline()
+    }
+
+    /// Whether files that look binary are skipped
+    fn skip_binary(&self) -> bool {
+        self.snapshot().skip_binary()
+    }
+
+    /// Whether files that look minified are skipped
+    fn skip_minified(&self) -> bool {
+        self.snapshot().skip_minified()
+    }
+
+    /// The first-line marker that skips a file, if any
+    fn disable_file_marker(&self) -> Option<String> {
+        self.snapshot().disable_file_marker().map(str::to_string)
+    }
+
+    /// Whether camelCase words outside identifiers are split
+    fn split_camel_case(&self) -> bool {
+        self.snapshot().split_camel_case()
+    }
+
+    /// Whether hyphenated words are checked whole before their parts
+    fn hyphenated_compounds(&self) -> bool {
+        self.snapshot().hyphenated_compounds()
+    }
+
+    /// Whether defined function and type names are learned as known words
+    fn learn_project_symbols(&self) -> bool {
+        self.snapshot().learn_project_symbols()
+    }
+
+    /// Whether prose suggestions are cased for their place in the sentence
+    fn sentence_case_suggestions(&self) -> bool {
+        self.snapshot().sentence_case_suggestions()
+    }
+
+    /// Whether words mixing look-alike letters from different scripts are
+    /// reported
+    fn flag_confusables(&self) -> bool {
+        self.snapshot().flag_confusables()
+    }
+
     fn should_check_tag(&self, tag: &str) -> bool {
         self.snapshot().should_check_tag(tag)
     }
 
+    fn check_strings(&self, language_ids: &[&str]) -> bool {
+        self.snapshot().check_strings(language_ids)
+    }
+
+    /// Get the language forced for a path by `language_overrides`, if any
+    fn language_override(&self, path: &Path) -> Option<String> {
+        self.snapshot()
+            .language_override(&self.config_relative_path(path))
+            .map(String::from)
+    }
+
+    /// Each entry's paths are relative to the config file that defines it,
+    /// so they are resolved per file rather than on the merged snapshot.
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        let inner = self.inner.read().unwrap();
+        let project = inner.project_config.content();
+        let mut entries: Vec<CustomDictionary> = Vec::new();
+        if project.is_none_or(|p| p.use_global)
+            && let Some(global) = inner.global_config.content()
+        {
+            let global_dir = inner.global_config.path().and_then(Path::parent);
+            entries.extend(
+                global
+                    .custom_dictionaries
+                    .iter()
+                    .map(|d| d.resolved(global_dir)),
+            );
+        }
+        let local = inner.local_config.content();
+        for settings in [project, local].into_iter().flatten() {
+            for entry in &settings.custom_dictionaries {
+                entries.retain(|e| e.name != entry.name);
+                entries.push(entry.resolved(self.config_dir.as_deref()));
+            }
+        }
+        entries
+    }
+
+    fn dictionaries_fingerprint(&self) -> u64 {
+        dictionaries_fingerprint(&self.snapshot(), &self.custom_dictionaries())
+    }
+
+    /// Like custom_dictionaries, resolved against the directory of the
+    /// config file that sets it. The personal setting wins, then the
+    /// project's.
+    fn frequency_list(&self) -> Option<PathBuf> {
+        self.configured_path(|settings| settings.frequency_list.as_deref())
+    }
+
     fn cache_dir(&self) -> &Path {
         &self.cache_dir
     }
@@ -557,6 +1270,8 @@ impl CodebookConfig for CodebookConfigFile {
         if snapshot.overrides.is_empty() {
             return None;
         }
+        let relative_path = self.config_relative_path(relative_path);
+        let relative_path = relative_path.as_ref();
         if !snapshot
             .overrides
             .iter()
@@ -578,16 +1293,20 @@ impl Default for CodebookConfigMemory {
     fn default() -> Self {
         Self {
             settings: RwLock::new(ConfigSettings::default()),
-            cache_dir: helpers::default_cache_dir(),
+            cache_dir: helpers::resolve_cache_dir(None),
         }
     }
 }
 
 impl CodebookConfigMemory {
     pub fn new(settings: ConfigSettings) -> Self {
+        let configured = settings
+            .cache_dir
+            .as_deref()
+            .map(|path| resolve_config_path(path, None));
         Self {
+            cache_dir: helpers::resolve_cache_dir(configured),
             settings: RwLock::new(settings),
-            cache_dir: helpers::default_cache_dir(),
         }
     }
 }
@@ -609,6 +1328,15 @@ impl CodebookConfig for CodebookConfigMemory {
         self.add_word(word)
     }
 
+    fn add_words(&self, words: &[&str]) -> Result<usize, ConfigError> {
+        let mut settings = self.settings.write().unwrap();
+        Ok(settings.insert_words(words))
+    }
+
+    fn add_words_global(&self, words: &[&str]) -> Result<usize, ConfigError> {
+        self.add_words(words)
+    }
+
     fn add_ignore(&self, file: &str) -> bool {
         let mut settings = self.settings.write().unwrap();
         settings.insert_ignore(file)
@@ -624,6 +1352,10 @@ impl CodebookConfig for CodebookConfigMemory {
         snapshot.dictionary_ids()
     }
 
+    fn detect_languages(&self) -> Vec<String> {
+        self.snapshot().detect_languages.clone()
+    }
+
     fn should_include_path(&self, path: &Path) -> bool {
         let snapshot = self.snapshot();
         snapshot.should_include_path(path)
@@ -648,14 +1380,101 @@ impl CodebookConfig for CodebookConfigMemory {
         self.settings.read().unwrap().ignore_patterns.clone()
     }
 
+    fn get_keep_together_patterns(&self) -> Vec<Regex> {
+        self.settings.read().unwrap().keep_together.clone()
+    }
+
     fn get_min_word_length(&self) -> usize {
         self.snapshot().min_word_length()
     }
 
+    fn get_min_word_length_code(&self) -> usize {
+        self.snapshot().min_word_length_code()
+    }
+
+    fn get_min_word_length_text(&self) -> usize {
+        self.snapshot().min_word_length_text()
+    }
+
+    fn max_suggestion_distance(&self, word: &str) -> usize {
+        self.snapshot().max_suggestion_distance(word)
+    }
+
+    fn strict_dictionaries(&self) -> bool {
+        self.snapshot().strict_dictionaries()
+    }
+
+    fn offline(&self) -> bool {
+        self.snapshot().offline()
+    }
+
+    fn skip_binary(&self) -> bool {
+        self.snapshot().skip_binary()
+    }
+
+    fn skip_minified(&self) -> bool {
+        self.snapshot().skip_minified()
+    }
+
+    fn disable_file_marker(&self) -> Option<String> {
+        self.snapshot().disable_file_marker().map(str::to_string)
+    }
+
+    fn split_camel_case(&self) -> bool {
+        self.snapshot().split_camel_case()
+    }
+
+    fn hyphenated_compounds(&self) -> bool {
+        self.snapshot().hyphenated_compounds()
+    }
+
+    fn learn_project_symbols(&self) -> bool {
+        self.snapshot().learn_project_symbols()
+    }
+
+    fn sentence_case_suggestions(&self) -> bool {
+        self.snapshot().sentence_case_suggestions()
+    }
+
+    fn flag_confusables(&self) -> bool {
+        self.snapshot().flag_confusables()
+    }
+
     fn should_check_tag(&self, tag: &str) -> bool {
         self.snapshot().should_check_tag(tag)
     }
 
+    fn check_strings(&self, language_ids: &[&str]) -> bool {
+        self.snapshot().check_strings(language_ids)
+    }
+
+    fn language_override(&self, path: &Path) -> Option<String> {
+        self.settings
+            .read()
+            .unwrap()
+            .language_override(path)
+            .map(String::from)
+    }
+
+    fn custom_dictionaries(&self) -> Vec<CustomDictionary> {
+        self.snapshot()
+            .custom_dictionaries
+            .iter()
+            .map(|d| d.resolved(None))
+            .collect()
+    }
+
+    fn dictionaries_fingerprint(&self) -> u64 {
+        dictionaries_fingerprint(&self.snapshot(), &self.custom_dictionaries())
+    }
+
+    fn frequency_list(&self) -> Option<PathBuf> {
+        self.snapshot()
+            .frequency_list
+            .as_deref()
+            .map(|path| resolve_config_path(path, None))
+    }
+
     fn cache_dir(&self) -> &Path {
         &self.cache_dir
     }
@@ -750,6 +1569,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_add_words() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("codebook.toml");
+        let config = CodebookConfigFile::default();
+        {
+            let mut inner = config.inner.write().unwrap();
+            inner.project_config = WatchedFile::new(Some(config_path.clone()));
+        }
+
+        assert_eq!(config.add_words(&["gamma", "alpha", "gamma"])?, 2);
+        assert_eq!(config.add_words(&["ALPHA"])?, 0);
+        config.save()?;
+
+        let loaded_config = load_from_file(ConfigType::Project, &config_path)?;
+        assert_eq!(loaded_config.snapshot().words, vec!["alpha", "gamma"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_words_keeps_unloadable_file() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("codebook.toml");
+        fs::write(&config_path, "words = [\"alpha\"]\n")?;
+        let config = load_from_file(ConfigType::Project, &config_path)?;
+
+        // Edited into invalid TOML: adding must not overwrite the edit
+        fs::write(&config_path, "words = [\"alpha\", \"beta\"\n")?;
+        assert!(matches!(
+            config.add_words(&["gamma"]),
+            Err(ConfigError::Parse { .. })
+        ));
+        assert!(!config.is_allowed_word("gamma"));
+        assert_eq!(
+            fs::read_to_string(&config_path)?,
+            "words = [\"alpha\", \"beta\"\n"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_add_word_global() -> Result<(), ConfigError> {
         let temp_dir = TempDir::new().unwrap();
@@ -776,6 +1637,49 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_merge_cli_overrides() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("codebook.toml");
+        fs::write(
+            &config_path,
+            "dictionaries = [\"en_us\"]\nwords = [\"projectword\"]\n",
+        )?;
+        let config = load_from_file(ConfigType::Project, &config_path)?;
+
+        config.merge_cli_overrides(ConfigSettings {
+            dictionaries: vec!["en_gb".to_string()],
+            words: vec!["cliword".to_string()],
+            ..Default::default()
+        });
+        config.merge_cli_overrides(ConfigSettings {
+            flag_words: vec!["badword".to_string()],
+            ..Default::default()
+        });
+        assert!(config.is_allowed_word("projectword"));
+        assert!(config.is_allowed_word("cliword"));
+        assert!(config.should_flag_word("badword"));
+        assert_eq!(config.get_dictionary_ids(), vec!["en_gb", "en_us"]);
+
+        // Saving writes only the file's own settings
+        config.add_word("addedword");
+        config.save()?;
+        let saved = fs::read_to_string(&config_path)?;
+        assert!(saved.contains("addedword"));
+        assert!(!saved.contains("cliword"));
+        assert!(!saved.contains("en_gb"));
+
+        // Overrides still apply after the file changes underneath
+        fs::write(&config_path, "words = [\"otherword\"]\n")?;
+        config.reload();
+        assert!(config.is_allowed_word("otherword"));
+        assert!(!config.is_allowed_word("projectword"));
+        assert!(config.is_allowed_word("cliword"));
+        assert_eq!(config.get_dictionary_ids(), vec!["en_gb"]);
+
+        Ok(())
+    }
+
     #[test]
     fn test_ignore_patterns() -> Result<(), ConfigError> {
         let temp_dir = TempDir::new().unwrap();
@@ -873,36 +1777,149 @@ mod tests {
         let config = CodebookConfigFile::load_configs(&sub_sub_dir, None, None)?;
         assert!(config.snapshot().words.contains(&"testword".to_string()));
 
-        // Check that the config file path is stored (canonicalized, since
-        // load_configs resolves symlinks like macOS's /var -> /private/var)
-        assert_eq!(
-            config.project_config_path(),
-            Some(config_path.canonicalize()?)
-        );
+        // Check that the config file path is stored (canonicalized, since
+        // load_configs resolves symlinks like macOS's /var -> /private/var)
+        assert_eq!(
+            config.project_config_path(),
+            Some(config_path.canonicalize()?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_recursive_search_from_relative_dir() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let sub_dir = temp_dir.path().join("sub");
+        fs::create_dir_all(&sub_dir)?;
+
+        let config_path = temp_dir.path().join("codebook.toml");
+        fs::write(&config_path, r#"words = ["parentword"]"#)?;
+
+        // Search up from "." like the CLI does when no --root is given.
+        let original_dir = env::current_dir()?;
+        env::set_current_dir(&sub_dir)?;
+        let result = CodebookConfigFile::load_configs(Path::new("."), None, None);
+        env::set_current_dir(original_dir)?;
+
+        let config = result?;
+        assert!(config.is_allowed_word("parentword"));
+        assert_eq!(
+            config.project_config_path(),
+            Some(temp_dir.path().canonicalize()?.join("codebook.toml"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_from_pyproject_tool_table() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let sub_dir = temp_dir.path().join("sub");
+        fs::create_dir_all(&sub_dir)?;
+        let pyproject = temp_dir.path().join("pyproject.toml");
+        fs::write(
+            &pyproject,
+            r#"
+            [project]
+            name = "demo"
+
+            [tool.codebook]
+            words = ["pyword"]
+            "#,
+        )?;
+
+        let config = CodebookConfigFile::load_configs(&sub_dir, None, None)?;
+        assert!(config.is_allowed_word("pyword"));
+        assert_eq!(
+            config.project_config_path(),
+            Some(pyproject.canonicalize()?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_from_package_json_key() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "demo", "codebook": {"words": ["jsword"], "min_word_length": 4}}"#,
+        )?;
+
+        let config = CodebookConfigFile::load_configs(temp_dir.path(), None, None)?;
+        assert!(config.is_allowed_word("jsword"));
+        assert_eq!(config.get_min_word_length(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_host_file_without_codebook_section_is_skipped() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let sub_dir = temp_dir.path().join("sub");
+        fs::create_dir_all(&sub_dir)?;
+        fs::write(
+            temp_dir.path().join("codebook.toml"),
+            r#"words = ["rootword"]"#,
+        )?;
+        // Neither host file configures Codebook, so the search continues up
+        fs::write(
+            sub_dir.join("pyproject.toml"),
+            "[tool.black]\nline-length = 88\n",
+        )?;
+        fs::write(sub_dir.join("package.json"), r#"{"name": "demo"}"#)?;
+
+        let config = CodebookConfigFile::load_configs(&sub_dir, None, None)?;
+        assert!(config.is_allowed_word("rootword"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedicated_config_wins_over_embedded() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("codebook.toml"),
+            r#"words = ["tomlword"]"#,
+        )?;
+        fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            "[tool.codebook]\nwords = [\"pyword\"]\n",
+        )?;
+
+        let config = CodebookConfigFile::load_configs(temp_dir.path(), None, None)?;
+        assert!(config.is_allowed_word("tomlword"));
+        assert!(!config.is_allowed_word("pyword"));
         Ok(())
     }
 
     #[test]
-    fn test_config_recursive_search_from_relative_dir() -> Result<(), ConfigError> {
+    fn test_invalid_embedded_section_fails_load() {
         let temp_dir = TempDir::new().unwrap();
-        let sub_dir = temp_dir.path().join("sub");
-        fs::create_dir_all(&sub_dir)?;
+        fs::write(
+            temp_dir.path().join("package.json"),
+            r#"{"codebook": {"ignore_patterns": ["[invalid"]}}"#,
+        )
+        .unwrap();
 
-        let config_path = temp_dir.path().join("codebook.toml");
-        fs::write(&config_path, r#"words = ["parentword"]"#)?;
+        let err = CodebookConfigFile::load_configs(temp_dir.path(), None, None).unwrap_err();
+        assert!(matches!(err, ConfigError::ParseJson { .. }), "{err}");
+    }
 
-        // Search up from "." like the CLI does when no --root is given.
-        let original_dir = env::current_dir()?;
-        env::set_current_dir(&sub_dir)?;
-        let result = CodebookConfigFile::load_configs(Path::new("."), None, None);
-        env::set_current_dir(original_dir)?;
+    #[test]
+    fn test_save_moves_embedded_config_to_codebook_toml() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let pyproject = temp_dir.path().join("pyproject.toml");
+        let original = "# keep me\n[tool.codebook]\nwords = [\"pyword\"]\n";
+        fs::write(&pyproject, original)?;
 
-        let config = result?;
-        assert!(config.is_allowed_word("parentword"));
-        assert_eq!(
-            config.project_config_path(),
-            Some(temp_dir.path().canonicalize()?.join("codebook.toml"))
-        );
+        let config = CodebookConfigFile::load_configs(temp_dir.path(), None, None)?;
+        assert!(config.add_word("newword"));
+        config.save()?;
+
+        // The host file is untouched; the dedicated file holds everything
+        assert_eq!(fs::read_to_string(&pyproject)?, original);
+        let dedicated = temp_dir.path().canonicalize()?.join("codebook.toml");
+        assert_eq!(config.project_config_path(), Some(dedicated.clone()));
+        let written = fs::read_to_string(&dedicated)?;
+        assert!(written.contains("pyword"));
+        assert!(written.contains("newword"));
         Ok(())
     }
 
@@ -933,6 +1950,37 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_default_dictionary_resolution_order() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace_dir = temp_dir.path().join("workspace");
+        fs::create_dir_all(&workspace_dir)?;
+        let global_path = temp_dir.path().join("global.toml");
+        let load = || {
+            CodebookConfigFile::load_with_overrides(
+                Some(workspace_dir.as_path()),
+                Some(global_path.clone()),
+                None,
+            )
+        };
+
+        // Nothing set anywhere: en_us
+        assert_eq!(load()?.get_dictionary_ids(), vec!["en_us"]);
+
+        // The global default applies when the project names no dictionaries
+        fs::write(&global_path, "default_dictionary = \"en_gb\"\n")?;
+        fs::write(workspace_dir.join("codebook.toml"), "words = [\"x\"]\n")?;
+        assert_eq!(load()?.get_dictionary_ids(), vec!["en_gb"]);
+
+        // Project dictionaries win
+        fs::write(
+            workspace_dir.join("codebook.toml"),
+            "dictionaries = [\"de\"]\n",
+        )?;
+        assert_eq!(load()?.get_dictionary_ids(), vec!["de"]);
+        Ok(())
+    }
+
     #[test]
     fn test_project_config_override_is_used() -> Result<(), ConfigError> {
         let temp_dir = TempDir::new().unwrap();
@@ -1057,6 +2105,240 @@ mod tests {
         assert!(!config.should_include_path("README.md".as_ref()));
     }
 
+    #[test]
+    fn test_ignore_paths_relative_to_parent_config_dir() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().join("app");
+        fs::create_dir_all(&workspace)?;
+        fs::write(
+            temp_dir.path().join("codebook.toml"),
+            r#"ignore_paths = ["app/generated/**", "docs/**"]"#,
+        )?;
+
+        // Opened below the config: workspace-relative paths are rebased
+        // onto the config's directory before matching.
+        let config = CodebookConfigFile::load_configs(&workspace, None, None)?;
+        assert!(config.should_ignore_path(Path::new("generated/api.rs")));
+        assert!(!config.should_ignore_path(Path::new("src/main.rs")));
+        // "docs/**" means <config dir>/docs, not <workspace>/docs
+        assert!(!config.should_ignore_path(Path::new("docs/guide.md")));
+        assert!(config.should_ignore_path(&temp_dir.path().join("docs/guide.md")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_dictionaries_relative_to_defining_config() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let workspace = root.join("app");
+        let global_dir = root.join("global");
+        fs::create_dir_all(&workspace)?;
+        fs::create_dir_all(&global_dir)?;
+        fs::write(
+            global_dir.join("codebook.toml"),
+            r#"
+            [[custom_dictionaries]]
+            name = "shared"
+            aff_path = "shared.aff"
+            dic_path = "~/dicts/shared.dic"
+
+            [[custom_dictionaries]]
+            name = "team"
+            aff_path = "old.aff"
+            dic_path = "old.dic"
+            "#,
+        )?;
+        fs::write(
+            root.join("codebook.toml"),
+            r#"
+            [[custom_dictionaries]]
+            name = "team"
+            aff_path = "dicts/team.aff"
+            dic_path = "dicts/team.dic"
+            "#,
+        )?;
+
+        let config = CodebookConfigFile::load_with_overrides(
+            Some(&workspace),
+            Some(global_dir.join("codebook.toml")),
+            None,
+        )?;
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(
+            config.custom_dictionaries(),
+            vec![
+                CustomDictionary {
+                    name: "shared".to_string(),
+                    aff_path: global_dir.join("shared.aff"),
+                    dic_path: home.join("dicts/shared.dic"),
+                },
+                // The project entry replaces the global one of the same name
+                CustomDictionary {
+                    name: "team".to_string(),
+                    aff_path: root.join("dicts/team.aff"),
+                    dic_path: root.join("dicts/team.dic"),
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_frequency_list_relative_to_defining_config() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let global_dir = root.join("global");
+        fs::create_dir_all(&global_dir)?;
+        fs::write(
+            global_dir.join("codebook.toml"),
+            r#"frequency_list = "freq.tsv""#,
+        )?;
+        let load = || {
+            CodebookConfigFile::load_with_overrides(
+                Some(&root),
+                Some(global_dir.join("codebook.toml")),
+                None,
+            )
+        };
+
+        // Only the global config sets it
+        fs::write(root.join("codebook.toml"), "")?;
+        assert_eq!(load()?.frequency_list(), Some(global_dir.join("freq.tsv")));
+
+        // The project's setting wins
+        fs::write(
+            root.join("codebook.toml"),
+            r#"frequency_list = "data/words.tsv""#,
+        )?;
+        assert_eq!(load()?.frequency_list(), Some(root.join("data/words.tsv")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_dir_relative_to_defining_config() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let sub_dir = root.join("sub");
+        fs::create_dir_all(&sub_dir)?;
+        fs::write(
+            root.join("codebook.toml"),
+            "cache_dir = \".cache/codebook\"\n",
+        )?;
+        let global_path = root.join("global").join("codebook.toml");
+
+        let config =
+            CodebookConfigFile::load_with_overrides(Some(&sub_dir), Some(global_path), None)?;
+        let expected = helpers::resolve_cache_dir(Some(root.join(".cache/codebook")));
+        assert_eq!(config.cache_dir(), expected);
+
+        let memory = CodebookConfigMemory::new(ConfigSettings {
+            cache_dir: Some(root.join("memory-cache")),
+            ..Default::default()
+        });
+        let expected = helpers::resolve_cache_dir(Some(root.join("memory-cache")));
+        assert_eq!(memory.cache_dir(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_codebookignore_negation_and_directories() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        fs::create_dir_all(root.join("generated"))?;
+        fs::create_dir_all(root.join("src/build"))?;
+        fs::write(root.join("codebook.toml"), r#"ignore_paths = ["*.lock"]"#)?;
+        fs::write(
+            root.join(".codebookignore"),
+            "# Generated code\ngenerated/\n*.min.js\n!keep.min.js\nbuild/\n",
+        )?;
+
+        let config = CodebookConfigFile::load_with_overrides(
+            Some(&root),
+            Some(root.join("global.toml")),
+            None,
+        )?;
+        assert!(config.should_ignore_path(Path::new("generated/api.rs")));
+        assert!(config.should_ignore_path(Path::new("src/app.min.js")));
+        assert!(!config.should_ignore_path(Path::new("src/keep.min.js")));
+        // Directory-only pattern: matches the directory at any depth
+        assert!(config.should_ignore_path(Path::new("src/build/out.rs")));
+        assert!(!config.should_ignore_path(Path::new("src/main.rs")));
+        // Merged with ignore_paths from the TOML config
+        assert!(config.should_ignore_path(Path::new("Cargo.lock")));
+
+        // Edits apply without a config reload
+        fs::write(root.join(".codebookignore"), "src/\n")?;
+        assert!(config.should_ignore_path(Path::new("src/main.rs")));
+        assert!(!config.should_ignore_path(Path::new("generated/api.rs")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_codebookignore_takes_precedence() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        fs::create_dir_all(root.join("docs/internal"))?;
+        fs::write(root.join(".codebookignore"), "*.md\n")?;
+        fs::write(root.join("docs/.codebookignore"), "!*.md\ninternal/\n")?;
+
+        let config = CodebookConfigFile::load_with_overrides(
+            Some(&root),
+            Some(root.join("global.toml")),
+            None,
+        )?;
+        assert!(config.should_ignore_path(Path::new("README.md")));
+        // docs/.codebookignore re-includes its markdown files
+        assert!(!config.should_ignore_path(Path::new("docs/guide.md")));
+        // ... and its patterns are relative to docs/
+        assert!(config.should_ignore_path(Path::new("docs/internal/notes.txt")));
+        assert!(!config.should_ignore_path(Path::new("internal/notes.txt")));
+        // Absolute paths under the project work too
+        assert!(config.should_ignore_path(&root.join("CHANGES.md")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_paths_same_from_any_start_dir() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+        fs::create_dir_all(&nested)?;
+        fs::write(
+            temp_dir.path().join("codebook.toml"),
+            r#"ignore_paths = ["a/b/*.snap"]"#,
+        )?;
+        let file = nested.join("out.snap");
+        fs::write(&file, "")?;
+
+        for start in [temp_dir.path().to_path_buf(), nested.clone()] {
+            let config = CodebookConfigFile::load_configs(&start, None, None)?;
+            assert!(config.should_ignore_path(&file), "from {}", start.display());
+        }
+        let from_root = CodebookConfigFile::load_configs(temp_dir.path(), None, None)?;
+        assert!(from_root.should_ignore_path(Path::new("a/b/out.snap")));
+        let from_nested = CodebookConfigFile::load_configs(&nested, None, None)?;
+        assert!(from_nested.should_ignore_path(Path::new("out.snap")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignore_paths_relative_to_override_config_dir() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let workspace = temp_dir.path().join("workspace");
+        let config_dir = temp_dir.path().join("configs");
+        fs::create_dir_all(&workspace)?;
+        fs::create_dir_all(&config_dir)?;
+        let config_path = config_dir.join("codebook.toml");
+        fs::write(&config_path, r#"ignore_paths = ["vendor/**"]"#)?;
+
+        let config = CodebookConfigFile::load_configs(&workspace, None, Some(config_path))?;
+        // The workspace is outside the config's directory, so its relative
+        // paths are matched as given.
+        assert!(config.should_ignore_path(Path::new("vendor/lib.rs")));
+        assert!(config.should_ignore_path(&config_dir.join("vendor/lib.rs")));
+        assert!(!config.should_ignore_path(Path::new("src/lib.rs")));
+        Ok(())
+    }
+
     #[test]
     fn test_reload() -> Result<(), ConfigError> {
         let temp_dir = TempDir::new().unwrap();
@@ -1087,6 +2369,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_dictionaries_fingerprint() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("codebook.toml");
+        fs::write(&config_path, "dictionaries = [\"en_us\"]\n")?;
+        let config = load_from_file(ConfigType::Project, &config_path)?;
+        let initial = config.dictionaries_fingerprint();
+        assert_eq!(config.dictionaries_fingerprint(), initial);
+
+        config.add_word("testword");
+        let with_word = config.dictionaries_fingerprint();
+        assert_ne!(with_word, initial);
+
+        // Same for changes picked up on reload
+        fs::write(&config_path, "dictionaries = [\"en_us\", \"de\"]\n")?;
+        config.reload();
+        assert_ne!(config.dictionaries_fingerprint(), with_word);
+
+        let memory = CodebookConfigMemory::default();
+        let initial = memory.dictionaries_fingerprint();
+        memory.add_word("testword");
+        assert_ne!(memory.dictionaries_fingerprint(), initial);
+        Ok(())
+    }
+
     #[test]
     fn test_reload_when_deleted() -> Result<(), ConfigError> {
         let temp_dir = TempDir::new().unwrap();
@@ -1179,6 +2486,264 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_local_config_merged_over_project_and_global() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let global_path = root.join("global").join("codebook.toml");
+        fs::create_dir_all(global_path.parent().unwrap())?;
+        fs::write(
+            &global_path,
+            "words = [\"globalword\"]\nmin_word_length = 2\nskip_binary = false\n",
+        )?;
+        fs::write(
+            root.join("codebook.toml"),
+            "words = [\"projectword\"]\nmin_word_length = 3\noffline = true\n",
+        )?;
+        fs::write(
+            root.join(LOCAL_CONFIG_FILE),
+            "words = [\"localword\"]\nmin_word_length = 4\n",
+        )?;
+
+        let config = CodebookConfigFile::load_with_overrides(Some(root), Some(global_path), None)?;
+        // Word lists from all three are combined
+        for word in ["globalword", "projectword", "localword"] {
+            assert!(config.is_allowed_word(word), "{word}");
+        }
+        // Personal beats project beats global; unset keys fall through
+        assert_eq!(config.get_min_word_length(), 4);
+        assert!(config.offline());
+        assert!(!config.skip_binary());
+
+        // Applies even when the project opts out of the global config
+        fs::write(root.join("codebook.toml"), "use_global = false\n")?;
+        config.reload();
+        assert!(!config.is_allowed_word("globalword"));
+        assert!(config.is_allowed_word("localword"));
+        assert_eq!(config.get_min_word_length(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_words_local() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let project_path = root.join("codebook.toml");
+        fs::write(&project_path, "words = [\"projectword\"]\n")?;
+        let global_path = root.join("global").join("codebook.toml");
+
+        let config = CodebookConfigFile::load_with_overrides(Some(&root), Some(global_path), None)?;
+        let local_path = root.join(LOCAL_CONFIG_FILE);
+        assert_eq!(config.local_config_path(), Some(local_path.clone()));
+
+        assert_eq!(config.add_words_local(&["myword", "projectword"])?, 2);
+        assert_eq!(config.add_words_local(&["myword"])?, 0);
+        assert!(config.is_allowed_word("myword"));
+        config.save_local()?;
+
+        // Only the personal file got the words
+        let project = fs::read_to_string(&project_path)?;
+        assert!(!project.contains("myword"));
+        let local = CodebookConfigFile::load_settings_from_file(&local_path)?;
+        assert_eq!(local.words, vec!["myword", "projectword"]);
+        // ...and saving didn't look like an external change
+        assert!(!config.reload());
+        Ok(())
+    }
+
+    #[test]
+    fn test_effective_toml() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let global_path = root.join("global.toml");
+        fs::write(
+            &global_path,
+            "words = [\"globalword\"]\nmin_word_length = 2\n",
+        )?;
+        let project_path = root.join("codebook.toml");
+        fs::write(
+            &project_path,
+            "words = [\"projectword\"]\nmin_word_length = 4\n\n[[overrides]]\npaths = [\"docs/**\"]\nwords = [\"docsword\"]\n",
+        )?;
+        let config =
+            CodebookConfigFile::load_with_overrides(Some(&root), Some(global_path.clone()), None)?;
+        let shown = config.effective_toml()?;
+        let global = format!("# from global config {}\n", global_path.display());
+        let project = format!("# from project config {}\n", project_path.display());
+        assert!(
+            shown.contains(&format!(
+                "{global}{project}min_word_length = 4\n\n{global}{project}words = [\n"
+            )),
+            "{shown}"
+        );
+        assert!(
+            shown.contains(&format!("{project}[[overrides]]")),
+            "{shown}"
+        );
+        // The output is a config that loads to the same settings
+        let reparsed: ConfigSettings = toml::from_str(&shown).unwrap();
+        assert_eq!(reparsed.words, vec!["globalword", "projectword"]);
+        assert_eq!(reparsed.overrides.len(), 1);
+
+        let empty = CodebookConfigFile::load_with_overrides(
+            Some(&root),
+            Some(root.join("none.toml")),
+            Some(root.join("missing.toml")),
+        )?;
+        assert!(empty.effective_toml()?.starts_with("# Nothing set"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_words_file() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let global_dir = root.join("global");
+        fs::create_dir_all(&global_dir)?;
+        fs::write(global_dir.join("mine.txt"), "globalword\n")?;
+        let global_path = global_dir.join("codebook.toml");
+        fs::write(&global_path, "words_file = \"mine.txt\"\n")?;
+        fs::create_dir_all(root.join("docs"))?;
+        fs::write(
+            root.join("docs").join("glossary.txt"),
+            "# Project terms\nFrobnicate\n\n  combobulate  \n",
+        )?;
+        fs::write(root.join("banned.txt"), "actualbad\n")?;
+        let project_path = root.join("codebook.toml");
+        fs::write(
+            &project_path,
+            r#"
+words = ["inline"]
+words_file = "docs/glossary.txt"
+flag_words_file = "banned.txt"
+"#,
+        )?;
+
+        let config = CodebookConfigFile::load_with_overrides(Some(&root), Some(global_path), None)?;
+        for word in ["inline", "frobnicate", "combobulate", "globalword"] {
+            assert!(config.is_allowed_word(word), "{word}");
+        }
+        assert!(!config.is_allowed_word("# Project terms"));
+        assert!(config.should_flag_word("actualbad"));
+
+        // Saving writes the config's own words, not the listed ones
+        config.add_word("added");
+        config.save()?;
+        let saved = CodebookConfigFile::load_settings_from_file(&project_path)?;
+        assert_eq!(saved.words, vec!["added", "inline"]);
+        assert!(config.is_allowed_word("frobnicate"));
+
+        // Editing a list alone is picked up too
+        fs::write(
+            root.join("docs").join("glossary.txt"),
+            "Frobnicate\ncombobulate\nnewterm\n",
+        )?;
+        assert!(config.reload());
+        assert!(config.is_allowed_word("newterm"));
+        assert!(!config.reload(), "nothing changed on disk");
+
+        // Lists are re-read with the config, and follow use_global
+        fs::write(
+            &project_path,
+            "use_global = false\nwords_file = \"banned.txt\"\n",
+        )?;
+        assert!(config.reload());
+        assert!(config.is_allowed_word("actualbad"));
+        assert!(!config.is_allowed_word("frobnicate"));
+        assert!(!config.is_allowed_word("globalword"));
+        assert!(!config.should_flag_word("actualbad"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_add_word_and_save() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let project_path = root.join("codebook.toml");
+        fs::write(&project_path, "")?;
+        let global_path = root.join("global").join("codebook.toml");
+        let config = Arc::new(CodebookConfigFile::load_with_overrides(
+            Some(&root),
+            Some(global_path),
+            None,
+        )?);
+
+        let threads: Vec<_> = (0..8)
+            .map(|t| {
+                let config = config.clone();
+                let project_path = project_path.clone();
+                std::thread::spawn(move || {
+                    for i in 0..25 {
+                        config.add_word(&format!("word{t}x{i}"));
+                        config.save().unwrap();
+                        // Every save leaves a complete, parseable file
+                        CodebookConfigFile::load_settings_from_file(&project_path).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let saved = CodebookConfigFile::load_settings_from_file(&project_path)?;
+        assert_eq!(saved.words.len(), 8 * 25);
+        assert!(!config.reload());
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&root)?.count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_roots_sharing_global_config_save() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize()?;
+        let global_dir = root.join("global");
+        let global_path = global_dir.join("codebook.toml");
+        let configs: Vec<Arc<CodebookConfigFile>> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                let dir = root.join(name);
+                fs::create_dir_all(&dir)?;
+                Ok(Arc::new(CodebookConfigFile::load_with_overrides(
+                    Some(&dir),
+                    Some(global_path.clone()),
+                    None,
+                )?))
+            })
+            .collect::<Result<_, ConfigError>>()?;
+
+        // A save by one root isn't dropped by the other's next save
+        configs[0].add_words_global(&["alpha"])?;
+        configs[0].save_global()?;
+        configs[1].add_words_global(&["beta"])?;
+        configs[1].save_global()?;
+        let saved = CodebookConfigFile::load_settings_from_file(&global_path)?;
+        assert_eq!(saved.words, vec!["alpha", "beta"]);
+
+        // Concurrent saves don't trip over each other's temporary files
+        let threads: Vec<_> = configs
+            .iter()
+            .enumerate()
+            .map(|(t, config)| {
+                let config = config.clone();
+                let global_path = global_path.clone();
+                std::thread::spawn(move || {
+                    for i in 0..25 {
+                        config.add_words_global(&[&format!("word{t}x{i}")]).unwrap();
+                        config.save_global().unwrap();
+                        CodebookConfigFile::load_settings_from_file(&global_path).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(fs::read_dir(&global_dir)?.count(), 1);
+        Ok(())
+    }
+
     #[test]
     fn test_global_and_project_config() -> Result<(), ConfigError> {
         // Create temporary directories for global and project configs
@@ -1648,4 +3213,75 @@ mod tests {
         assert!(config.is_allowed_word("zebra"));
         Ok(())
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_keeps_symlink_and_permissions() -> Result<(), ConfigError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let shared = temp_dir.path().join("shared.toml");
+        fs::write(&shared, r#"words = ["zebra"]"#)?;
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o640))?;
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project)?;
+        let config_path = project.join("codebook.toml");
+        std::os::unix::fs::symlink(&shared, &config_path)?;
+
+        let config = CodebookConfigFile::load_with_overrides(
+            Some(&project),
+            Some(temp_dir.path().join("global.toml")),
+            None,
+        )?;
+        config.add_word("frobnicate");
+        config.save()?;
+
+        assert!(fs::symlink_metadata(&config_path)?.file_type().is_symlink());
+        assert!(fs::read_to_string(&shared)?.contains("frobnicate"));
+        assert_eq!(fs::metadata(&shared)?.permissions().mode() & 0o777, 0o640);
+        assert!(!config.reload());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_with_same_settings_is_not_a_change() -> Result<(), ConfigError> {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("codebook.toml");
+        fs::write(
+            &config_path,
+            r#"words = ["zebra"]
+ignore_patterns = ["^[A-Z]+$"]"#,
+        )?;
+
+        let config = CodebookConfigFile::load_with_overrides(
+            Some(temp_dir.path()),
+            Some(temp_dir.path().join("global.toml")),
+            None,
+        )?;
+        let before = config.snapshot();
+        assert!(!config.reload(), "nothing changed on disk");
+
+        // An editor save that only reformats the file
+        fs::write(
+            &config_path,
+            r#"# Project words
+ignore_patterns = [ "^[A-Z]+$" ]
+words = [ "zebra" ]
+"#,
+        )?;
+        assert!(
+            !config.reload(),
+            "same settings should not count as a change"
+        );
+        assert!(Arc::ptr_eq(&before, &config.snapshot()));
+
+        fs::write(
+            &config_path,
+            r#"words = ["zebra"]
+ignore_patterns = ["^[a-z]+$"]"#,
+        )?;
+        assert!(config.reload(), "a changed pattern is a change");
+        assert!(!Arc::ptr_eq(&before, &config.snapshot()));
+        Ok(())
+    }
 }
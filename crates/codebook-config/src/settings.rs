@@ -1,8 +1,10 @@
 use glob::Pattern;
 use log::warn;
 use regex::{Regex, RegexBuilder};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Compile a user-supplied ignore pattern. Multiline mode is enabled so `^`
 /// and `$` match line boundaries.
@@ -91,7 +93,7 @@ where
 ///
 /// Word-related fields deserialize through `lowercase_*` so lookups are
 /// case-insensitive; paths and regex patterns keep their original casing.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct OverrideBlock {
     /// Required: glob patterns matched against file path relative to project
     /// root, compiled at parse time. Defaulted rather than required so a
@@ -102,6 +104,7 @@ pub struct OverrideBlock {
         deserialize_with = "glob_vec",
         serialize_with = "globs_as_strings"
     )]
+    #[schemars(with = "Vec<String>")]
     pub paths: Vec<Pattern>,
 
     // --- Replace fields (replace the base list entirely) ---
@@ -132,6 +135,7 @@ pub struct OverrideBlock {
         serialize_with = "opt_regexes_as_strings",
         skip_serializing_if = "Option::is_none"
     )]
+    #[schemars(with = "Option<Vec<String>>")]
     pub ignore_patterns: Option<Vec<Regex>>,
 
     // --- Append fields (append to the resolved list) ---
@@ -162,6 +166,7 @@ pub struct OverrideBlock {
         serialize_with = "opt_regexes_as_strings",
         skip_serializing_if = "Option::is_none"
     )]
+    #[schemars(with = "Option<Vec<String>>")]
     pub extra_ignore_patterns: Option<Vec<Regex>>,
 }
 
@@ -173,6 +178,26 @@ where
     Ok(v.into_iter().map(|s| s.to_ascii_lowercase()).collect())
 }
 
+fn lowercase_keys<'de, D, V>(deserializer: D) -> Result<BTreeMap<String, V>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let map = BTreeMap::<String, V>::deserialize(deserializer)?;
+    Ok(map
+        .into_iter()
+        .map(|(k, v)| (k.to_ascii_lowercase(), v))
+        .collect())
+}
+
+fn lowercase_opt<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let v = Option::<String>::deserialize(deserializer)?;
+    Ok(v.map(|s| s.to_ascii_lowercase()))
+}
+
 fn lowercase_opt_vec<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -229,7 +254,87 @@ impl OverrideBlock {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// A coarse kind of text for `check_scope`, covering a family of capture
+/// tags. Simpler than listing tags when a team only cares about, say, prose
+/// in comments.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckScope {
+    /// `comment` tags, including doc comments
+    Comments,
+    /// `string` tags
+    Strings,
+    /// `identifier` tags: names of functions, types, variables and so on
+    Identifiers,
+    /// Every tag
+    All,
+}
+
+impl CheckScope {
+    /// Whether this scope covers the capture tag.
+    pub fn covers_tag(self, tag: &str) -> bool {
+        match self {
+            CheckScope::Comments => tag_matches_pattern(tag, "comment"),
+            CheckScope::Strings => tag_matches_pattern(tag, "string"),
+            CheckScope::Identifiers => tag_matches_pattern(tag, "identifier"),
+            CheckScope::All => true,
+        }
+    }
+}
+
+/// Settings for one language, under `[languages.<id>]` where the ID is a
+/// language ID like `python` or `typescriptreact`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub struct LanguageSettings {
+    /// Check string literals (`string.*` captures). Unset means true.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_strings: Option<bool>,
+}
+
+impl LanguageSettings {
+    /// Take each setting the other (project) config sets explicitly.
+    fn merge(&mut self, other: LanguageSettings) {
+        if other.check_strings.is_some() {
+            self.check_strings = other.check_strings;
+        }
+    }
+}
+
+/// A Hunspell dictionary stored on disk, such as one checked into the repo.
+/// Paths are relative to the config file that defines the entry, and may
+/// start with `~`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, JsonSchema)]
+pub struct CustomDictionary {
+    /// Name used in logs and load errors
+    pub name: String,
+    pub aff_path: PathBuf,
+    pub dic_path: PathBuf,
+}
+
+impl CustomDictionary {
+    /// This entry with `~` expanded and relative paths joined onto
+    /// `base_dir`, the directory of the config file that defines it.
+    pub(crate) fn resolved(&self, base_dir: Option<&Path>) -> Self {
+        let resolve = |path: &Path| resolve_config_path(path, base_dir);
+        Self {
+            name: self.name.clone(),
+            aff_path: resolve(&self.aff_path),
+            dic_path: resolve(&self.dic_path),
+        }
+    }
+}
+
+/// `path` with `~` and environment variables expanded and, if relative, joined onto `base_dir`, the
+/// directory of the config file that names it.
+pub(crate) fn resolve_config_path(path: &Path, base_dir: Option<&Path>) -> PathBuf {
+    let path = crate::helpers::expand_env_and_tilde(path).unwrap_or_else(|| path.to_path_buf());
+    match base_dir {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ConfigSettings {
     /// List of dictionaries to use for spell checking.
     /// Dictionary IDs are language codes (e.g. "en_US") — normalized to
@@ -242,6 +347,26 @@ pub struct ConfigSettings {
     )]
     pub dictionaries: Vec<String>,
 
+    /// Dictionary used when no config sets `dictionaries`, in place of
+    /// `en_us`. Meant for the global config, so every project picks up the
+    /// user's locale. None means "not set", which uses `en_us`.
+    #[serde(
+        default,
+        deserialize_with = "lowercase_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(extend("default" = "en_us"))]
+    pub default_dictionary: Option<String>,
+
+    /// Dictionaries a misspelled word is also looked up in, to suggest the
+    /// language it may be written in. Empty (the default) skips the lookups.
+    #[serde(
+        default,
+        deserialize_with = "lowercase_vec",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub detect_languages: Vec<String>,
+
     /// Custom allowlist of words
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub words: Vec<String>,
@@ -250,6 +375,22 @@ pub struct ConfigSettings {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub flag_words: Vec<String>,
 
+    /// Contractions to allow, like `y'all`. Only words with an apostrophe
+    /// are looked up here, and straight and curly apostrophes match each
+    /// other.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_contractions: Vec<String>,
+
+    /// A file of more `words`, one per line, read when the config loads.
+    /// Blank lines and `#` comments are skipped. Relative paths are
+    /// relative to the defining config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words_file: Option<PathBuf>,
+
+    /// Like `words_file`, for `flag_words`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag_words_file: Option<PathBuf>,
+
     /// Glob patterns for paths to include
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub include_paths: Vec<String>,
@@ -265,21 +406,113 @@ pub struct ConfigSettings {
         serialize_with = "regexes_as_strings",
         skip_serializing_if = "Vec::is_empty"
     )]
+    #[schemars(with = "Vec<String>")]
     pub ignore_patterns: Vec<Regex>,
 
+    /// Regex patterns for text checked as one word (`C\+\+`, `\.NET`)
+    /// instead of being split into parts, compiled at parse time
+    #[serde(
+        default,
+        deserialize_with = "regex_vec",
+        serialize_with = "regexes_as_strings",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    #[schemars(with = "Vec<String>")]
+    pub keep_together: Vec<Regex>,
+
     /// Whether to use global configuration
     #[serde(
         default = "default_use_global",
         skip_serializing_if = "is_default_use_global"
     )]
+    #[schemars(extend("default" = true))]
     pub use_global: bool,
 
     /// Minimum word length to check (words shorter than this are ignored).
     /// None means "not set" so merging can tell an explicit value — even one
     /// equal to the default — apart from an omitted one.
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = 3))]
     pub min_word_length: Option<usize>,
 
+    /// Treat a dictionary that fails to load as a hard error instead of a
+    /// warning. None means "not set", like min_word_length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = false))]
+    pub strict_dictionaries: Option<bool>,
+
+    /// Never download dictionaries; only use ones already cached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = false))]
+    pub offline: Option<bool>,
+
+    /// Skip files that look binary (a NUL byte near the start).
+    /// None means "not set", which skips them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = true))]
+    pub skip_binary: Option<bool>,
+
+    /// Skip files that look minified (very long average line length).
+    /// None means "not set", which skips them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = true))]
+    pub skip_minified: Option<bool>,
+
+    /// Skip files whose first non-empty line contains this marker, such as
+    /// `// codebook: off`. None means "not set", which uses `codebook: off`;
+    /// an empty string turns the check off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = "codebook: off"))]
+    pub disable_file_marker: Option<String>,
+
+    /// Split camelCase words like `macOS` in comments, strings and prose.
+    /// Identifiers are always split. None means "not set", which splits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = true))]
+    pub split_camel_case: Option<bool>,
+
+    /// Check hyphenated words like `e-mail` whole first, and only check
+    /// their parts when the whole isn't known. None means "not set", which
+    /// only checks the parts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = false))]
+    pub hyphenated_compounds: Option<bool>,
+
+    /// Treat the words of function and type names defined in checked files
+    /// as known everywhere else. None means "not set", which is off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = false))]
+    pub learn_project_symbols: Option<bool>,
+
+    /// Give suggestions for misspellings in comments and plain text the
+    /// casing their position calls for: capitalized at the start of a
+    /// sentence, lowercase in the middle of one. None means "not set",
+    /// which keeps the dictionary's casing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = false))]
+    pub sentence_case_suggestions: Option<bool>,
+
+    /// Report words that mix look-alike letters from different scripts,
+    /// like a Cyrillic `а` in an otherwise Latin word. None means "not
+    /// set", which is off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(extend("default" = false))]
+    pub flag_confusables: Option<bool>,
+
+    /// Minimum length for words in identifiers. Falls back to min_word_length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_word_length_code: Option<usize>,
+
+    /// Minimum length for words in comments, strings and plain text.
+    /// Falls back to min_word_length.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_word_length_text: Option<usize>,
+
+    /// Coarse kinds of text to check, e.g. `["comments", "strings"]`. Unset
+    /// means everything. Applied before include_tags/exclude_tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_scope: Option<Vec<CheckScope>>,
+
     /// Tag prefixes to include (if non-empty, only matching tags are checked)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub include_tags: Vec<String>,
@@ -288,6 +521,40 @@ pub struct ConfigSettings {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub exclude_tags: Vec<String>,
 
+    /// Glob patterns mapped to a language name (e.g. `"*.tpl" = "html"`).
+    /// A matching file is parsed as that language regardless of its
+    /// extension.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub language_overrides: BTreeMap<String, String>,
+
+    /// Per-language settings keyed by lowercase language ID
+    #[serde(
+        default,
+        deserialize_with = "lowercase_keys",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub languages: BTreeMap<String, LanguageSettings>,
+
+    /// Local Hunspell dictionaries, always used alongside `dictionaries`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_dictionaries: Vec<CustomDictionary>,
+
+    /// A `word<TAB>count` list used to rank suggestions, most frequent
+    /// first. Relative paths are relative to the defining config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_list: Option<PathBuf>,
+
+    /// Most edits a suggestion may be from the misspelled word. None means
+    /// "not set", which allows half the word's length, from 1 up to 4.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_suggestion_distance: Option<usize>,
+
+    /// Where downloaded dictionaries are cached, instead of the platform's
+    /// data directory. Relative paths are relative to the defining config
+    /// file. The `CODEBOOK_CACHE_DIR` environment variable wins over it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+
     /// Scoped configuration overrides
     #[serde(
         default,
@@ -305,6 +572,13 @@ fn is_default_use_global(value: &bool) -> bool {
     *value == default_use_global()
 }
 
+/// The marker `disable_file_marker` looks for when it isn't set.
+const DEFAULT_DISABLE_FILE_MARKER: &str = "codebook: off";
+
+/// The most edits `max_suggestion_distance` allows when it isn't set, for
+/// the longest words.
+const DEFAULT_MAX_SUGGESTION_DISTANCE: usize = 4;
+
 fn default_min_word_length() -> usize {
     3
 }
@@ -313,15 +587,40 @@ impl Default for ConfigSettings {
     fn default() -> Self {
         Self {
             dictionaries: vec![],
+            detect_languages: vec![],
             words: Vec::new(),
             flag_words: Vec::new(),
+            allowed_contractions: Vec::new(),
+            words_file: None,
+            flag_words_file: None,
             include_paths: Vec::new(),
             ignore_paths: Vec::new(),
             ignore_patterns: Vec::new(),
+            keep_together: Vec::new(),
             use_global: true,
+            default_dictionary: None,
             min_word_length: None,
+            strict_dictionaries: None,
+            offline: None,
+            skip_binary: None,
+            skip_minified: None,
+            disable_file_marker: None,
+            split_camel_case: None,
+            hyphenated_compounds: None,
+            learn_project_symbols: None,
+            sentence_case_suggestions: None,
+            flag_confusables: None,
+            min_word_length_code: None,
+            min_word_length_text: None,
+            check_scope: None,
+            frequency_list: None,
+            max_suggestion_distance: None,
+            cache_dir: None,
             include_tags: Vec::new(),
             exclude_tags: Vec::new(),
+            language_overrides: BTreeMap::new(),
+            languages: BTreeMap::new(),
+            custom_dictionaries: Vec::new(),
             overrides: Vec::new(),
         }
     }
@@ -333,13 +632,26 @@ impl ConfigSettings {
     pub fn merge(&mut self, other: ConfigSettings) {
         // Add items from the other config
         self.dictionaries.extend(other.dictionaries);
+        self.detect_languages.extend(other.detect_languages);
         self.words.extend(other.words);
         self.flag_words.extend(other.flag_words);
+        self.allowed_contractions.extend(other.allowed_contractions);
         self.include_paths.extend(other.include_paths);
         self.ignore_paths.extend(other.ignore_paths);
         self.ignore_patterns.extend(other.ignore_patterns);
+        self.keep_together.extend(other.keep_together);
         self.include_tags.extend(other.include_tags);
         self.exclude_tags.extend(other.exclude_tags);
+        // Same glob in both configs: the other (project) config's language wins
+        self.language_overrides.extend(other.language_overrides);
+        // Same language in both configs: merged setting by setting
+        for (id, settings) in other.languages {
+            self.languages.entry(id).or_default().merge(settings);
+        }
+        // Same name in both configs: the other (project) config's entry wins
+        self.custom_dictionaries
+            .retain(|d| !other.custom_dictionaries.iter().any(|o| o.name == d.name));
+        self.custom_dictionaries.extend(other.custom_dictionaries);
 
         // Append overrides (global first, then project — order matters)
         self.overrides.extend(other.overrides);
@@ -347,10 +659,67 @@ impl ConfigSettings {
         // The use_global setting from the other config is ignored during merging
         // as this is a per-config setting
 
+        if other.default_dictionary.is_some() {
+            self.default_dictionary = other.default_dictionary;
+        }
         // Override min_word_length if the other config sets one explicitly
         if other.min_word_length.is_some() {
             self.min_word_length = other.min_word_length;
         }
+        if other.strict_dictionaries.is_some() {
+            self.strict_dictionaries = other.strict_dictionaries;
+        }
+        if other.offline.is_some() {
+            self.offline = other.offline;
+        }
+        if other.skip_binary.is_some() {
+            self.skip_binary = other.skip_binary;
+        }
+        if other.skip_minified.is_some() {
+            self.skip_minified = other.skip_minified;
+        }
+        if other.disable_file_marker.is_some() {
+            self.disable_file_marker = other.disable_file_marker;
+        }
+        if other.split_camel_case.is_some() {
+            self.split_camel_case = other.split_camel_case;
+        }
+        if other.hyphenated_compounds.is_some() {
+            self.hyphenated_compounds = other.hyphenated_compounds;
+        }
+        if other.learn_project_symbols.is_some() {
+            self.learn_project_symbols = other.learn_project_symbols;
+        }
+        if other.sentence_case_suggestions.is_some() {
+            self.sentence_case_suggestions = other.sentence_case_suggestions;
+        }
+        if other.flag_confusables.is_some() {
+            self.flag_confusables = other.flag_confusables;
+        }
+        if other.min_word_length_code.is_some() {
+            self.min_word_length_code = other.min_word_length_code;
+        }
+        if other.min_word_length_text.is_some() {
+            self.min_word_length_text = other.min_word_length_text;
+        }
+        if other.check_scope.is_some() {
+            self.check_scope = other.check_scope;
+        }
+        if other.words_file.is_some() {
+            self.words_file = other.words_file;
+        }
+        if other.flag_words_file.is_some() {
+            self.flag_words_file = other.flag_words_file;
+        }
+        if other.frequency_list.is_some() {
+            self.frequency_list = other.frequency_list;
+        }
+        if other.max_suggestion_distance.is_some() {
+            self.max_suggestion_distance = other.max_suggestion_distance;
+        }
+        if other.cache_dir.is_some() {
+            self.cache_dir = other.cache_dir;
+        }
 
         // Sort and deduplicate each collection (but NOT overrides)
         self.sort_and_dedup();
@@ -361,15 +730,17 @@ impl ConfigSettings {
         // Sort and deduplicate each Vec. Word lists dedup case-insensitively
         // to match how lookups compare them (unicase::eq).
         sort_and_dedup(&mut self.dictionaries);
+        sort_and_dedup(&mut self.detect_languages);
         sort_and_dedup_unicase(&mut self.words);
         sort_and_dedup_unicase(&mut self.flag_words);
+        sort_and_dedup_unicase(&mut self.allowed_contractions);
         sort_and_dedup(&mut self.include_paths);
         sort_and_dedup(&mut self.ignore_paths);
         sort_and_dedup(&mut self.include_tags);
         sort_and_dedup(&mut self.exclude_tags);
         // Note: overrides are NOT sorted — order matters for resolution.
-        // ignore_patterns keep their written order too: they're never added
-        // programmatically, and a duplicate pattern is harmless.
+        // ignore_patterns and keep_together keep their written order too:
+        // they're never added programmatically, and a duplicate is harmless.
     }
 
     /// Apply a single override block to this settings (mutates in place).
@@ -420,6 +791,50 @@ impl ConfigSettings {
     }
 }
 
+/// Tags only checked when an `include_tags` entry matches them. Keys in
+/// data files are usually dictated by a schema rather than written as prose.
+const OPT_IN_TAGS: &[&str] = &["identifier.key"];
+
+impl ConfigSettings {
+    /// A JSON Schema for config files, for editors to validate and complete
+    /// them. `dictionary_ids` become the allowed values wherever a setting
+    /// takes a dictionary ID; TOML has no null, so unset options are only
+    /// left out.
+    pub fn json_schema(dictionary_ids: &[String]) -> serde_json::Value {
+        let mut schema = schemars::schema_for!(ConfigSettings).to_value();
+        drop_null_types(&mut schema);
+        schema["title"] = "Codebook configuration".into();
+        let id = serde_json::json!({ "type": "string", "enum": dictionary_ids });
+        for setting in ["dictionaries", "detect_languages"] {
+            schema["properties"][setting]["items"] = id.clone();
+        }
+        for setting in ["dictionaries", "extra_dictionaries"] {
+            schema["$defs"]["OverrideBlock"]["properties"][setting]["items"] = id.clone();
+        }
+        let default_dictionary = &mut schema["properties"]["default_dictionary"];
+        default_dictionary["enum"] = dictionary_ids.into();
+        schema
+    }
+}
+
+/// Turn each `"type": ["x", "null"]` in `schema` into `"type": "x"`.
+fn drop_null_types(schema: &mut serde_json::Value) {
+    match schema {
+        serde_json::Value::Object(map) => {
+            if let Some(serde_json::Value::Array(types)) = map.get_mut("type") {
+                types.retain(|t| t != "null");
+                if let [only] = types.as_mut_slice() {
+                    let only = only.take();
+                    map.insert("type".to_string(), only);
+                }
+            }
+            map.values_mut().for_each(drop_null_types);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(drop_null_types),
+        _ => {}
+    }
+}
+
 /// Check if a tag matches a pattern using prefix matching.
 /// "comment" matches "comment", "comment.line", "comment.block", etc.
 fn tag_matches_pattern(tag: &str, pattern: &str) -> bool {
@@ -428,8 +843,15 @@ fn tag_matches_pattern(tag: &str, pattern: &str) -> bool {
 
 impl ConfigSettings {
     /// Determine whether a capture tag should be spell-checked based on
-    /// include_tags and exclude_tags. exclude_tags takes precedence.
+    /// check_scope, include_tags and exclude_tags. exclude_tags takes
+    /// precedence. Opt-in tags (see `OPT_IN_TAGS`) need a matching
+    /// include_tags entry.
     pub fn should_check_tag(&self, tag: &str) -> bool {
+        if let Some(scopes) = &self.check_scope
+            && !scopes.iter().any(|s| s.covers_tag(tag))
+        {
+            return false;
+        }
         // exclude_tags takes precedence
         if self
             .exclude_tags
@@ -445,7 +867,19 @@ impl ConfigSettings {
                 .iter()
                 .any(|p| tag_matches_pattern(tag, p));
         }
-        true
+        !OPT_IN_TAGS.iter().any(|p| tag_matches_pattern(tag, p))
+    }
+
+    /// Whether string literals are checked for a language known by
+    /// `language_ids`: false only if a `[languages.<id>]` entry for one of
+    /// them sets `check_strings = false`.
+    pub fn check_strings(&self, language_ids: &[&str]) -> bool {
+        !language_ids.iter().any(|id| {
+            self.languages
+                .get(&id.to_ascii_lowercase())
+                .and_then(|l| l.check_strings)
+                == Some(false)
+        })
     }
 
     /// Insert a word into the allowlist, returning true when it was newly added.
@@ -459,6 +893,28 @@ impl ConfigSettings {
         true
     }
 
+    /// Insert several words into the allowlist, sorting once, and return how
+    /// many were newly added. Duplicates are matched as in `insert_word`,
+    /// including within `words`.
+    pub fn insert_words(&mut self, words: &[&str]) -> usize {
+        let mut known: HashSet<unicase::UniCase<&str>> = self
+            .words
+            .iter()
+            .map(|w| unicase::UniCase::new(w.as_str()))
+            .collect();
+        let new: Vec<String> = words
+            .iter()
+            .filter(|w| known.insert(unicase::UniCase::new(w)))
+            .map(|w| w.to_string())
+            .collect();
+        if new.is_empty() {
+            return 0;
+        }
+        self.words.extend_from_slice(&new);
+        self.words.sort();
+        new.len()
+    }
+
     /// Insert a path into the ignore list, returning true when it was newly added.
     pub fn insert_ignore(&mut self, file: &str) -> bool {
         let file = normalize_separators(file);
@@ -483,10 +939,15 @@ impl ConfigSettings {
         true
     }
 
-    /// Resolve configured dictionary IDs, providing a default when none are set.
+    /// Resolve configured dictionary IDs, providing a default when none are
+    /// set: `default_dictionary`, or else `en_us`.
     pub fn dictionary_ids(&self) -> Vec<String> {
         if self.dictionaries.is_empty() {
-            vec!["en_us".to_string()]
+            vec![
+                self.default_dictionary
+                    .clone()
+                    .unwrap_or_else(|| "en_us".to_string()),
+            ]
         } else {
             self.dictionaries.clone()
         }
@@ -507,9 +968,35 @@ impl ConfigSettings {
         match_pattern(&self.ignore_paths, &path_str)
     }
 
+    /// Look up the language forced for a path by `language_overrides`.
+    /// When several globs match, the first in sorted order wins.
+    pub fn language_override(&self, path: &Path) -> Option<&str> {
+        if self.language_overrides.is_empty() {
+            return None;
+        }
+        let path_str = normalize_separators(&path.to_string_lossy());
+        self.language_overrides
+            .iter()
+            .find(|(pattern, _)| match_pattern(std::slice::from_ref(*pattern), &path_str))
+            .map(|(_, language)| language.as_str())
+    }
+
     /// Check if a word is explicitly allowed.
     pub fn is_allowed_word(&self, word: &str) -> bool {
         self.words.iter().any(|w| unicase::eq(w.as_str(), word))
+            || self.is_allowed_contraction(word)
+    }
+
+    /// Check if a word with an apostrophe is in `allowed_contractions`.
+    pub fn is_allowed_contraction(&self, word: &str) -> bool {
+        const APOSTROPHES: [char; 2] = ['\'', '\u{2019}'];
+        if !word.contains(APOSTROPHES) {
+            return false;
+        }
+        let word = word.replace(APOSTROPHES, "'");
+        self.allowed_contractions
+            .iter()
+            .any(|c| unicase::eq(c.replace(APOSTROPHES, "'").as_str(), word.as_str()))
     }
 
     /// Check if a word should be flagged.
@@ -523,6 +1010,79 @@ impl ConfigSettings {
     pub fn min_word_length(&self) -> usize {
         self.min_word_length.unwrap_or_else(default_min_word_length)
     }
+
+    /// Whether a dictionary failing to load is a hard error.
+    pub fn strict_dictionaries(&self) -> bool {
+        self.strict_dictionaries.unwrap_or(false)
+    }
+
+    /// Whether dictionaries may only come from the cache.
+    pub fn offline(&self) -> bool {
+        self.offline.unwrap_or(false)
+    }
+
+    /// Whether files that look binary are skipped.
+    pub fn skip_binary(&self) -> bool {
+        self.skip_binary.unwrap_or(true)
+    }
+
+    /// Whether files that look minified are skipped.
+    pub fn skip_minified(&self) -> bool {
+        self.skip_minified.unwrap_or(true)
+    }
+
+    /// The first-line marker that skips a file, or None when turned off.
+    pub fn disable_file_marker(&self) -> Option<&str> {
+        match self.disable_file_marker.as_deref() {
+            None => Some(DEFAULT_DISABLE_FILE_MARKER),
+            Some("") => None,
+            Some(marker) => Some(marker),
+        }
+    }
+
+    /// Whether camelCase words outside identifiers are split.
+    pub fn split_camel_case(&self) -> bool {
+        self.split_camel_case.unwrap_or(true)
+    }
+
+    /// Whether hyphenated words are checked whole before their parts.
+    pub fn hyphenated_compounds(&self) -> bool {
+        self.hyphenated_compounds.unwrap_or(false)
+    }
+
+    /// Whether defined function and type names are learned as known words.
+    pub fn learn_project_symbols(&self) -> bool {
+        self.learn_project_symbols.unwrap_or(false)
+    }
+
+    /// Whether prose suggestions are cased for their place in the sentence.
+    pub fn sentence_case_suggestions(&self) -> bool {
+        self.sentence_case_suggestions.unwrap_or(false)
+    }
+
+    /// Whether words mixing look-alike letters from different scripts are
+    /// reported.
+    pub fn flag_confusables(&self) -> bool {
+        self.flag_confusables.unwrap_or(false)
+    }
+
+    /// Minimum length for words in identifiers.
+    pub fn min_word_length_code(&self) -> usize {
+        self.min_word_length_code
+            .unwrap_or_else(|| self.min_word_length())
+    }
+
+    /// Minimum length for words in comments, strings and plain text.
+    pub fn min_word_length_text(&self) -> usize {
+        self.min_word_length_text
+            .unwrap_or_else(|| self.min_word_length())
+    }
+
+    /// Most edits a suggestion for `word` may be away from it.
+    pub fn max_suggestion_distance(&self, word: &str) -> usize {
+        self.max_suggestion_distance
+            .unwrap_or_else(|| (word.chars().count() / 2).clamp(1, DEFAULT_MAX_SUGGESTION_DISTANCE))
+    }
 }
 
 fn match_pattern(patterns: &[String], path_str: &str) -> bool {
@@ -766,6 +1326,204 @@ mod tests {
         assert_eq!(base.min_word_length, Some(2));
     }
 
+    #[test]
+    fn test_min_word_length_code_and_text() {
+        let toml_str = r#"
+        min_word_length = 4
+        min_word_length_text = 2
+        "#;
+        let config: ConfigSettings = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.min_word_length_text(), 2);
+        // Unset thresholds fall back to min_word_length
+        assert_eq!(config.min_word_length_code(), 4);
+
+        let config = ConfigSettings::default();
+        assert_eq!(config.min_word_length_code(), 3);
+        assert_eq!(config.min_word_length_text(), 3);
+
+        let mut base = ConfigSettings {
+            min_word_length_code: Some(5),
+            ..Default::default()
+        };
+        base.merge(ConfigSettings {
+            min_word_length_text: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(base.min_word_length_code, Some(5));
+        assert_eq!(base.min_word_length_text, Some(1));
+    }
+
+    #[test]
+    fn test_strict_dictionaries() {
+        assert!(!ConfigSettings::default().strict_dictionaries());
+        let config: ConfigSettings = toml::from_str("strict_dictionaries = true").unwrap();
+        assert!(config.strict_dictionaries());
+
+        // A project can turn off strictness set globally
+        let mut base = config.clone();
+        base.merge(ConfigSettings {
+            strict_dictionaries: Some(false),
+            ..Default::default()
+        });
+        assert!(!base.strict_dictionaries());
+        let mut base = config;
+        base.merge(ConfigSettings::default());
+        assert!(base.strict_dictionaries());
+    }
+
+    #[test]
+    fn test_offline() {
+        assert!(!ConfigSettings::default().offline());
+        let mut config: ConfigSettings = toml::from_str("offline = true").unwrap();
+        assert!(config.offline());
+        config.merge(ConfigSettings {
+            offline: Some(false),
+            ..Default::default()
+        });
+        assert!(!config.offline());
+    }
+
+    #[test]
+    fn test_skip_binary_and_minified() {
+        let defaults = ConfigSettings::default();
+        assert!(defaults.skip_binary() && defaults.skip_minified());
+        let mut config: ConfigSettings = toml::from_str("skip_minified = false").unwrap();
+        assert!(config.skip_binary());
+        assert!(!config.skip_minified());
+        config.merge(ConfigSettings {
+            skip_binary: Some(false),
+            ..Default::default()
+        });
+        assert!(!config.skip_binary());
+        assert!(!config.skip_minified());
+    }
+
+    #[test]
+    fn test_disable_file_marker() {
+        assert_eq!(
+            ConfigSettings::default().disable_file_marker(),
+            Some("codebook: off")
+        );
+        let mut config: ConfigSettings =
+            toml::from_str(r#"disable_file_marker = "spellcheck: off""#).unwrap();
+        assert_eq!(config.disable_file_marker(), Some("spellcheck: off"));
+        config.merge(ConfigSettings {
+            disable_file_marker: Some(String::new()),
+            ..Default::default()
+        });
+        assert_eq!(config.disable_file_marker(), None);
+    }
+
+    #[test]
+    fn test_split_camel_case() {
+        assert!(ConfigSettings::default().split_camel_case());
+        let mut config: ConfigSettings = toml::from_str("split_camel_case = false").unwrap();
+        assert!(!config.split_camel_case());
+        config.merge(ConfigSettings::default());
+        assert!(!config.split_camel_case());
+    }
+
+    #[test]
+    fn test_hyphenated_compounds() {
+        assert!(!ConfigSettings::default().hyphenated_compounds());
+        let mut config: ConfigSettings = toml::from_str("hyphenated_compounds = true").unwrap();
+        assert!(config.hyphenated_compounds());
+        config.merge(ConfigSettings::default());
+        assert!(config.hyphenated_compounds());
+    }
+
+    #[test]
+    fn test_max_suggestion_distance() {
+        let config = ConfigSettings::default();
+        assert_eq!(config.max_suggestion_distance("ab"), 1);
+        assert_eq!(config.max_suggestion_distance("wrod"), 2);
+        assert_eq!(config.max_suggestion_distance("internationalizaton"), 4);
+        let config: ConfigSettings = toml::from_str("max_suggestion_distance = 1").unwrap();
+        assert_eq!(config.max_suggestion_distance("wrod"), 1);
+        assert_eq!(config.max_suggestion_distance("internationalizaton"), 1);
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let schema = ConfigSettings::json_schema(&["en_us".to_string(), "fr".to_string()]);
+        let properties = &schema["properties"];
+        for field in ["words", "flag_words", "ignore_patterns", "overrides"] {
+            assert!(properties.get(field).is_some(), "missing {field}");
+        }
+        assert_eq!(properties["dictionaries"]["items"]["enum"][1], "fr");
+        assert_eq!(properties["min_word_length"]["default"], 3);
+        assert_eq!(properties["skip_binary"]["type"], "boolean");
+        assert_eq!(
+            properties["ignore_patterns"]["description"],
+            "Regex patterns for text to ignore, compiled at parse time"
+        );
+        let overrides = &schema["$defs"]["OverrideBlock"]["properties"];
+        assert_eq!(overrides["extra_dictionaries"]["items"]["enum"][0], "en_us");
+        assert_eq!(
+            schema["$defs"]["CheckScope"]["oneOf"][0]["const"],
+            "comments"
+        );
+    }
+
+    #[test]
+    fn test_flag_confusables() {
+        assert!(!ConfigSettings::default().flag_confusables());
+        let mut config: ConfigSettings = toml::from_str("flag_confusables = true").unwrap();
+        assert!(config.flag_confusables());
+        config.merge(ConfigSettings::default());
+        assert!(config.flag_confusables());
+    }
+
+    #[test]
+    fn test_learn_project_symbols() {
+        assert!(!ConfigSettings::default().learn_project_symbols());
+        let mut config: ConfigSettings = toml::from_str("learn_project_symbols = true").unwrap();
+        assert!(config.learn_project_symbols());
+        config.merge(ConfigSettings::default());
+        assert!(config.learn_project_symbols());
+    }
+
+    #[test]
+    fn test_sentence_case_suggestions() {
+        assert!(!ConfigSettings::default().sentence_case_suggestions());
+        let mut config: ConfigSettings =
+            toml::from_str("sentence_case_suggestions = true").unwrap();
+        assert!(config.sentence_case_suggestions());
+        config.merge(ConfigSettings::default());
+        assert!(config.sentence_case_suggestions());
+    }
+
+    #[test]
+    fn test_allowed_contractions() {
+        let mut config: ConfigSettings =
+            toml::from_str(r#"allowed_contractions = ["y'all", "O’clock"]"#).unwrap();
+        config.merge(ConfigSettings {
+            allowed_contractions: vec!["Y'ALL".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(config.allowed_contractions.len(), 2);
+        assert!(config.is_allowed_word("y'all"));
+        assert!(config.is_allowed_word("Y\u{2019}all"));
+        assert!(config.is_allowed_word("o'clock"));
+        assert!(config.is_allowed_word("o\u{2019}clock"));
+        // Only consulted for words with an apostrophe
+        config.allowed_contractions.push("yall".to_string());
+        assert!(!config.is_allowed_word("yall"));
+        assert!(!config.is_allowed_word("ma'am"));
+    }
+
+    #[test]
+    fn test_detect_languages() {
+        assert!(ConfigSettings::default().detect_languages.is_empty());
+        let mut config: ConfigSettings =
+            toml::from_str(r#"detect_languages = ["FR", "de"]"#).unwrap();
+        config.merge(ConfigSettings {
+            detect_languages: vec!["fr".to_string(), "it".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(config.detect_languages, vec!["de", "fr", "it"]);
+    }
+
     #[test]
     fn test_merge_min_word_length_default() {
         let mut base = ConfigSettings {
@@ -881,6 +1639,17 @@ mod tests {
         assert!(config.should_flag_word("Ошибка"));
     }
 
+    #[test]
+    fn test_insert_words() {
+        let mut config = ConfigSettings {
+            words: vec!["zeta".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.insert_words(&["beta", "Zeta", "alpha", "BETA"]), 2);
+        assert_eq!(config.words, vec!["alpha", "beta", "zeta"]);
+        assert_eq!(config.insert_words(&["alpha"]), 0);
+    }
+
     #[test]
     fn test_include_tags_deserialization() {
         let toml_str = r#"
@@ -949,6 +1718,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_language_overrides_deserialization() {
+        let toml_str = r#"
+        [language_overrides]
+        "*.tpl" = "html"
+        "conf/**/*.conf" = "text"
+        "#;
+        let config: ConfigSettings = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.language_overrides.len(), 2);
+        assert_eq!(
+            config.language_override(Path::new("templates/page.tpl")),
+            Some("html")
+        );
+        assert_eq!(
+            config.language_override(Path::new("conf/app/site.conf")),
+            Some("text")
+        );
+        assert_eq!(config.language_override(Path::new("src/main.rs")), None);
+    }
+
+    #[test]
+    fn test_language_overrides_merge_other_wins() {
+        let mut base = ConfigSettings::default();
+        base.language_overrides
+            .insert("*.tpl".to_string(), "text".to_string());
+        base.language_overrides
+            .insert("*.conf".to_string(), "toml".to_string());
+        let mut other = ConfigSettings::default();
+        other
+            .language_overrides
+            .insert("*.tpl".to_string(), "html".to_string());
+        base.merge(other);
+        assert_eq!(base.language_override(Path::new("a.tpl")), Some("html"));
+        assert_eq!(base.language_override(Path::new("a.conf")), Some("toml"));
+    }
+
+    #[test]
+    fn test_language_overrides_roundtrip() {
+        let mut config = ConfigSettings::default();
+        assert!(
+            !toml::to_string(&config)
+                .unwrap()
+                .contains("language_overrides")
+        );
+        config
+            .language_overrides
+            .insert("*.tpl".to_string(), "html".to_string());
+        let serialized = toml::to_string(&config).unwrap();
+        let parsed: ConfigSettings = toml::from_str(&serialized).unwrap();
+        assert_eq!(parsed.language_overrides, config.language_overrides);
+    }
+
     #[test]
     fn test_should_check_tag_no_filters() {
         let config = ConfigSettings::default();
@@ -957,6 +1778,79 @@ mod tests {
         assert!(config.should_check_tag("identifier.function"));
     }
 
+    #[test]
+    fn test_should_check_tag_check_scope() {
+        let config: ConfigSettings = toml::from_str(r#"check_scope = ["comments", "strings"]"#)
+            .expect("Failed to deserialize");
+        assert_eq!(
+            config.check_scope,
+            Some(vec![CheckScope::Comments, CheckScope::Strings])
+        );
+        assert!(config.should_check_tag("comment.line"));
+        assert!(config.should_check_tag("string.heredoc"));
+        assert!(!config.should_check_tag("identifier.field"));
+
+        // Tag filters narrow the scope further
+        let config = ConfigSettings {
+            check_scope: Some(vec![CheckScope::Identifiers]),
+            exclude_tags: vec!["identifier.variable".to_string()],
+            ..Default::default()
+        };
+        assert!(config.should_check_tag("identifier.function"));
+        assert!(!config.should_check_tag("identifier.variable"));
+        assert!(!config.should_check_tag("comment"));
+
+        let config = ConfigSettings {
+            check_scope: Some(vec![CheckScope::All]),
+            ..Default::default()
+        };
+        assert!(config.should_check_tag("comment"));
+        assert!(config.should_check_tag("identifier.type"));
+    }
+
+    #[test]
+    fn test_languages_check_strings() {
+        let mut global: ConfigSettings = toml::from_str(
+            r#"
+            [languages.Python]
+            check_strings = false
+
+            [languages.lua]
+            check_strings = false
+            "#,
+        )
+        .unwrap();
+        assert!(!global.check_strings(&["python", "python3"]));
+        assert!(global.check_strings(&["rust"]));
+
+        // The project turns Lua strings back on and leaves Python alone
+        let project: ConfigSettings = toml::from_str(
+            r#"
+            [languages.lua]
+            check_strings = true
+            "#,
+        )
+        .unwrap();
+        global.merge(project);
+        assert!(!global.check_strings(&["python"]));
+        assert!(global.check_strings(&["lua"]));
+    }
+
+    #[test]
+    fn test_check_scope_project_replaces_global() {
+        let mut global = ConfigSettings {
+            check_scope: Some(vec![CheckScope::All]),
+            ..Default::default()
+        };
+        global.merge(ConfigSettings {
+            check_scope: Some(vec![CheckScope::Comments]),
+            ..Default::default()
+        });
+        assert_eq!(global.check_scope, Some(vec![CheckScope::Comments]));
+        global.merge(ConfigSettings::default());
+        assert_eq!(global.check_scope, Some(vec![CheckScope::Comments]));
+    }
+
     #[test]
     fn test_should_check_tag_include_only() {
         let config = ConfigSettings {
@@ -1013,6 +1907,20 @@ mod tests {
         assert!(!config.should_check_tag("identifier.type"));
     }
 
+    #[test]
+    fn test_should_check_tag_opt_in() {
+        let config = ConfigSettings::default();
+        assert!(!config.should_check_tag("identifier.key"));
+        assert!(config.should_check_tag("identifier.field"));
+
+        let config = ConfigSettings {
+            include_tags: vec!["string".to_string(), "identifier.key".to_string()],
+            ..Default::default()
+        };
+        assert!(config.should_check_tag("identifier.key"));
+        assert!(config.should_check_tag("string"));
+    }
+
     #[test]
     fn test_partial_deserialization() {
         let toml_str = r#"
@@ -1382,6 +2290,25 @@ mod tests {
         assert_eq!(settings.dictionary_ids(), vec!["en_us"]);
     }
 
+    #[test]
+    fn test_default_dictionary() {
+        let global: ConfigSettings = toml::from_str(r#"default_dictionary = "EN_GB""#).unwrap();
+        assert_eq!(global.default_dictionary.as_deref(), Some("en_gb"));
+
+        // A project without dictionaries gets the global default
+        let mut merged = global.clone();
+        merged.merge(ConfigSettings::default());
+        assert_eq!(merged.dictionary_ids(), vec!["en_gb"]);
+
+        // Dictionaries set anywhere win over the default
+        let mut merged = global.clone();
+        merged.merge(ConfigSettings {
+            dictionaries: vec!["de".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(merged.dictionary_ids(), vec!["de"]);
+    }
+
     impl OverrideBlock {
         /// Helper for tests: creates an OverrideBlock with all fields set to None/empty.
         fn default_for_test() -> Self {
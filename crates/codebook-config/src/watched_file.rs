@@ -40,8 +40,8 @@ impl<T: Clone> WatchedFile<T> {
         self.path.as_deref()
     }
 
-    /// Check if the file has changed since last check
-    #[cfg(test)]
+    /// Check if the file has changed since last check. Only stats the file,
+    /// so it is cheap enough to call before deciding to reload.
     pub fn has_changed(&self) -> bool {
         let Some(path) = &self.path else {
             return false;
@@ -98,29 +98,17 @@ impl<T: Clone> WatchedFile<T> {
         F: FnOnce(&Path) -> Result<T, E>,
         E: std::fmt::Display,
     {
-        let Some(path) = self.path.clone() else {
-            // A watched file without a path never changes.
-            return (self, false);
-        };
-
-        // Stat before reading (see load() for why)
-        let meta = disk_meta(&path);
-        if !self.meta_differs(&meta) {
-            return (self, false);
-        }
-
-        match loader(&path) {
-            Ok(content) => (self.with_content_meta(content, meta), true),
-            Err(_) if meta.is_none() => {
-                // File was deleted, clear the content
-                (self.cleared(), true)
-            }
+        match self.clone().try_reload_if_changed(loader) {
+            Ok(reloaded) => reloaded,
             Err(e) => {
                 // File exists but is unreadable or invalid (e.g. mid-edit TOML).
                 // Keep the last good content; the stale stamp means we retry on
                 // the next reload. Warn only when the failing file changed, so
                 // a config left broken on disk logs once, not on every poll.
-                if self.last_failed_meta != meta {
+                let meta = self.path.as_deref().and_then(disk_meta);
+                if self.last_failed_meta != meta
+                    && let Some(path) = &self.path
+                {
                     log::warn!("Keeping previous config for {}: {e}", path.display());
                 }
                 (
@@ -134,6 +122,34 @@ impl<T: Clone> WatchedFile<T> {
         }
     }
 
+    /// Like `reload_if_changed`, but a file that exists and fails to load is
+    /// an error instead of keeping the last good content, for callers about
+    /// to write the file back.
+    pub fn try_reload_if_changed<F, E>(self, loader: F) -> Result<(Self, bool), E>
+    where
+        F: FnOnce(&Path) -> Result<T, E>,
+    {
+        let Some(path) = self.path.clone() else {
+            // A watched file without a path never changes.
+            return Ok((self, false));
+        };
+
+        // Stat before reading (see load() for why)
+        let meta = disk_meta(&path);
+        if !self.meta_differs(&meta) {
+            return Ok((self, false));
+        }
+
+        match loader(&path) {
+            Ok(content) => Ok((self.with_content_meta(content, meta), true)),
+            Err(_) if meta.is_none() => {
+                // File was deleted, clear the content
+                Ok((self.cleared(), true))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     /// Get the current content
     pub fn content(&self) -> Option<&T> {
         self.content.as_ref()
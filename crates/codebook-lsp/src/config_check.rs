@@ -0,0 +1,70 @@
+use codebook::dictionaries::available_dictionaries;
+use codebook_config::CodebookConfig;
+
+/// Problems in a loaded config that loading itself doesn't catch: unknown
+/// dictionary IDs and missing dictionary files. Patterns and globs are
+/// already compiled by then, so a config with a bad one never gets here.
+pub fn check_config(config: &dyn CodebookConfig) -> Vec<String> {
+    let known: Vec<String> = available_dictionaries().into_iter().map(|d| d.id).collect();
+    let mut problems = Vec::new();
+    for (setting, ids) in [
+        ("dictionaries", config.get_dictionary_ids()),
+        ("detect_languages", config.detect_languages()),
+    ] {
+        for id in ids.iter().filter(|id| !known.contains(id)) {
+            problems.push(format!(
+                "unknown dictionary '{id}' in `{setting}`; run `codebook-lsp dictionaries` for valid IDs"
+            ));
+        }
+    }
+    for dictionary in config.custom_dictionaries() {
+        for path in [&dictionary.aff_path, &dictionary.dic_path] {
+            if !path.is_file() {
+                problems.push(format!(
+                    "custom dictionary '{}': {} not found",
+                    dictionary.name,
+                    path.display()
+                ));
+            }
+        }
+    }
+    if let Some(path) = config.frequency_list()
+        && !path.is_file()
+    {
+        problems.push(format!("frequency_list: {} not found", path.display()));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codebook_config::{CodebookConfigMemory, ConfigSettings, CustomDictionary};
+
+    #[test]
+    fn test_check_config() {
+        assert!(check_config(&CodebookConfigMemory::default()).is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        let aff_path = dir.path().join("team.aff");
+        std::fs::write(&aff_path, "SET UTF-8\n").unwrap();
+        let config = CodebookConfigMemory::new(ConfigSettings {
+            dictionaries: vec!["en_us".to_string(), "en_usa".to_string()],
+            detect_languages: vec!["klingon".to_string()],
+            custom_dictionaries: vec![CustomDictionary {
+                name: "team".to_string(),
+                aff_path,
+                dic_path: dir.path().join("team.dic"),
+            }],
+            frequency_list: Some(dir.path().join("freq.tsv")),
+            ..Default::default()
+        });
+        let problems = check_config(&config);
+        assert_eq!(problems.len(), 4, "{problems:?}");
+        assert!(problems[0].contains("'en_usa' in `dictionaries`"));
+        assert!(problems[1].contains("'klingon' in `detect_languages`"));
+        assert!(problems[2].starts_with("custom dictionary 'team'"));
+        assert!(problems[2].contains("team.dic"));
+        assert!(problems[3].starts_with("frequency_list"));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Lines added or modified relative to a git revision, by file. Built from
+/// `git diff --unified=0`, so it covers staged and unstaged changes to
+/// tracked files; untracked files are not part of the diff.
+#[derive(Debug, Default)]
+pub struct ChangedLines {
+    /// Canonical file path to 1-based line ranges in the working tree file
+    files: HashMap<PathBuf, Vec<RangeInclusive<usize>>>,
+}
+
+impl ChangedLines {
+    /// Runs git in `root` to collect the lines changed since `base`.
+    pub fn from_git(root: &Path, base: &str) -> Result<Self, String> {
+        let toplevel = run_git(root, &["rev-parse", "--show-toplevel"])?;
+        let toplevel = PathBuf::from(toplevel.trim());
+        let toplevel = toplevel.canonicalize().unwrap_or(toplevel);
+        let diff = run_git(
+            root,
+            &[
+                "-c",
+                "core.quotePath=false",
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                // Pin the prefixes `parse` expects over `diff.noprefix` and
+                // `diff.mnemonicPrefix`
+                "--src-prefix=a/",
+                "--dst-prefix=b/",
+                base,
+                "--",
+            ],
+        )?;
+        Ok(Self::parse(&diff, &toplevel))
+    }
+
+    /// Parses unified diff output whose paths are relative to `toplevel`.
+    fn parse(diff: &str, toplevel: &Path) -> Self {
+        let mut files: HashMap<PathBuf, Vec<RangeInclusive<usize>>> = HashMap::new();
+        let mut current: Option<PathBuf> = None;
+        // Lines left in the current hunk on the old and new side, so hunk
+        // lines like an added `++ x` aren't taken for headers
+        let (mut old_left, mut new_left) = (0usize, 0usize);
+        for line in diff.lines() {
+            if old_left > 0 || new_left > 0 {
+                match line.as_bytes().first() {
+                    Some(b'-') => old_left = old_left.saturating_sub(1),
+                    Some(b'+') => new_left = new_left.saturating_sub(1),
+                    Some(b'\\') => {}
+                    _ => {
+                        old_left = old_left.saturating_sub(1);
+                        new_left = new_left.saturating_sub(1);
+                    }
+                }
+                continue;
+            }
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // `/dev/null` for deleted files, which have no lines to check.
+                // Git may end names containing spaces with a tab.
+                current = path
+                    .strip_prefix("b/")
+                    .map(|p| toplevel.join(p.trim_end_matches('\t')));
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                (old_left, new_left) = parse_hunk_counts(hunk).unwrap_or_default();
+                if let Some(file) = &current
+                    && let Some(range) = parse_hunk_new_range(hunk)
+                {
+                    files.entry(file.clone()).or_default().push(range);
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Files with at least one changed line.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// The changed line ranges of `path`, or `None` if it has none.
+    pub fn lines(&self, path: &Path) -> Option<&[RangeInclusive<usize>]> {
+        let path = path.canonicalize().ok()?;
+        self.files.get(&path).map(Vec::as_slice)
+    }
+}
+
+/// The new-file side of a hunk header body such as `-3,2 +4,5 @@ fn x()`,
+/// as 1-based lines. Pure deletions (`+4,0`) have no lines.
+fn parse_hunk_new_range(hunk: &str) -> Option<RangeInclusive<usize>> {
+    let new = hunk.split_whitespace().find_map(|s| s.strip_prefix('+'))?;
+    let (start, count) = parse_hunk_side(new)?;
+    (count > 0).then(|| start..=start + count - 1)
+}
+
+/// How many old-side and new-side lines follow a hunk header body.
+fn parse_hunk_counts(hunk: &str) -> Option<(usize, usize)> {
+    let mut sides = hunk.split_whitespace();
+    let (_, old) = parse_hunk_side(sides.next()?.strip_prefix('-')?)?;
+    let (_, new) = parse_hunk_side(sides.next()?.strip_prefix('+')?)?;
+    Some((old, new))
+}
+
+/// A `start,count` hunk side, where a bare `start` means one line.
+fn parse_hunk_side(side: &str) -> Option<(usize, usize)> {
+    match side.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((side.parse().ok()?, 1)),
+    }
+}
+
+fn run_git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .map_err(|e| format!("failed to run git: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3 +3 @@ fn one()
+-old
++new
+@@ -10,0 +11,3 @@ fn two()
++a
++b
++c
+@@ -20,2 +23,0 @@ fn three()
+-gone
+-gone
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1,2 +0,0 @@
+-x
+-y
+";
+        let top = Path::new("/repo");
+        let changed = ChangedLines::parse(diff, top);
+        assert_eq!(
+            changed.files.get(&top.join("src/lib.rs")).unwrap(),
+            &vec![3..=3, 11..=13]
+        );
+        assert_eq!(changed.files.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_diff_hunk_lines_like_headers() {
+        // Removed `-- a` and added `++ b` lines read like file headers
+        let diff = "\
+diff --git a/notes.md b/notes.md
+index 1111111..2222222 100644
+--- a/notes.md
++++ b/notes.md
+@@ -2 +2,2 @@
+--- a
++++ b
++@@ -1 +1 @@
+@@ -9,0 +11 @@
++c
+";
+        let top = Path::new("/repo");
+        let changed = ChangedLines::parse(diff, top);
+        assert_eq!(
+            changed.files.get(&top.join("notes.md")).unwrap(),
+            &vec![2..=3, 11..=11]
+        );
+        assert_eq!(changed.files.len(), 1);
+    }
+
+    #[test]
+    fn test_from_git_ignores_prefix_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let git = |args: &[&str]| run_git(root, args).unwrap();
+        git(&["init", "-q"]);
+        std::fs::write(root.join("notes.txt"), "one\ntwo\n").unwrap();
+        git(&["add", "notes.txt"]);
+        git(&[
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "-c",
+            "commit.gpgsign=false",
+            "commit",
+            "-q",
+            "-m",
+            "init",
+        ]);
+        std::fs::write(root.join("notes.txt"), "one\nthree\n").unwrap();
+        for (key, value) in [("diff.noprefix", "true"), ("diff.mnemonicPrefix", "true")] {
+            git(&["config", key, value]);
+            let changed = ChangedLines::from_git(root, "HEAD").unwrap();
+            assert_eq!(
+                changed.lines(&root.join("notes.txt")),
+                Some(&[2..=2][..]),
+                "{key}"
+            );
+            git(&["config", "--unset", key]);
+        }
+    }
+
+    #[test]
+    fn test_parse_hunk_new_range() {
+        assert_eq!(parse_hunk_new_range("-1 +1 @@"), Some(1..=1));
+        assert_eq!(parse_hunk_new_range("-1,2 +4,5 @@ ctx"), Some(4..=8));
+        assert_eq!(parse_hunk_new_range("-1,2 +0,0 @@"), None);
+        assert_eq!(parse_hunk_new_range("garbage"), None);
+        assert_eq!(parse_hunk_counts("-1 +4,5 @@ ctx"), Some((1, 5)));
+        assert_eq!(parse_hunk_counts("-3,0 +4 @@"), Some((0, 1)));
+        assert_eq!(parse_hunk_counts("garbage"), None);
+    }
+}
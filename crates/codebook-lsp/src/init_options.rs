@@ -45,6 +45,12 @@ fn default_check_while_typing() -> bool {
     true
 }
 
+/// Documents larger than this aren't checked; generated files of many
+/// megabytes would otherwise stall the server on open.
+fn default_max_file_size_bytes() -> usize {
+    2 * 1024 * 1024
+}
+
 fn default_diagnostic_severity() -> DiagnosticSeverity {
     DiagnosticSeverity::INFORMATION
 }
@@ -57,7 +63,7 @@ where
     match s.as_deref() {
         Some("error") => Ok(DiagnosticSeverity::ERROR),
         Some("warning") => Ok(DiagnosticSeverity::WARNING),
-        Some("information") => Ok(DiagnosticSeverity::INFORMATION),
+        Some("information" | "info") => Ok(DiagnosticSeverity::INFORMATION),
         Some("hint") => Ok(DiagnosticSeverity::HINT),
         _ => Ok(default_diagnostic_severity()),
     }
@@ -82,6 +88,12 @@ pub(crate) struct ClientInitializationOptions {
         deserialize_with = "deserialize_diagnostic_severity"
     )]
     pub(crate) diagnostic_severity: DiagnosticSeverity,
+    /// 0 checks documents of any size
+    #[serde(default = "default_max_file_size_bytes")]
+    pub(crate) max_file_size_bytes: usize,
+    /// Dictionary IDs used on top of the config's for this session only
+    #[serde(default)]
+    pub(crate) extra_dictionaries: Vec<String>,
 }
 
 impl Default for ClientInitializationOptions {
@@ -92,6 +104,8 @@ impl Default for ClientInitializationOptions {
             config_path: None,
             check_while_typing: true,
             diagnostic_severity: default_diagnostic_severity(),
+            max_file_size_bytes: default_max_file_size_bytes(),
+            extra_dictionaries: Vec::new(),
         }
     }
 }
@@ -145,6 +159,42 @@ mod tests {
         assert_eq!(options.diagnostic_severity, DiagnosticSeverity::WARNING);
     }
 
+    #[test]
+    fn test_diagnostic_severity_values() {
+        for (value, expected) in [
+            ("hint", DiagnosticSeverity::HINT),
+            ("info", DiagnosticSeverity::INFORMATION),
+            ("information", DiagnosticSeverity::INFORMATION),
+            ("warning", DiagnosticSeverity::WARNING),
+            ("error", DiagnosticSeverity::ERROR),
+            ("loud", DiagnosticSeverity::INFORMATION),
+        ] {
+            let json = format!(r#"{{"diagnosticSeverity": "{value}"}}"#);
+            let options: ClientInitializationOptions = serde_json::from_str(&json).unwrap();
+            assert_eq!(options.diagnostic_severity, expected, "{value}");
+        }
+        let options: ClientInitializationOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.diagnostic_severity, DiagnosticSeverity::INFORMATION);
+    }
+
+    #[test]
+    fn test_max_file_size_bytes() {
+        let options: ClientInitializationOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options.max_file_size_bytes, 2 * 1024 * 1024);
+        let json = r#"{"maxFileSizeBytes": 0}"#;
+        let options: ClientInitializationOptions = serde_json::from_str(json).unwrap();
+        assert_eq!(options.max_file_size_bytes, 0);
+    }
+
+    #[test]
+    fn test_extra_dictionaries() {
+        let options: ClientInitializationOptions = serde_json::from_str("{}").unwrap();
+        assert!(options.extra_dictionaries.is_empty());
+        let json = r#"{"extraDictionaries": ["medical"]}"#;
+        let options: ClientInitializationOptions = serde_json::from_str(json).unwrap();
+        assert_eq!(options.extra_dictionaries, ["medical"]);
+    }
+
     #[test]
     fn test_config_path() {
         let json = r#"{"configPath": "toolConfig/codebook.toml"}"#;
@@ -2,3 +2,5 @@ pub mod file_cache;
 mod init_options;
 pub mod lsp;
 pub mod lsp_logger;
+mod position_encoding;
+mod workspace;
@@ -1,9 +1,14 @@
+use crate::git_diff::ChangedLines;
+use crate::lsp::language_hint;
 use codebook::Codebook;
-use codebook_config::{CodebookConfig, CodebookConfigFile};
+use codebook::confusables::find_confusable;
+use codebook::parser::{IssueKind, TextRange};
+use codebook_config::{CodebookConfig, CodebookConfigFile, ConfigError, ConfigSettings};
 use globset::Glob;
 use ignore::WalkBuilder;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::IsTerminal;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use string_offsets::{AllConfig, StringOffsets};
@@ -14,6 +19,12 @@ macro_rules! err {
     };
 }
 
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        eprintln!("{} {}", Paint::stderr().yellow("warning:"), format_args!($($arg)*))
+    };
+}
+
 /// Minimal ANSI styling without a dependency. Enabled per stream only when it
 /// is a terminal and the NO_COLOR convention (https://no-color.org) is unset.
 #[derive(Clone, Copy)]
@@ -56,6 +67,10 @@ impl Paint {
         self.wrap("32", text)
     }
 
+    fn yellow(self, text: &str) -> String {
+        self.wrap("33", text)
+    }
+
     fn cyan(self, text: &str) -> String {
         self.wrap("36", text)
     }
@@ -90,14 +105,128 @@ fn relative_to_root(root_canonical: Option<&Path>, path: &Path) -> String {
         .unwrap_or_else(|| path.to_string_lossy().into_owned())
 }
 
-pub fn run_lint(files: &[String], root: &Path, unique: bool, suggest: bool) -> LintResult {
-    let config = match CodebookConfigFile::load(Some(root)) {
+/// Which files are checked and how findings are reported.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LintOptions {
+    /// Check files even if `ignore_paths` or `include_paths` leave them out
+    pub force: bool,
+    /// Only report each misspelled word once, across all files
+    pub unique: bool,
+    /// Show spelling suggestions for each misspelled word
+    pub suggest: bool,
+    /// Print the offending line with the word underlined under each finding
+    pub snippets: bool,
+    /// Print a table of misspelled words by frequency instead of each finding
+    pub summary: bool,
+}
+
+/// Misspelled words seen so far in a run, keyed by lowercased word.
+#[derive(Default)]
+struct WordTally {
+    /// Words already reported, for `unique`
+    seen: HashSet<String>,
+    /// Every occurrence, for `summary`
+    counts: HashMap<String, WordCount>,
+}
+
+struct WordCount {
+    /// The word as first written
+    word: String,
+    occurrences: usize,
+    /// "file:line" of the first occurrence
+    first_seen: String,
+}
+
+impl WordTally {
+    fn count(&mut self, word: &str, location: impl FnOnce() -> String) {
+        self.counts
+            .entry(word.to_lowercase())
+            .and_modify(|c| c.occurrences += 1)
+            .or_insert_with(|| WordCount {
+                word: word.to_string(),
+                occurrences: 1,
+                first_seen: location(),
+            });
+    }
+
+    /// Print the words from most to least frequent, ties alphabetically.
+    fn print_summary(&self, paint: Paint) {
+        let mut counts: Vec<&WordCount> = self.counts.values().collect();
+        counts.sort_by(|a, b| {
+            b.occurrences
+                .cmp(&a.occurrences)
+                .then_with(|| a.word.to_lowercase().cmp(&b.word.to_lowercase()))
+        });
+        let word_width = counts
+            .iter()
+            .map(|c| c.word.chars().count())
+            .max()
+            .unwrap_or(0);
+        for c in counts {
+            let pad = " ".repeat(word_width - c.word.chars().count());
+            println!(
+                "{:>6}  {}{pad}  {}",
+                c.occurrences,
+                paint.red_bold(&c.word),
+                paint.dim(&c.first_seen)
+            );
+        }
+        println!();
+    }
+}
+
+/// Load the config for a command: the project config at `config_path`
+/// (`--config`) if given, otherwise the one found by searching up from `root`.
+pub fn load_config(
+    root: &Path,
+    config_path: Option<&Path>,
+) -> Result<CodebookConfigFile, ConfigError> {
+    CodebookConfigFile::load_with_overrides(Some(root), None, config_path.map(Path::to_path_buf))
+}
+
+/// Lint `files`, with `overrides` (from command-line flags) merged over the
+/// loaded config for this run only. With `diff_base`, only lines changed
+/// since that git revision are reported, and no `files` means every changed
+/// file.
+pub fn run_lint(
+    files: &[String],
+    root: &Path,
+    config_path: Option<&Path>,
+    overrides: Option<ConfigSettings>,
+    diff_base: Option<&str>,
+    options: LintOptions,
+) -> LintResult {
+    let config = match load_config(root, config_path) {
         Ok(c) => Arc::new(c),
         Err(e) => {
             err!("failed to load config: {e}");
             return LintResult::Failure;
         }
     };
+    if let Some(overrides) = overrides {
+        config.merge_cli_overrides(overrides);
+    }
+
+    let changed = match diff_base.map(|base| ChangedLines::from_git(root, base)) {
+        Some(Ok(changed)) => Some(changed),
+        Some(Err(e)) => {
+            err!("{e}");
+            return LintResult::Failure;
+        }
+        None => None,
+    };
+    let explicit = named_files(files, root);
+    let changed_files: Vec<String>;
+    let files = match &changed {
+        Some(changed) if files.is_empty() => {
+            changed_files = changed
+                .files()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect();
+            &changed_files[..]
+        }
+        _ => files,
+    };
 
     print_config_source(&config);
     eprintln!();
@@ -109,32 +238,54 @@ pub fn run_lint(files: &[String], root: &Path, unique: bool, suggest: bool) -> L
 
     let (resolved, mut had_failure) = resolve_paths(files, root);
 
-    let mut seen_words: HashSet<String> = HashSet::new();
+    let mut tally = WordTally::default();
     let mut total_errors = 0;
     let mut files_with_errors: usize = 0;
     let mut ignored = 0;
     let mut excluded = 0;
+    let mut unchanged = 0;
 
+    learn_project_symbols(
+        &resolved,
+        root_canonical.as_deref(),
+        &codebook,
+        |path, rel_path| {
+            options.force
+                || explicit.contains(path)
+                || (!config.should_ignore_path(rel_path) && config.should_include_path(rel_path))
+        },
+    );
     for path in &resolved {
         let relative = relative_to_root(root_canonical.as_deref(), path);
         let rel_path = Path::new(&relative);
+        let force = options.force || explicit.contains(path);
 
-        if config.should_ignore_path(rel_path) {
+        if !force && config.should_ignore_path(rel_path) {
             ignored += 1;
             continue;
         }
-        if !config.should_include_path(rel_path) {
+        if !force && !config.should_include_path(rel_path) {
             excluded += 1;
             continue;
         }
+        let lines = match &changed {
+            Some(changed) => match changed.lines(path) {
+                Some(lines) => Some(lines),
+                None => {
+                    unchanged += 1;
+                    continue;
+                }
+            },
+            None => None,
+        };
 
         let (errors, file_failure) = check_file(
             path,
             &relative,
+            lines,
             &codebook,
-            &mut seen_words,
-            unique,
-            suggest,
+            &mut tally,
+            LintOptions { force, ..options },
             Paint::stdout(),
         );
         had_failure |= file_failure;
@@ -144,13 +295,25 @@ pub fn run_lint(files: &[String], root: &Path, unique: bool, suggest: bool) -> L
         }
     }
 
+    had_failure |= report_dictionary_errors(&codebook, config.as_ref());
+
+    if options.summary {
+        tally.print_summary(Paint::stdout());
+    }
+
     let total = resolved.len();
-    let checked = total - ignored - excluded;
-    let unique_label = if unique { "unique " } else { "" };
+    let checked = total - ignored - excluded - unchanged;
+    let unique_label = if options.unique { "unique " } else { "" };
     let paint = Paint::stderr();
-    eprintln!(
-        "Out of {total} total file(s), checked {checked}, ignored {ignored}, and excluded {excluded}."
-    );
+    if changed.is_some() {
+        eprintln!(
+            "Out of {total} total file(s), checked {checked}, ignored {ignored}, excluded {excluded}, and skipped {unchanged} without changes."
+        );
+    } else {
+        eprintln!(
+            "Out of {total} total file(s), checked {checked}, ignored {ignored}, and excluded {excluded}."
+        );
+    }
     let summary = format!(
         "Found {total_errors} {unique_label}spelling error(s) in {files_with_errors} file(s)."
     );
@@ -169,18 +332,477 @@ pub fn run_lint(files: &[String], root: &Path, unique: bool, suggest: bool) -> L
     }
 }
 
+/// Spell-checks text read from stdin, for editors and pipelines that don't
+/// want to write a temp file. `filename` picks the language and is matched
+/// against ignore/include paths; line and column numbers refer to the stdin
+/// content.
+pub fn run_lint_stdin(
+    filename: Option<&str>,
+    root: &Path,
+    config_path: Option<&Path>,
+    overrides: Option<ConfigSettings>,
+    options: LintOptions,
+) -> LintResult {
+    let text = match std::io::read_to_string(std::io::stdin()) {
+        Ok(t) => t,
+        Err(e) => {
+            err!("failed to read stdin: {e}");
+            return LintResult::Failure;
+        }
+    };
+
+    let config = match load_config(root, config_path) {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            err!("failed to load config: {e}");
+            return LintResult::Failure;
+        }
+    };
+    if let Some(overrides) = overrides {
+        config.merge_cli_overrides(overrides);
+    }
+    let codebook = Codebook::new(config.clone());
+
+    let relative =
+        filename.map(|f| relative_to_root(root.canonicalize().ok().as_deref(), Path::new(f)));
+    let mut tally = WordTally::default();
+    let errors = check_text(
+        &text,
+        relative.as_deref(),
+        None,
+        &codebook,
+        &mut tally,
+        LintOptions {
+            unique: false,
+            ..options
+        },
+        Paint::stdout(),
+    );
+    if options.summary {
+        tally.print_summary(Paint::stdout());
+    }
+
+    if report_dictionary_errors(&codebook, config.as_ref()) {
+        LintResult::Failure
+    } else if errors > 0 {
+        LintResult::Errors
+    } else {
+        LintResult::Clean
+    }
+}
+
+/// Corrects misspellings that have a clear fix (see `Codebook::autofix`),
+/// rewriting the files when `write` is set and only reporting the changes
+/// otherwise. Misspellings left alone make the run exit with `Errors`.
+pub fn run_fix(
+    files: &[String],
+    root: &Path,
+    config_path: Option<&Path>,
+    write: bool,
+) -> LintResult {
+    let config = match load_config(root, config_path) {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            err!("failed to load config: {e}");
+            return LintResult::Failure;
+        }
+    };
+    let codebook = Codebook::new(config.clone());
+    let root_canonical = root.canonicalize().ok();
+    let (resolved, mut had_failure) = resolve_paths(files, root);
+    let paint = Paint::stdout();
+
+    let mut total_fixed = 0;
+    let mut total_unfixed = 0;
+    let mut files_changed: usize = 0;
+    learn_project_symbols(
+        &resolved,
+        root_canonical.as_deref(),
+        &codebook,
+        |_, rel_path| !config.should_ignore_path(rel_path) && config.should_include_path(rel_path),
+    );
+    for path in &resolved {
+        let relative = relative_to_root(root_canonical.as_deref(), path);
+        let rel_path = Path::new(&relative);
+        if config.should_ignore_path(rel_path) || !config.should_include_path(rel_path) {
+            continue;
+        }
+        let Some((fixed, unfixed)) = fix_file(path, &relative, &codebook, write, paint) else {
+            had_failure = true;
+            continue;
+        };
+        total_fixed += fixed;
+        total_unfixed += unfixed;
+        if fixed > 0 {
+            files_changed += 1;
+        }
+    }
+
+    had_failure |= report_dictionary_errors(&codebook, config.as_ref());
+
+    let verb = if write { "Fixed" } else { "Would fix" };
+    eprintln!(
+        "{verb} {total_fixed} word(s) in {files_changed} file(s); left {total_unfixed} without a clear fix."
+    );
+    if !write && total_fixed > 0 {
+        eprintln!("Run again with --write to apply the fixes.");
+    }
+
+    if had_failure {
+        LintResult::Failure
+    } else if total_unfixed > 0 {
+        LintResult::Errors
+    } else {
+        LintResult::Clean
+    }
+}
+
+/// Autofixes a single file, printing each fix and each word left alone.
+///
+/// Returns `(fixed, unfixed)` word counts, or None when the file could not
+/// be read or written. Binary / non-UTF-8 files are skipped as clean.
+fn fix_file(
+    path: &Path,
+    relative: &str,
+    codebook: &Codebook,
+    write: bool,
+    paint: Paint,
+) -> Option<(usize, usize)> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return Some((0, 0)),
+        Err(e) => {
+            err!("{}: {e}", path.display());
+            return None;
+        }
+    };
+
+    let result = codebook.autofix(&text, None, Some(relative));
+    if result.fixed.is_empty() && result.unfixed.is_empty() {
+        return Some((0, 0));
+    }
+    println!(
+        "{}",
+        paint.bold(relative.strip_prefix("./").unwrap_or(relative))
+    );
+    for (word, replacement) in &result.fixed {
+        println!(
+            "  {}  -> {}",
+            paint.red_bold(word),
+            paint.green(replacement)
+        );
+    }
+    for word in &result.unfixed {
+        println!(
+            "  {}  {}",
+            paint.red_bold(word),
+            paint.dim("(no clear fix)")
+        );
+    }
+    println!();
+
+    if write
+        && !result.fixed.is_empty()
+        && let Err(e) = std::fs::write(path, &result.text)
+    {
+        err!("{}: {e}", path.display());
+        return None;
+    }
+    Some((result.fixed.len(), result.unfixed.len()))
+}
+
+/// `lint --interactive`: walks through every misspelling in `files` and
+/// asks what to do with it: apply one of the suggestions, add the word to
+/// the project dictionary, ignore the word for the rest of the run, or skip
+/// this occurrence. Accepted fixes are written back to each file, and added
+/// words are saved to the config once at the end. Choices are read from
+/// stdin. Files are chosen as in `run_lint`.
+pub fn run_lint_interactive(
+    files: &[String],
+    root: &Path,
+    config_path: Option<&Path>,
+    overrides: Option<ConfigSettings>,
+    force: bool,
+) -> LintResult {
+    let config = match load_config(root, config_path) {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            err!("failed to load config: {e}");
+            return LintResult::Failure;
+        }
+    };
+    if let Some(overrides) = overrides {
+        config.merge_cli_overrides(overrides);
+    }
+    let codebook = Codebook::new(config.clone());
+    let root_canonical = root.canonicalize().ok();
+    let explicit = named_files(files, root);
+    let (resolved, mut had_failure) = resolve_paths(files, root);
+    let paint = Paint::stdout();
+    let mut input = std::io::stdin().lock();
+
+    let mut review = Review::default();
+    let mut files_changed: usize = 0;
+    learn_project_symbols(
+        &resolved,
+        root_canonical.as_deref(),
+        &codebook,
+        |path, rel_path| {
+            force
+                || explicit.contains(path)
+                || (!config.should_ignore_path(rel_path) && config.should_include_path(rel_path))
+        },
+    );
+    for path in &resolved {
+        if review.quit {
+            break;
+        }
+        let relative = relative_to_root(root_canonical.as_deref(), path);
+        let rel_path = Path::new(&relative);
+        let force = force || explicit.contains(path);
+        if !force && (config.should_ignore_path(rel_path) || !config.should_include_path(rel_path))
+        {
+            continue;
+        }
+        match review_file(path, &relative, &codebook, &mut review, &mut input, paint) {
+            Some(0) => {}
+            Some(_) => files_changed += 1,
+            None => had_failure = true,
+        }
+    }
+
+    if !review.added.is_empty() {
+        let words: Vec<&str> = review.added.iter().map(String::as_str).collect();
+        match config.add_words(&words).and_then(|_| config.save()) {
+            Ok(()) => match config.project_config_path() {
+                Some(p) => eprintln!("Added {} word(s) to {}", words.len(), p.display()),
+                None => eprintln!("Added {} word(s)", words.len()),
+            },
+            Err(e) => {
+                err!("failed to save config: {e}");
+                had_failure = true;
+            }
+        }
+    }
+
+    had_failure |= report_dictionary_errors(&codebook, config.as_ref());
+    eprintln!("Fixed {} word(s) in {files_changed} file(s).", review.fixed);
+
+    if had_failure {
+        LintResult::Failure
+    } else {
+        LintResult::Clean
+    }
+}
+
+/// Decisions carried across files in `run_lint_interactive`.
+#[derive(Default)]
+struct Review {
+    /// Words to add to the project dictionary when the run ends
+    added: Vec<String>,
+    /// Lowercased words not to ask about again: added or ignored
+    settled: HashSet<String>,
+    /// Occurrences replaced so far
+    fixed: usize,
+    /// Set when the user quits or input runs out
+    quit: bool,
+}
+
+/// An answer to one prompt in `review_file`.
+#[derive(Debug, PartialEq)]
+enum Choice {
+    /// Apply the suggestion at this index
+    Suggestion(usize),
+    Add,
+    Ignore,
+    Skip,
+    Quit,
+}
+
+impl Choice {
+    /// Parses a line of input; suggestions are numbered from 1. An empty
+    /// line skips.
+    fn parse(line: &str, suggestions: usize) -> Option<Self> {
+        match line.trim() {
+            "" | "s" => Some(Self::Skip),
+            "a" => Some(Self::Add),
+            "i" => Some(Self::Ignore),
+            "q" => Some(Self::Quit),
+            n => n
+                .parse::<usize>()
+                .ok()
+                .filter(|n| (1..=suggestions).contains(n))
+                .map(|n| Self::Suggestion(n - 1)),
+        }
+    }
+}
+
+/// Prompts for each misspelling in one file, in document order, and writes
+/// the accepted fixes back to it. Like `Codebook::autofix`, an accepted
+/// suggestion replaces the word everywhere in the file, so an identifier's
+/// uses are renamed with its definition, and the word isn't asked about
+/// again in this file.
+///
+/// Returns the number of occurrences fixed, or None when the file could not
+/// be read or written. Binary / non-UTF-8 files are skipped.
+fn review_file(
+    path: &Path,
+    relative: &str,
+    codebook: &Codebook,
+    review: &mut Review,
+    input: &mut impl std::io::BufRead,
+    paint: Paint,
+) -> Option<usize> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return Some(0),
+        Err(e) => {
+            err!("{}: {e}", path.display());
+            return None;
+        }
+    };
+    let display = relative.strip_prefix("./").unwrap_or(relative);
+    let offsets = StringOffsets::<AllConfig>::new(&text);
+    let mut occurrences: Vec<(&str, Option<&str>, TextRange)> = Vec::new();
+    let locations = codebook.spell_check(&text, None, Some(relative));
+    for wl in &locations {
+        occurrences.extend(
+            wl.locations
+                .iter()
+                .map(|r| (wl.word.as_str(), wl.tag.as_deref(), *r)),
+        );
+    }
+    occurrences.sort_by_key(|(_, _, r)| r.start_byte);
+
+    let mut edits: Vec<(TextRange, String)> = Vec::new();
+    let mut renamed: HashSet<&str> = HashSet::new();
+    for (word, tag, range) in occurrences {
+        if review.quit {
+            break;
+        }
+        if renamed.contains(word) || review.settled.contains(&word.to_lowercase()) {
+            continue;
+        }
+        let pos = offsets.utf8_to_char_pos(range.start_byte.min(text.len()));
+        println!(
+            "{}  {}",
+            paint.dim(&format!("{display}:{}:{}", pos.line + 1, pos.col + 1)),
+            paint.red_bold(word)
+        );
+        print!("{}", render_snippet(&text, range, pos.line + 1, paint));
+        let suggestions = codebook
+            .get_suggestions_in_context(word, &text, range.start_byte, tag)
+            .unwrap_or_default();
+        let numbered: Vec<String> = suggestions
+            .iter()
+            .enumerate()
+            .map(|(i, s)| format!("[{}] {}", i + 1, paint.cyan(s)))
+            .collect();
+        if !numbered.is_empty() {
+            println!("  {}", numbered.join("  "));
+        }
+        println!(
+            "  {}",
+            paint.dim("[a] add to dictionary  [i] ignore  [s] skip  [q] quit")
+        );
+
+        let choice = loop {
+            print!("> ");
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            let mut line = String::new();
+            match input.read_line(&mut line) {
+                Ok(0) | Err(_) => break Choice::Quit,
+                Ok(_) => {}
+            }
+            match Choice::parse(&line, suggestions.len()) {
+                Some(choice) => break choice,
+                None => println!("  {}", paint.yellow("Unrecognized choice")),
+            }
+        };
+        match choice {
+            Choice::Suggestion(i) => {
+                let reported = locations
+                    .iter()
+                    .filter(|wl| wl.word == word)
+                    .flat_map(|wl| wl.locations.iter().copied())
+                    .collect::<Vec<_>>();
+                let ranges = codebook::rename_ranges(&text, word, &reported);
+                review.fixed += ranges.len();
+                edits.extend(ranges.into_iter().map(|r| (r, suggestions[i].clone())));
+                renamed.insert(word);
+            }
+            Choice::Add => {
+                review.settled.insert(word.to_lowercase());
+                review.added.push(word.to_string());
+            }
+            Choice::Ignore => {
+                review.settled.insert(word.to_lowercase());
+            }
+            Choice::Skip => {}
+            Choice::Quit => review.quit = true,
+        }
+        println!();
+    }
+
+    let fixed = edits.len();
+    if fixed > 0
+        && let Err(e) = std::fs::write(path, codebook::apply_edits(&text, edits))
+    {
+        err!("{}: {e}", path.display());
+        return None;
+    }
+    Some(fixed)
+}
+
+/// Learns the project symbols of the files `checked` keeps before checking
+/// any, so a name defined in one file is known in the files checked before
+/// it. Files that can't be read are left for the check to report.
+fn learn_project_symbols(
+    paths: &[PathBuf],
+    root_canonical: Option<&Path>,
+    codebook: &Codebook,
+    checked: impl Fn(&Path, &Path) -> bool,
+) {
+    for path in paths {
+        let relative = relative_to_root(root_canonical, path);
+        if !checked(path, Path::new(&relative)) {
+            continue;
+        }
+        if let Ok(text) = std::fs::read_to_string(path) {
+            codebook.learn_symbols(&text, None, Some(&relative));
+        }
+    }
+}
+
+/// Prints dictionaries that failed to load, as errors under
+/// `strict_dictionaries` and warnings otherwise. Returns true when that
+/// should fail the run.
+fn report_dictionary_errors(codebook: &Codebook, config: &dyn CodebookConfig) -> bool {
+    let errors = codebook.dictionary_load_errors();
+    let strict = config.strict_dictionaries();
+    for error in &errors {
+        if strict {
+            err!("{error}");
+        } else {
+            warning!("{error}");
+        }
+    }
+    strict && !errors.is_empty()
+}
+
 /// Spell-checks a single file and prints any diagnostics to stdout.
 ///
 /// Returns `(error_count, had_io_error)`. `error_count` is 0 if the file was
 /// clean; `had_io_error` is true when the file could not be read. `relative` is
-/// the workspace-relative path used for display and ignore matching.
+/// the workspace-relative path used for display and ignore matching, and
+/// `lines` limits findings as in `check_text`.
 fn check_file(
     path: &Path,
     relative: &str,
+    lines: Option<&[RangeInclusive<usize>]>,
     codebook: &Codebook,
-    seen_words: &mut HashSet<String>,
-    unique: bool,
-    suggest: bool,
+    tally: &mut WordTally,
+    options: LintOptions,
     paint: Paint,
 ) -> (usize, bool) {
     let text = match std::fs::read_to_string(path) {
@@ -195,79 +817,197 @@ fn check_file(
         }
     };
 
-    let display = relative.strip_prefix("./").unwrap_or(relative);
+    let errors = check_text(
+        &text,
+        Some(relative),
+        lines,
+        codebook,
+        tally,
+        options,
+        paint,
+    );
+    (errors, false)
+}
+
+/// Spell-checks `text` and prints any diagnostics to stdout. `relative` is the
+/// workspace-relative path used for display, language detection and ignore
+/// matching; text without one (stdin) is shown as `<stdin>`. When `lines` is
+/// set, only findings on those 1-based lines are reported. Returns the
+/// number of errors printed.
+fn check_text(
+    text: &str,
+    relative: Option<&str>,
+    lines: Option<&[RangeInclusive<usize>]>,
+    codebook: &Codebook,
+    tally: &mut WordTally,
+    options: LintOptions,
+    paint: Paint,
+) -> usize {
+    let display = relative.map_or("<stdin>", |r| r.strip_prefix("./").unwrap_or(r));
 
     // Build the offset table once per file
-    let offsets = StringOffsets::<AllConfig>::new(&text);
-    let mut locations = codebook.spell_check(&text, None, Some(relative));
-    // Sort inner locations first (HashSet iteration order is nondeterministic),
-    // then sort the outer list by first occurrence in the file.
-    for wl in &mut locations {
-        wl.locations.sort_by_key(|r| r.start_byte);
-    }
-    locations.sort_by_key(|l| l.locations.first().map(|r| r.start_byte).unwrap_or(0));
+    let offsets = StringOffsets::<AllConfig>::new(text);
+    // Already in document order
+    let locations = if options.force {
+        codebook.spell_check_ignoring_paths(text, None, relative)
+    } else {
+        codebook.spell_check(text, None, relative)
+    };
+
+    // Without a diff every line counts as changed
+    let on_changed_line =
+        |line: usize| lines.is_none_or(|ranges| ranges.iter().any(|r| r.contains(&line)));
 
     // Collect hits first so we can compute pad_len for column alignment. The
     // unique check is per-word, so all ranges of a word are included or skipped
     // together.
-    let mut hits: Vec<(String, &str, Option<Vec<String>>)> = Vec::new();
+    let mut hits: Vec<Hit> = Vec::new();
+    if options.summary {
+        let mut count = 0;
+        for wl in &locations {
+            for range in &wl.locations {
+                let pos = offsets.utf8_to_char_pos(range.start_byte.min(text.len()));
+                if on_changed_line(pos.line + 1) {
+                    tally.count(&wl.word, || format!("{display}:{}", pos.line + 1));
+                    count += 1;
+                }
+            }
+        }
+        return count;
+    }
+
     for wl in &locations {
-        if unique && !seen_words.insert(wl.word.to_lowercase()) {
+        // utf8_to_char_pos returns 0-based line and Unicode-char column.
+        let mut ranges: Vec<_> = wl
+            .locations
+            .iter()
+            .map(|range| {
+                (
+                    range,
+                    offsets.utf8_to_char_pos(range.start_byte.min(text.len())),
+                )
+            })
+            .filter(|(_, pos)| on_changed_line(pos.line + 1))
+            .collect();
+        if ranges.is_empty() {
             continue;
         }
 
-        let mut suggestions = if suggest {
-            codebook.get_suggestions(wl.word.as_str())
+        // If unique mode: Only emit the first occurrence of each word.
+        if options.unique {
+            if !tally.seen.insert(wl.word.to_lowercase()) {
+                continue;
+            }
+            ranges.truncate(1);
+        }
+
+        let mut suggestions = if options.suggest {
+            codebook.get_suggestions_in_context(
+                wl.word.as_str(),
+                text,
+                ranges[0].0.start_byte,
+                wl.tag.as_deref(),
+            )
         } else {
             None
         };
-
-        // If unique mode: Only emit the first occurrence of each word.
-        let ranges = if unique {
-            &wl.locations[..1]
+        let confusable = (wl.kind == IssueKind::Confusable)
+            .then(|| find_confusable(&wl.word))
+            .flatten();
+        let hint = if let Some(confusable) = confusable {
+            format!(" (mixed scripts: {confusable})")
+        } else if codebook.is_flagged_word(&wl.word, relative) {
+            // Flagged words are known words, so guessing a language is moot
+            String::new()
         } else {
-            &wl.locations[..]
+            language_hint(&codebook.guess_languages(&wl.word))
         };
 
-        for (i, range) in ranges.iter().enumerate() {
-            // utf8_to_char_pos returns 0-based line and Unicode-char column.
-            let pos = offsets.utf8_to_char_pos(range.start_byte.min(text.len()));
-
+        let last = ranges.len() - 1;
+        for (i, (range, pos)) in ranges.into_iter().enumerate() {
             // Move out of `suggestions` on the last iteration to avoid a clone.
-            let sugg = if i + 1 < ranges.len() {
+            let sugg = if i < last {
                 suggestions.clone()
             } else {
                 suggestions.take()
             };
 
-            hits.push((
-                format!("{}:{}", pos.line + 1, pos.col + 1),
-                wl.word.as_str(),
-                sugg,
-            ));
+            hits.push(Hit {
+                linecol: format!("{}:{}", pos.line + 1, pos.col + 1),
+                line: pos.line + 1,
+                word: wl.word.as_str(),
+                hint: hint.clone(),
+                range: *range,
+                suggestions: sugg,
+            });
         }
     }
 
     if hits.is_empty() {
-        return (0, false);
+        return 0;
     }
 
-    let pad_len = hits.iter().map(|(lc, _, _)| lc.len()).max().unwrap_or(0);
+    let pad_len = hits.iter().map(|h| h.linecol.len()).max().unwrap_or(0);
 
     println!("{}", paint.bold(display));
-    for (linecol, word, suggestions) in &hits {
-        let pad = " ".repeat(pad_len - linecol.len());
-        let loc = paint.dim(&format!("{display}:{linecol}"));
-        let word = paint.red_bold(word);
-        if let Some(s) = suggestions {
+    for hit in &hits {
+        let pad = " ".repeat(pad_len - hit.linecol.len());
+        let loc = paint.dim(&format!("{display}:{}", hit.linecol));
+        let mut word = paint.red_bold(hit.word);
+        if !hit.hint.is_empty() {
+            word.push_str(&paint.dim(&hit.hint));
+        }
+        if let Some(s) = &hit.suggestions {
             println!("  {loc}{pad}  {word}  -> {}", paint.cyan(&s.join(", ")));
         } else {
             println!("  {loc}{pad}  {word}");
         }
+        if options.snippets {
+            print!("{}", render_snippet(text, hit.range, hit.line, paint));
+        }
     }
     println!();
 
-    (hits.len(), false)
+    hits.len()
+}
+
+/// One misspelling occurrence to print.
+struct Hit<'a> {
+    /// "line:col", 1-based
+    linecol: String,
+    line: usize,
+    word: &'a str,
+    /// Language guess for the word, from `language_hint`
+    hint: String,
+    range: TextRange,
+    suggestions: Option<Vec<String>>,
+}
+
+/// Renders the line containing `range` with the word underlined, rustc-style:
+///
+/// ```text
+///      3 | let wrod = 1;
+///        |     ^^^^
+/// ```
+fn render_snippet(text: &str, range: TextRange, line: usize, paint: Paint) -> String {
+    let line_text = range.line_text(text);
+    let start = range.start_byte.min(text.len());
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    // Keep tabs so the carets line up however the terminal renders them
+    let indent: String = text[line_start..start]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let end = range.end_byte.clamp(start, line_start + line_text.len());
+    let carets = "^".repeat(text[start..end].chars().count().max(1));
+    let gutter = format!("{line:>5}");
+    let blank = " ".repeat(gutter.len());
+    format!(
+        "  {} {line_text}\n  {} {indent}{}\n",
+        paint.dim(&format!("{gutter} |")),
+        paint.dim(&format!("{blank} |")),
+        paint.red(&carets),
+    )
 }
 
 /// Prints which config file is being used, or notes that the default is active.
@@ -354,6 +1094,17 @@ fn resolve_paths(patterns: &[String], root: &Path) -> (Vec<PathBuf>, bool) {
     (paths, had_failure)
 }
 
+/// The `patterns` that name a file directly, as `resolve_paths` resolves
+/// them. These are checked whatever `ignore_paths` and `include_paths` say;
+/// only directory and glob expansions follow them.
+fn named_files(patterns: &[String], root: &Path) -> HashSet<PathBuf> {
+    patterns
+        .iter()
+        .map(|pattern| root.join(pattern))
+        .filter(|p| p.is_file())
+        .collect()
+}
+
 /// Walks using the given `WalkBuilder`, collecting all files into `out`.
 /// Respects `.gitignore` rules (including nested) and skips hidden
 /// files/directories. Returns `true` if any I/O error occurred.
@@ -409,6 +1160,52 @@ mod tests {
         assert!(err_missing);
     }
 
+    #[test]
+    fn test_named_files_bypass_ignore_paths() {
+        let dir = tempdir().unwrap();
+        let generated = dir.path().join("generated");
+        fs::create_dir_all(&generated).unwrap();
+        let file = generated.join("foo.rs");
+        fs::write(&file, "// actualbad\n").unwrap();
+
+        let patterns = ["generated/foo.rs".to_string(), "generated".to_string()];
+        let (resolved, _) = resolve_paths(&patterns, dir.path());
+        assert_eq!(resolved, vec![file.clone()]);
+        // Only the file named directly counts, not the directory holding it
+        assert_eq!(
+            named_files(&patterns, dir.path()),
+            HashSet::from([file.clone()])
+        );
+        assert!(named_files(&patterns[1..], dir.path()).is_empty());
+
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let config = CodebookConfigMemory::new(ConfigSettings {
+            ignore_paths: vec!["generated/**".to_string()],
+            ..Default::default()
+        });
+        let cb = Codebook::with_dictionary_dir(Arc::new(config), Some(fixtures));
+        let check = |force| {
+            let options = LintOptions {
+                force,
+                ..Default::default()
+            };
+            let mut tally = WordTally::default();
+            check_file(
+                &file,
+                "generated/foo.rs",
+                None,
+                &cb,
+                &mut tally,
+                options,
+                Paint(false),
+            )
+            .0
+        };
+        assert_eq!(check(false), 0);
+        assert_eq!(check(true), 1);
+    }
+
     #[test]
     fn test_check_file_logic() {
         let dir = tempdir().unwrap();
@@ -423,31 +1220,45 @@ mod tests {
             Arc::new(CodebookConfigMemory::default()),
             Some(fixtures),
         );
-        let mut seen = HashSet::new();
+        let mut seen = WordTally::default();
 
         // Test basic flagging and multi-occurrence counting
-        let (count, err) = check_file(&f, "test.txt", &cb, &mut seen, false, false, Paint(false));
+        let (count, err) = check_file(
+            &f,
+            "test.txt",
+            None,
+            &cb,
+            &mut seen,
+            LintOptions::default(),
+            Paint(false),
+        );
         assert_eq!(count, 2);
         assert!(!err);
 
         // Test unique mode
-        let mut seen_unique = HashSet::new();
+        let mut seen_unique = WordTally::default();
         let (c1, _) = check_file(
             &f,
             "f1.txt",
+            None,
             &cb,
             &mut seen_unique,
-            true,
-            false,
+            LintOptions {
+                unique: true,
+                ..Default::default()
+            },
             Paint(false),
         );
         let (c2, _) = check_file(
             &f,
             "f2.txt",
+            None,
             &cb,
             &mut seen_unique,
-            true,
-            false,
+            LintOptions {
+                unique: true,
+                ..Default::default()
+            },
             Paint(false),
         );
         assert_eq!(c1, 1, "Should flag word once");
@@ -457,20 +1268,301 @@ mod tests {
         let (_, err_io) = check_file(
             &dir.path().join("missing"),
             "!",
+            None,
             &cb,
             &mut seen,
-            false,
-            false,
+            LintOptions::default(),
             Paint(false),
         );
         assert!(err_io);
     }
 
+    #[test]
+    fn test_load_config_from_path() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("codebook.toml"), "").unwrap();
+        let strict = root.join("ci").join("strict.toml");
+        let lenient = root.join("ci").join("lenient.toml");
+        fs::create_dir_all(strict.parent().unwrap()).unwrap();
+        fs::write(&strict, "use_global = false\n").unwrap();
+        fs::write(&lenient, "use_global = false\nwords = [\"actualbad\"]\n").unwrap();
+        let f = root.join("notes.txt");
+        fs::write(&f, "actualbad\n").unwrap();
+
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let count = |config_path: &Path| {
+            let config = load_config(root, Some(config_path)).unwrap();
+            assert_eq!(config.project_config_path().as_deref(), Some(config_path));
+            let cb = Codebook::with_dictionary_dir(Arc::new(config), Some(fixtures.clone()));
+            check_file(
+                &f,
+                "notes.txt",
+                None,
+                &cb,
+                &mut WordTally::default(),
+                LintOptions::default(),
+                Paint(false),
+            )
+            .0
+        };
+        // Same file, same root: only the chosen config differs
+        assert_eq!(count(&strict), 1);
+        assert_eq!(count(&lenient), 0);
+    }
+
+    #[test]
+    fn test_check_text_uses_filename_for_language() {
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let cb = Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures),
+        );
+        // As Rust, a call using existing names isn't checked; without a
+        // file name (plain stdin) the text is checked word by word.
+        let text = "fooo(quxx);";
+        let as_rust = check_text(
+            text,
+            Some("src/lib.rs"),
+            None,
+            &cb,
+            &mut WordTally::default(),
+            LintOptions::default(),
+            Paint(false),
+        );
+        assert_eq!(as_rust, 0);
+        let as_text = check_text(
+            text,
+            None,
+            None,
+            &cb,
+            &mut WordTally::default(),
+            LintOptions::default(),
+            Paint(false),
+        );
+        assert_eq!(as_text, 2);
+    }
+
+    #[test]
+    fn test_fix_file() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("notes.txt");
+        fs::write(&f, "Recieve the xyzzyq").unwrap();
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let cb = Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures),
+        );
+
+        // Without write, the file is untouched
+        assert_eq!(
+            fix_file(&f, "notes.txt", &cb, false, Paint(false)),
+            Some((1, 1))
+        );
+        assert_eq!(fs::read_to_string(&f).unwrap(), "Recieve the xyzzyq");
+
+        assert_eq!(
+            fix_file(&f, "notes.txt", &cb, true, Paint(false)),
+            Some((1, 1))
+        );
+        assert_eq!(fs::read_to_string(&f).unwrap(), "Receive the xyzzyq");
+
+        let missing = dir.path().join("missing.txt");
+        assert_eq!(
+            fix_file(&missing, "missing.txt", &cb, true, Paint(false)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_review_file() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("notes.txt");
+        fs::write(
+            &f,
+            "Recieve the xyzzyq, then xyzzyq and the qwvbnm\nplus qwvbnm",
+        )
+        .unwrap();
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let cb = Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures),
+        );
+        let first = cb.get_suggestions("Recieve").unwrap().remove(0);
+
+        // Fix the first word, ignore the next then stop at the third: the
+        // ignored word isn't asked about again
+        let mut review = Review::default();
+        let mut input = std::io::Cursor::new("1\ni\nbogus\nq\n");
+        assert_eq!(
+            review_file(&f, "notes.txt", &cb, &mut review, &mut input, Paint(false)),
+            Some(1)
+        );
+        assert!(review.quit);
+        assert_eq!(
+            fs::read_to_string(&f).unwrap(),
+            format!("{first} the xyzzyq, then xyzzyq and the qwvbnm\nplus qwvbnm")
+        );
+
+        // Adding a word settles its other occurrences; input running out quits
+        let mut review = Review::default();
+        let mut input = std::io::Cursor::new("s\na\n");
+        assert_eq!(
+            review_file(&f, "notes.txt", &cb, &mut review, &mut input, Paint(false)),
+            Some(0)
+        );
+        assert_eq!(review.added, vec!["xyzzyq"]);
+        assert!(review.quit);
+    }
+
+    #[test]
+    fn test_review_file_renames_uses() {
+        let dir = tempdir().unwrap();
+        let f = dir.path().join("main.rs");
+        fs::write(
+            &f,
+            "fn calulate_total() {}\nfn main() { calulate_total(); }\n",
+        )
+        .unwrap();
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let cb = Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures),
+        );
+        let first = cb.get_suggestions("calulate").unwrap().remove(0);
+
+        // One answer fixes the definition and the call, which isn't asked about
+        let mut review = Review::default();
+        let mut input = std::io::Cursor::new("1\n");
+        assert_eq!(
+            review_file(&f, "main.rs", &cb, &mut review, &mut input, Paint(false)),
+            Some(2)
+        );
+        assert!(!review.quit);
+        assert_eq!(
+            fs::read_to_string(&f).unwrap(),
+            format!("fn {first}_total() {{}}\nfn main() {{ {first}_total(); }}\n")
+        );
+    }
+
+    #[test]
+    fn test_choice_parse() {
+        assert_eq!(Choice::parse("2\n", 3), Some(Choice::Suggestion(1)));
+        assert_eq!(Choice::parse("4", 3), None);
+        assert_eq!(Choice::parse("0", 3), None);
+        assert_eq!(Choice::parse("\n", 3), Some(Choice::Skip));
+        assert_eq!(Choice::parse(" a ", 0), Some(Choice::Add));
+        assert_eq!(Choice::parse("x", 0), None);
+    }
+
+    #[test]
+    fn test_summary_counts_every_occurrence() {
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let cb = Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures),
+        );
+        let options = LintOptions {
+            summary: true,
+            ..Default::default()
+        };
+        let mut tally = WordTally::default();
+        let first = check_text(
+            "wrod\nactualbad wrod\n",
+            Some("a.txt"),
+            None,
+            &cb,
+            &mut tally,
+            options,
+            Paint(false),
+        );
+        let second = check_text(
+            "Wrod",
+            Some("b.txt"),
+            None,
+            &cb,
+            &mut tally,
+            options,
+            Paint(false),
+        );
+        assert_eq!((first, second), (3, 1));
+
+        let wrod = &tally.counts["wrod"];
+        assert_eq!(wrod.word, "wrod");
+        assert_eq!(wrod.occurrences, 3);
+        assert_eq!(wrod.first_seen, "a.txt:1");
+        let actualbad = &tally.counts["actualbad"];
+        assert_eq!(actualbad.occurrences, 1);
+        assert_eq!(actualbad.first_seen, "a.txt:2");
+    }
+
+    #[test]
+    fn test_check_text_only_changed_lines() {
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let cb = Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures),
+        );
+        let text = "wrod\nactualbad wrod\nxyzzyq\n";
+        let changed = [2..=2];
+        let check = |options, tally: &mut WordTally| {
+            check_text(
+                text,
+                Some("a.txt"),
+                Some(&changed),
+                &cb,
+                tally,
+                options,
+                Paint(false),
+            )
+        };
+        assert_eq!(check(LintOptions::default(), &mut WordTally::default()), 2);
+
+        // A word first seen on an unchanged line is still reported once
+        let unique = LintOptions {
+            unique: true,
+            ..Default::default()
+        };
+        let mut tally = WordTally::default();
+        assert_eq!(check(unique, &mut tally), 2);
+        assert!(!tally.seen.contains("xyzzyq"));
+
+        let summary = LintOptions {
+            summary: true,
+            ..Default::default()
+        };
+        let mut tally = WordTally::default();
+        assert_eq!(check(summary, &mut tally), 2);
+        assert_eq!(tally.counts["wrod"].first_seen, "a.txt:2");
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let text = "fn main() {\n\tlet wrod = 1;\n}";
+        let start = text.find("wrod").unwrap();
+        let range = TextRange {
+            start_byte: start,
+            end_byte: start + 4,
+        };
+        assert_eq!(
+            render_snippet(text, range, 2, Paint(false)),
+            "      2 | \tlet wrod = 1;\n        | \t    ^^^^\n"
+        );
+    }
+
     #[test]
     fn test_unicode_line_col() {
         let cases = [
             ("actualbad", 0, 1, 1),        // Start
             ("ok\nactualbad", 3, 2, 1),    // Newline
+            ("ok\r\nactualbad", 4, 2, 1),  // Windows newline
             ("résumé actualbad", 9, 1, 8), // Multi-byte chars (é is 2 bytes)
             ("🦀 actualbad", 5, 1, 3),     // Emoji (4 bytes, 1 char)
         ];
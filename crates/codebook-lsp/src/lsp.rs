@@ -1,16 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr as _;
 use std::sync::{Arc, Mutex, OnceLock, RwLock};
 use std::time::{Duration, Instant};
 
-use codebook::parser::get_word_from_string;
 use codebook::queries::LanguageType;
-use string_offsets::AllConfig;
 use string_offsets::Pos;
-use string_offsets::StringOffsets;
 
 use log::error;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::task;
 use tower_lsp::jsonrpc::Result as RpcResult;
@@ -18,15 +16,25 @@ use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
 use codebook::Codebook;
-use codebook_config::{CodebookConfig, CodebookConfigFile};
+use codebook::confusables::find_confusable;
+use codebook::parser::IssueKind;
+use codebook_config::{CodebookConfig, CodebookConfigFile, ConfigError};
 use log::{debug, info};
 
 use crate::file_cache::TextDocumentCache;
 use crate::init_options::ClientInitializationOptions;
 use crate::lsp_logger;
+use crate::position_encoding::{OffsetTable, PositionEncoding};
+use crate::workspace::{DictionaryManagers, WorkspaceRoot, WorkspaceRoots};
 
 const SOURCE_NAME: &str = "Codebook";
 
+/// Diagnostic codes, stable so editors can filter on them.
+const CODE_SPELLING: &str = "codebook.spelling";
+const CODE_FLAGGED: &str = "codebook.flagged";
+const CODE_CONFUSABLE: &str = "codebook.confusable";
+const CODES_DOCS_URL: &str = "https://github.com/blopker/codebook#diagnostic-codes";
+
 /// How often to poll the config files for external changes. spell_check runs
 /// on every keystroke with checkWhileTyping, so polling is debounced rather
 /// than done per call; changes made via code actions bypass this by calling
@@ -36,7 +44,7 @@ const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
 /// Computes the relative path of a file from a workspace directory.
 /// Returns the relative path if the file is within the workspace, otherwise returns the absolute path.
 /// If `workspace_dir_canonical` is provided, skips canonicalizing the workspace directory (optimization).
-fn compute_relative_path(
+pub(crate) fn compute_relative_path(
     workspace_dir: &Path,
     workspace_dir_canonical: Option<&Path>,
     file_path: &Path,
@@ -63,20 +71,113 @@ fn compute_relative_path(
 
 pub struct Backend {
     client: Client,
+    /// The default root, for files outside every workspace folder
     workspace_dir: PathBuf,
-    /// Cached canonicalized workspace directory for efficient relative path computation
-    workspace_dir_canonical: Option<PathBuf>,
-    codebook: OnceLock<Arc<Codebook>>,
-    config: OnceLock<Arc<CodebookConfigFile>>,
+    /// The client's workspace folders, each with its own config
+    roots: WorkspaceRoots,
+    /// Shared by the checkers of the roots with the same cache dir
+    dictionary_managers: DictionaryManagers,
     document_cache: TextDocumentCache,
     initialize_options: RwLock<Arc<ClientInitializationOptions>>,
+    /// Column units agreed with the client in `initialize`
+    position_encoding: OnceLock<PositionEncoding>,
+    /// Whether the client lets us register file watchers, from `initialize`
+    can_watch_files: OnceLock<bool>,
     /// When the config files were last polled for changes (None = never)
     last_config_poll: Mutex<Option<Instant>>,
+    /// Dictionary load errors already shown to the user
+    reported_load_errors: Mutex<HashSet<String>>,
+    /// What was last published for each open document
+    published: PublishedDiagnostics,
+}
+
+/// The diagnostics last published per document, so an unchanged set isn't
+/// sent again. Editors redraw on every publish, which makes squiggles
+/// flicker while typing even when nothing about them changed.
+#[derive(Default)]
+struct PublishedDiagnostics(Mutex<HashMap<Url, Vec<Diagnostic>>>);
+
+impl PublishedDiagnostics {
+    /// Remember `diagnostics` as published for `uri`. Returns false when
+    /// they are the same as last time, so there's nothing to publish.
+    fn record(&self, uri: &Url, diagnostics: &[Diagnostic]) -> bool {
+        let mut published = self.0.lock().unwrap();
+        if published.get(uri).is_some_and(|last| last == diagnostics) {
+            return false;
+        }
+        published.insert(uri.clone(), diagnostics.to_vec());
+        true
+    }
+
+    fn forget(&self, uri: &Url) {
+        self.0.lock().unwrap().remove(uri);
+    }
+
+    /// The distinct unknown words last published for `uri`, in document
+    /// order, as the diagnostics recorded them: the text may have changed
+    /// since. Flagged words and confusables aren't included, as allowing
+    /// them wouldn't clear their diagnostics. Words differing only in case
+    /// count once, as the allowlist matches them the same.
+    fn words(&self, uri: &Url) -> Vec<String> {
+        let published = self.0.lock().unwrap();
+        let Some(diagnostics) = published.get(uri) else {
+            return Vec::new();
+        };
+        let spelling = NumberOrString::String(CODE_SPELLING.to_string());
+        let mut seen = HashSet::new();
+        diagnostics
+            .iter()
+            .filter(|d| d.code.as_ref() == Some(&spelling))
+            .filter_map(|d| d.data.as_ref()?.get("word")?.as_str())
+            .filter(|word| !word.is_empty() && seen.insert(word.to_lowercase()))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The word last flagged at `position` in `uri`, read from `text`.
+    fn word_at(
+        &self,
+        uri: &Url,
+        text: &str,
+        position: Position,
+        encoding: PositionEncoding,
+    ) -> Option<String> {
+        let published = self.0.lock().unwrap();
+        let range = published.get(uri)?.iter().map(|d| d.range).find(|r| {
+            r.start.line == position.line
+                && r.end.line == position.line
+                && r.start.character <= position.character
+                && position.character <= r.end.character
+        })?;
+        let line = text.lines().nth(position.line as usize)?;
+        let word = encoding.slice(
+            line,
+            range.start.character as usize,
+            range.end.character as usize,
+        );
+        (!word.is_empty()).then_some(word)
+    }
+}
+
+/// Parameters of the custom `codebook/suggestions` request.
+#[derive(Debug, Deserialize)]
+pub struct SuggestionsParams {
+    pub uri: Url,
+    pub position: Position,
+}
+
+/// Result of the custom `codebook/suggestions` request. `suggestions` is
+/// empty when the word is spelled correctly.
+#[derive(Debug, Serialize)]
+pub struct SuggestionsResult {
+    pub word: String,
+    pub suggestions: Vec<String>,
 }
 
 enum CodebookCommand {
     AddWord,
     AddWordGlobal,
+    AddWordLocal,
     IgnoreFile,
     Unknown,
 }
@@ -86,6 +187,7 @@ impl From<&str> for CodebookCommand {
         match command {
             "codebook.addWord" => CodebookCommand::AddWord,
             "codebook.addWordGlobal" => CodebookCommand::AddWordGlobal,
+            "codebook.addWordLocal" => CodebookCommand::AddWordLocal,
             "codebook.ignoreFile" => CodebookCommand::IgnoreFile,
             _ => CodebookCommand::Unknown,
         }
@@ -97,6 +199,7 @@ impl From<CodebookCommand> for String {
         match command {
             CodebookCommand::AddWord => "codebook.addWord".to_string(),
             CodebookCommand::AddWordGlobal => "codebook.addWordGlobal".to_string(),
+            CodebookCommand::AddWordLocal => "codebook.addWordLocal".to_string(),
             CodebookCommand::IgnoreFile => "codebook.ignoreFile".to_string(),
             CodebookCommand::Unknown => "codebook.unknown".to_string(),
         }
@@ -119,9 +222,29 @@ impl LanguageServer for Backend {
 
         *self.initialize_options.write().unwrap() = Arc::new(client_options);
 
+        for folder in params.workspace_folders.into_iter().flatten() {
+            match folder.uri.to_file_path() {
+                Ok(dir) => self.roots.add_folder(dir),
+                Err(()) => info!("Ignoring non-file workspace folder {}", folder.uri),
+            }
+        }
+
+        let can_watch_files = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|w| w.did_change_watched_files)
+            .and_then(|w| w.dynamic_registration)
+            .unwrap_or(false);
+        let _ = self.can_watch_files.set(can_watch_files);
+
+        let encoding = PositionEncoding::negotiate(&params.capabilities);
+        info!("Position encoding: {encoding:?}");
+        let _ = self.position_encoding.set(encoding);
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
-                position_encoding: Some(PositionEncodingKind::UTF16),
+                position_encoding: Some(encoding.kind()),
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
@@ -136,6 +259,7 @@ impl LanguageServer for Backend {
                     commands: vec![
                         CodebookCommand::AddWord.into(),
                         CodebookCommand::AddWordGlobal.into(),
+                        CodebookCommand::AddWordLocal.into(),
                         CodebookCommand::IgnoreFile.into(),
                     ],
                     work_done_progress_options: Default::default(),
@@ -149,6 +273,13 @@ impl LanguageServer for Backend {
                         },
                     },
                 )),
+                workspace: Some(WorkspaceServerCapabilities {
+                    workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                        supported: Some(true),
+                        change_notifications: Some(OneOf::Left(true)),
+                    }),
+                    file_operations: None,
+                }),
                 ..ServerCapabilities::default()
             },
             server_info: Some(ServerInfo {
@@ -160,14 +291,18 @@ impl LanguageServer for Backend {
 
     async fn initialized(&self, _: InitializedParams) {
         info!("Server ready!");
-        let config = self.config_handle();
-        match config.project_config_path() {
-            Some(path) => info!("Project config: {}", path.display()),
-            None => info!("Project config: <not set>"),
+        if self.can_watch_files.get().copied().unwrap_or(false) {
+            self.watch_config_files().await;
         }
+        // Loading the default root logs its project config and starts
+        // preloading its dictionaries
+        let root = self.default_root();
         info!(
             "Global config: {}",
-            config.global_config_path().unwrap_or_default().display()
+            root.config
+                .global_config_path()
+                .unwrap_or_default()
+                .display()
         );
     }
 
@@ -176,6 +311,21 @@ impl LanguageServer for Backend {
         Ok(())
     }
 
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        for folder in params.event.removed {
+            if let Ok(dir) = folder.uri.to_file_path() {
+                self.roots.remove_folder(&dir);
+            }
+        }
+        for folder in params.event.added {
+            if let Ok(dir) = folder.uri.to_file_path() {
+                self.roots.add_folder(dir);
+            }
+        }
+        // Open documents may have moved to another root
+        self.recheck_all(false).await;
+    }
+
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         debug!(
             "Opened document: uri {:?}, language: {}, version: {}",
@@ -191,18 +341,41 @@ impl LanguageServer for Backend {
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         self.document_cache.remove(&params.text_document.uri);
+        self.published.forget(&params.text_document.uri);
         // Clear diagnostics when a file is closed.
         self.client
             .publish_diagnostics(params.text_document.uri, vec![], None)
             .await;
     }
 
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        // Only config files are watched. One created or deleted can move
+        // files to another root.
+        debug!("Config files changed: {:?}", params.changes);
+        self.roots.forget_owning_dirs();
+        self.reload_roots();
+        self.recheck_all(false).await;
+    }
+
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
         debug!("Saved document: {}", params.text_document.uri);
         if let Some(text) = params.text {
             self.document_cache
                 .update(&params.text_document.uri, &text, None);
         }
+        // Without file watching, a config saved from the editor is the
+        // next best sign one was created
+        if let Ok(path) = params.text_document.uri.to_file_path()
+            && path
+                .parent()
+                .and_then(CodebookConfigFile::project_config_in)
+                .is_some_and(|config| config == path)
+        {
+            self.roots.forget_owning_dirs();
+            self.reload_roots();
+            self.recheck_all(false).await;
+            return;
+        }
         self.spell_check(&params.text_document.uri).await;
     }
 
@@ -230,7 +403,13 @@ impl LanguageServer for Backend {
             None => return Ok(None),
         };
 
+        let encoding = self.position_encoding();
+        let root = self.root_for_uri(&params.text_document.uri);
+        // Lets the add-word commands find this document's root
+        let document: Value = serde_json::json!({ "uri": params.text_document.uri });
         let mut has_codebook_diagnostic = false;
+        // Identifiers already offered a whole-identifier fix, by (line, start)
+        let mut seen_identifiers = HashSet::new();
         for diag in params.context.diagnostics {
             // Only process our own diagnostics
             if diag.source.as_deref() != Some(SOURCE_NAME) {
@@ -244,14 +423,29 @@ impl LanguageServer for Backend {
                 .unwrap_or_default();
             let start_char = diag.range.start.character as usize;
             let end_char = diag.range.end.character as usize;
-            let word = get_word_from_string(start_char, end_char, line);
+            let word = encoding.slice(line, start_char, end_char);
             // info!("Word to suggest: {}", word);
             if word.is_empty() || word.contains(" ") {
                 continue;
             }
-            let cb = self.codebook_handle();
+            let cb = root.codebook.clone();
             let inner_word = word.clone();
-            let suggestions = task::spawn_blocking(move || cb.get_suggestions(&inner_word)).await;
+            // Whether the word starts a sentence only depends on the lines
+            // just above it
+            let context_start = line_start_byte(&doc.text, diag.range.start.line.saturating_sub(2));
+            let word_start = line_start_byte(&doc.text, diag.range.start.line)
+                + encoding.slice(line, 0, start_char).len();
+            let context = doc.text[context_start..word_start.min(doc.text.len())].to_string();
+            let tag = diag
+                .data
+                .as_ref()
+                .and_then(|data| data.get("tag"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let suggestions = task::spawn_blocking(move || {
+                cb.get_suggestions_in_context(&inner_word, &context, context.len(), tag.as_deref())
+            })
+            .await;
 
             let suggestions = match suggestions {
                 Ok(suggestions) => suggestions,
@@ -277,6 +471,34 @@ impl LanguageServer for Backend {
                     &params.text_document.uri,
                 )));
             });
+            // When the word is one part of a longer identifier, also offer
+            // fixing all of the identifier's misspelled parts at once.
+            if let Some((id_start, id_end, identifier)) =
+                identifier_around(line, start_char, end_char, encoding)
+                && identifier != word
+                && seen_identifiers.insert((diag.range.start.line, id_start))
+            {
+                let cb = root.codebook.clone();
+                let fixed =
+                    task::spawn_blocking(move || cb.get_identifier_suggestion(&identifier)).await;
+                if let Ok(Some(fixed)) = fixed {
+                    let range = Range {
+                        start: Position {
+                            line: diag.range.start.line,
+                            character: id_start as u32,
+                        },
+                        end: Position {
+                            line: diag.range.start.line,
+                            character: id_end as u32,
+                        },
+                    };
+                    actions.push(CodeActionOrCommand::CodeAction(self.make_suggestion(
+                        &fixed,
+                        &range,
+                        &params.text_document.uri,
+                    )));
+                }
+            }
             actions.push(CodeActionOrCommand::CodeAction(CodeAction {
                 title: format!("Add '{word}' to dictionary"),
                 kind: Some(CodeActionKind::QUICKFIX),
@@ -285,7 +507,7 @@ impl LanguageServer for Backend {
                 command: Some(Command {
                     title: format!("Add '{word}' to dictionary"),
                     command: CodebookCommand::AddWord.into(),
-                    arguments: Some(vec![word.to_string().into()]),
+                    arguments: Some(vec![word.to_string().into(), document.clone()]),
                 }),
                 is_preferred: None,
                 disabled: None,
@@ -305,8 +527,49 @@ impl LanguageServer for Backend {
                 disabled: None,
                 data: None,
             }));
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: format!("Add '{word}' to personal dictionary"),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: None,
+                edit: None,
+                command: Some(Command {
+                    title: format!("Add '{word}' to personal dictionary"),
+                    command: CodebookCommand::AddWordLocal.into(),
+                    arguments: Some(vec![word.to_string().into(), document.clone()]),
+                }),
+                is_preferred: None,
+                disabled: None,
+                data: None,
+            }));
         }
         if has_codebook_diagnostic {
+            let words = self.published.words(&params.text_document.uri);
+            if !words.is_empty() {
+                let title = match words.len() {
+                    1 => "Add the 1 word in this file to dictionary".to_string(),
+                    n => format!("Add all {n} words in this file to dictionary"),
+                };
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: title.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: None,
+                    edit: None,
+                    command: Some(Command {
+                        title,
+                        command: CodebookCommand::AddWord.into(),
+                        arguments: Some(
+                            words
+                                .into_iter()
+                                .map(Value::from)
+                                .chain([document])
+                                .collect(),
+                        ),
+                    }),
+                    is_preferred: None,
+                    disabled: None,
+                    data: None,
+                }));
+            }
             actions.push(CodeActionOrCommand::CodeAction(CodeAction {
                 title: "Add current file to ignore list".to_string(),
                 kind: Some(CodeActionKind::QUICKFIX),
@@ -331,36 +594,49 @@ impl LanguageServer for Backend {
     async fn execute_command(&self, params: ExecuteCommandParams) -> RpcResult<Option<Value>> {
         match CodebookCommand::from(params.command.as_str()) {
             CodebookCommand::AddWord => {
-                let config = self.config_handle();
-                let words = params
+                let config = self.command_root(&params.arguments).config.clone();
+                let words: Vec<&str> = params
                     .arguments
                     .iter()
-                    .filter_map(|arg| arg.as_str().map(|s| s.to_string()));
-                info!(
-                    "Adding words to dictionary {}",
-                    words.clone().collect::<Vec<String>>().join(", ")
-                );
-                let updated = self.add_words(config.as_ref(), words);
+                    .filter_map(|arg| arg.as_str())
+                    .collect();
+                info!("Adding words to dictionary {}", words.join(", "));
+                let updated = self.add_words(config.as_ref(), &words);
                 if updated {
-                    if let Err(e) = config.save() {
-                        error!("Failed to save config: {e}");
-                    }
-                    self.recheck_all().await;
+                    save_config(config, CodebookConfigFile::save, "config").await;
+                    // Other roots may share the file just saved
+                    self.reload_roots();
+                    self.recheck_all(true).await;
                 }
                 Ok(None)
             }
             CodebookCommand::AddWordGlobal => {
-                let config = self.config_handle();
-                let words = params
+                let config = self.command_root(&params.arguments).config.clone();
+                let words: Vec<&str> = params
                     .arguments
                     .iter()
-                    .filter_map(|arg| arg.as_str().map(|s| s.to_string()));
-                let updated = self.add_words_global(config.as_ref(), words);
+                    .filter_map(|arg| arg.as_str())
+                    .collect();
+                let updated = self.add_words_global(config.as_ref(), &words);
                 if updated {
-                    if let Err(e) = config.save_global() {
-                        error!("Failed to save global config: {e}");
-                    }
-                    self.recheck_all().await;
+                    save_config(config, CodebookConfigFile::save_global, "global config").await;
+                    self.reload_roots();
+                    self.recheck_all(true).await;
+                }
+                Ok(None)
+            }
+            CodebookCommand::AddWordLocal => {
+                let config = self.command_root(&params.arguments).config.clone();
+                let words: Vec<&str> = params
+                    .arguments
+                    .iter()
+                    .filter_map(|arg| arg.as_str())
+                    .collect();
+                let updated = self.add_words_local(config.as_ref(), &words);
+                if updated {
+                    save_config(config, CodebookConfigFile::save_local, "personal config").await;
+                    self.reload_roots();
+                    self.recheck_all(true).await;
                 }
                 Ok(None)
             }
@@ -369,13 +645,19 @@ impl LanguageServer for Backend {
                     error!("IgnoreFile command missing or invalid file URI argument");
                     return Ok(None);
                 };
-                let config = self.config_handle();
-                let updated = self.add_ignore_file(config.as_ref(), file_uri);
-                if updated {
-                    if let Err(e) = config.save() {
-                        error!("Failed to save config: {e}");
+                let file_uri = match Url::parse(file_uri) {
+                    Ok(uri) => uri,
+                    Err(e) => {
+                        error!("Failed to parse URI '{file_uri}': {e}");
+                        return Ok(None);
                     }
-                    self.recheck_all().await;
+                };
+                let root = self.root_for_uri(&file_uri);
+                let updated = self.add_ignore_file(&root, &file_uri);
+                if updated {
+                    save_config(root.config.clone(), CodebookConfigFile::save, "config").await;
+                    self.reload_roots();
+                    self.recheck_all(true).await;
                 }
                 Ok(None)
             }
@@ -384,46 +666,250 @@ impl LanguageServer for Backend {
     }
 }
 
+/// Run `save` on a blocking thread so the disk write doesn't hold up the
+/// async runtime, logging failures.
+/// Whether adding `requested` words to `dictionary` added any, logging the
+/// ones already there or why none could be added.
+fn words_added(added: Result<usize, ConfigError>, requested: usize, dictionary: &str) -> bool {
+    let added = match added {
+        Ok(added) => added,
+        Err(e) => {
+            error!("Failed to add words to {dictionary}: {e}");
+            return false;
+        }
+    };
+    if added < requested {
+        info!(
+            "{} word(s) already exist in {dictionary}.",
+            requested - added
+        );
+    }
+    added > 0
+}
+
+async fn save_config(
+    config: Arc<CodebookConfigFile>,
+    save: fn(&CodebookConfigFile) -> Result<(), ConfigError>,
+    label: &str,
+) {
+    match task::spawn_blocking(move || save(&config)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!("Failed to save {label}: {e}"),
+        Err(e) => error!("Failed to save {label}: {e}"),
+    }
+}
+
 impl Backend {
     pub fn new(client: Client, workspace_dir: &Path) -> Self {
-        let workspace_dir_canonical = workspace_dir.canonicalize().ok();
         Self {
             client,
             workspace_dir: workspace_dir.to_path_buf(),
-            workspace_dir_canonical,
-            codebook: OnceLock::new(),
-            config: OnceLock::new(),
+            roots: WorkspaceRoots::default(),
+            dictionary_managers: DictionaryManagers::default(),
             document_cache: TextDocumentCache::default(),
             initialize_options: RwLock::new(Arc::new(ClientInitializationOptions::default())),
+            position_encoding: OnceLock::new(),
+            can_watch_files: OnceLock::new(),
             last_config_poll: Mutex::new(None),
+            reported_load_errors: Mutex::new(HashSet::new()),
+            published: PublishedDiagnostics::default(),
         }
     }
 
-    fn config_handle(&self) -> Arc<CodebookConfigFile> {
-        self.config
-            .get_or_init(|| {
-                let options = self.initialize_options.read().unwrap();
-                let global_config_path = options.global_config_path.clone();
-                let project_config_path = options
-                    .config_path
-                    .clone()
-                    .map(|p| self.resolve_workspace_path(&p));
-                drop(options);
-
-                // Strict at startup by design: an invalid config crashes the
-                // server with the parse error on stderr rather than silently
-                // running with defaults. Mid-session edits are lenient (the
-                // last good config is kept).
-                Arc::new(
-                    CodebookConfigFile::load_with_overrides(
-                        Some(self.workspace_dir.as_path()),
-                        global_config_path,
-                        project_config_path,
-                    )
-                    .unwrap_or_else(|e| panic!("Unable to load configuration: {e}")),
-                )
-            })
+    /// Handler for the custom `codebook/isIgnored` request: whether the
+    /// document is skipped rather than checked, so editors can say so. Open
+    /// documents are judged on their current text; other files on their
+    /// path and size only.
+    pub async fn is_ignored(&self, params: TextDocumentIdentifier) -> RpcResult<bool> {
+        let file_path = params.uri.to_file_path().unwrap_or_default();
+        let root = self.root_for_path(&file_path);
+        let relative_path = root.relative_path(&file_path);
+        let cb = root.codebook.clone();
+        let ignored = match self.document_cache.get(params.uri.as_ref()) {
+            Some(doc) => {
+                self.exceeds_max_file_size(doc.text.len())
+                    || cb.skips_file(&doc.text, Some(&relative_path))
+            }
+            None => {
+                let size = std::fs::metadata(&file_path).map_or(0, |m| m.len());
+                self.exceeds_max_file_size(usize::try_from(size).unwrap_or(usize::MAX))
+                    || cb.skips_file("", Some(&relative_path))
+            }
+        };
+        Ok(ignored)
+    }
+
+    /// Handler for the custom `codebook/dictionaries` request: the IDs of
+    /// the dictionaries the document is actually checked with, leaving out
+    /// configured ones that are unknown or failed to load. Open documents
+    /// use their language ID; other files are judged by their path.
+    pub async fn dictionaries(&self, params: TextDocumentIdentifier) -> RpcResult<Vec<String>> {
+        let file_path = params.uri.to_file_path().unwrap_or_default();
+        let root = self.root_for_path(&file_path);
+        let relative_path = root.relative_path(&file_path);
+        let cb = root.codebook.clone();
+        let lang_type = self
+            .document_cache
+            .get(params.uri.as_ref())
+            .and_then(|doc| doc.language_id)
+            .and_then(|lang| LanguageType::from_str(&lang).ok());
+        let extra_dictionaries = self
+            .initialize_options
+            .read()
+            .unwrap()
+            .extra_dictionaries
+            .clone();
+        let active = task::spawn_blocking(move || {
+            cb.active_dictionaries(lang_type, Some(&relative_path), &extra_dictionaries)
+        })
+        .await;
+        match active {
+            Ok(active) => Ok(active),
+            Err(e) => {
+                error!("Error listing dictionaries for {}: {e}", params.uri);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Handler for the custom `codebook/suggestions` request: the word at a
+    /// position and its suggestions, without a code action round-trip. The
+    /// word is the flagged one the position falls in, or else the run of
+    /// letters around it. Returns null when there's no open document or no
+    /// word there.
+    pub async fn suggestions(
+        &self,
+        params: SuggestionsParams,
+    ) -> RpcResult<Option<SuggestionsResult>> {
+        let Some(doc) = self.document_cache.get(params.uri.as_ref()) else {
+            return Ok(None);
+        };
+        let encoding = self.position_encoding();
+        let word = self
+            .published
+            .word_at(&params.uri, &doc.text, params.position, encoding)
+            .or_else(|| {
+                let line = doc.text.lines().nth(params.position.line as usize)?;
+                word_around(line, params.position.character as usize, encoding)
+            });
+        let Some(word) = word else {
+            return Ok(None);
+        };
+        let cb = self.root_for_uri(&params.uri).codebook.clone();
+        let inner_word = word.clone();
+        let suggestions = match task::spawn_blocking(move || cb.get_suggestions(&inner_word)).await
+        {
+            Ok(suggestions) => suggestions.unwrap_or_default(),
+            Err(e) => {
+                error!("Error getting suggestions for word '{word}': {e}");
+                Vec::new()
+            }
+        };
+        Ok(Some(SuggestionsResult { word, suggestions }))
+    }
+
+    /// Whether a document of `len` bytes is over the `maxFileSizeBytes` limit.
+    fn exceeds_max_file_size(&self, len: usize) -> bool {
+        let max = self.initialize_options.read().unwrap().max_file_size_bytes;
+        max > 0 && len > max
+    }
+
+    fn position_encoding(&self) -> PositionEncoding {
+        self.position_encoding.get().copied().unwrap_or_default()
+    }
+
+    /// The root for files outside every workspace folder.
+    fn default_root(&self) -> Arc<WorkspaceRoot> {
+        self.roots
+            .get_or_load(&self.workspace_dir, |dir| self.load_root(dir))
+    }
+
+    /// The root `file_path` belongs to. With a `configPath` from the client
+    /// every file shares that one config, so they're all in the default root.
+    fn root_for_path(&self, file_path: &Path) -> Arc<WorkspaceRoot> {
+        if self
+            .initialize_options
+            .read()
+            .unwrap()
+            .config_path
+            .is_some()
+        {
+            return self.default_root();
+        }
+        let dir = self.roots.owning_dir(file_path, &self.workspace_dir);
+        self.roots.get_or_load(&dir, |dir| self.load_root(dir))
+    }
+
+    fn root_for_uri(&self, uri: &Url) -> Arc<WorkspaceRoot> {
+        self.root_for_path(&uri.to_file_path().unwrap_or_default())
+    }
+
+    /// The root a command acts on: that of the document named by its
+    /// `{ "uri": ... }` argument, else the default root.
+    fn command_root(&self, arguments: &[Value]) -> Arc<WorkspaceRoot> {
+        arguments
+            .iter()
+            .find_map(|arg| Url::parse(arg.get("uri")?.as_str()?).ok())
+            .map_or_else(|| self.default_root(), |uri| self.root_for_uri(&uri))
+    }
+
+    /// Load the config for the root at `dir`, finding its project config by
+    /// walking up from there, and start preloading its dictionaries.
+    fn load_root(&self, dir: &Path) -> Arc<WorkspaceRoot> {
+        let options = self.initialize_options.read().unwrap();
+        let global_config_path = options.global_config_path.clone();
+        let project_config_path = options
+            .config_path
             .clone()
+            .map(|p| self.resolve_workspace_path(&p));
+        drop(options);
+
+        let config = match CodebookConfigFile::load_with_overrides(
+            Some(dir),
+            global_config_path,
+            project_config_path,
+        ) {
+            Ok(config) => Arc::new(config),
+            // Strict at startup by design: an invalid config crashes the
+            // server with the parse error on stderr rather than silently
+            // running with defaults. Mid-session edits are lenient (the
+            // last good config is kept).
+            Err(e) if dir == self.workspace_dir => {
+                panic!("Unable to load configuration: {e}")
+            }
+            // Other folders are opened mid-session, so they mustn't take
+            // the server down with them
+            Err(e) => {
+                error!(
+                    "Unable to load configuration for {}, using the default: {e}",
+                    dir.display()
+                );
+                return self.default_root();
+            }
+        };
+        info!(
+            "Workspace root {}: project config {}",
+            dir.display(),
+            config
+                .project_config_path()
+                .map_or_else(|| "<not set>".to_string(), |p| p.display().to_string())
+        );
+        let manager = self.dictionary_managers.get(config.cache_dir());
+        let codebook = Arc::new(Codebook::with_manager(config.clone(), manager));
+
+        // Load dictionaries now rather than on the first check of the first
+        // file opened. Ones another root already loaded are shared.
+        let cb = codebook.clone();
+        let label = dir.display().to_string();
+        task::spawn_blocking(move || {
+            let start = std::time::Instant::now();
+            let loaded = cb.preload_dictionaries();
+            info!(
+                "Preloaded {loaded} dictionaries for {label} in {:?}",
+                start.elapsed()
+            );
+        });
+        Arc::new(WorkspaceRoot::new(dir, config, codebook))
     }
 
     /// Resolve a user-provided path against the workspace directory.
@@ -436,65 +922,38 @@ impl Backend {
         }
     }
 
-    fn codebook_handle(&self) -> Arc<Codebook> {
-        self.codebook
-            .get_or_init(|| Arc::new(Codebook::new(self.config_handle())))
-            .clone()
-    }
-
     fn should_spellcheck_while_typing(&self) -> bool {
         self.initialize_options.read().unwrap().check_while_typing
     }
 
-    fn add_words(&self, config: &CodebookConfigFile, words: impl Iterator<Item = String>) -> bool {
-        let mut should_save = false;
-        for word in words {
-            if config.add_word(&word) {
-                should_save = true;
-            } else {
-                info!("Word '{word}' already exists in dictionary.");
-            }
-        }
-        should_save
+    fn add_words(&self, config: &CodebookConfigFile, words: &[&str]) -> bool {
+        words_added(config.add_words(words), words.len(), "dictionary")
     }
 
-    fn add_words_global(
-        &self,
-        config: &CodebookConfigFile,
-        words: impl Iterator<Item = String>,
-    ) -> bool {
-        let mut should_save = false;
-        for word in words {
-            if config.add_word_global(&word) {
-                should_save = true;
-            } else {
-                info!("Word '{word}' already exists in global dictionary.");
-            }
-        }
-        should_save
+    fn add_words_global(&self, config: &CodebookConfigFile, words: &[&str]) -> bool {
+        words_added(
+            config.add_words_global(words),
+            words.len(),
+            "global dictionary",
+        )
     }
 
-    fn get_relative_path(&self, uri: &str) -> Option<String> {
-        let parsed_uri = match Url::parse(uri) {
-            Ok(u) => u,
-            Err(e) => {
-                error!("Failed to parse URI '{uri}': {e}");
-                return None;
-            }
-        };
-        let file_path = parsed_uri.to_file_path().unwrap_or_default();
-        Some(compute_relative_path(
-            &self.workspace_dir,
-            self.workspace_dir_canonical.as_deref(),
-            &file_path,
-        ))
+    fn add_words_local(&self, config: &CodebookConfigFile, words: &[&str]) -> bool {
+        words_added(
+            config.add_words_local(words),
+            words.len(),
+            "personal dictionary",
+        )
     }
 
-    fn add_ignore_file(&self, config: &CodebookConfigFile, file_uri: &str) -> bool {
-        let Some(relative_path) = self.get_relative_path(file_uri) else {
-            return false;
-        };
-        if config.add_ignore(&relative_path) {
+    fn add_ignore_file(&self, root: &WorkspaceRoot, file_uri: &Url) -> bool {
+        let file_path = file_uri.to_file_path().unwrap_or_default();
+        let relative_path = root.relative_path(&file_path);
+        let config = &root.config;
+        // ignore_paths are relative to the config file, which may live above
+        // the workspace root.
+        let relative_path = config.config_relative_path(Path::new(&relative_path));
+        if config.add_ignore(&relative_path.to_string_lossy()) {
             true
         } else {
             info!("File {file_uri} already exists in the ignored files.");
@@ -529,11 +988,42 @@ impl Backend {
         }
     }
 
-    async fn recheck_all(&self) {
+    /// Recheck every open document. With `summarize`, for rechecks the user
+    /// asked for, also log a one-line summary of what was found so they can
+    /// see that an add-word or ignore took effect.
+    async fn recheck_all(&self, summarize: bool) {
         let urls = self.document_cache.cached_urls();
         debug!("Rechecking documents: {urls:?}");
+        let mut counts = Vec::with_capacity(urls.len());
         for url in urls {
-            self.publish_spellcheck_diagnostics(&url).await;
+            if let Some(count) = self.publish_spellcheck_diagnostics(&url).await {
+                counts.push(count);
+            }
+        }
+        if summarize {
+            self.client
+                .log_message(MessageType::INFO, recheck_summary(&counts))
+                .await;
+        }
+    }
+
+    /// Ask the client to tell us when a project config file is created,
+    /// changed or deleted anywhere in the workspace.
+    async fn watch_config_files(&self) {
+        let watchers = CodebookConfigFile::project_config_names()
+            .map(|name| FileSystemWatcher {
+                glob_pattern: GlobPattern::String(format!("**/{name}")),
+                kind: None,
+            })
+            .collect();
+        let options = DidChangeWatchedFilesRegistrationOptions { watchers };
+        let registration = Registration {
+            id: "codebook-config-files".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(options).ok(),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            error!("Failed to watch config files: {e}");
         }
     }
 
@@ -548,7 +1038,18 @@ impl Backend {
             *last_poll = Some(Instant::now());
         }
 
-        self.config_handle().reload()
+        self.reload_roots()
+    }
+
+    /// Reload every loaded root's config from disk. Returns true when any
+    /// of them changed.
+    fn reload_roots(&self) -> bool {
+        let mut changed = false;
+        for root in self.roots.loaded() {
+            // Every root must reload, so no short-circuiting here
+            changed |= root.config.reload();
+        }
+        changed
     }
 
     async fn spell_check(&self, uri: &Url) {
@@ -556,19 +1057,17 @@ impl Backend {
 
         if did_reload {
             debug!("Config reloaded, rechecking all files.");
-            self.recheck_all().await;
+            self.recheck_all(false).await;
         } else {
             debug!("Checking file: {uri:?}");
             self.publish_spellcheck_diagnostics(uri).await;
         }
     }
 
-    /// Helper method to publish diagnostics for spell-checking.
-    async fn publish_spellcheck_diagnostics(&self, uri: &Url) {
-        let doc = match self.document_cache.get(uri.as_ref()) {
-            Some(doc) => doc,
-            None => return,
-        };
+    /// Helper method to publish diagnostics for spell-checking. Returns how
+    /// many were published, or None if nothing was (closed or stale document).
+    async fn publish_spellcheck_diagnostics(&self, uri: &Url) -> Option<usize> {
+        let doc = self.document_cache.get(uri.as_ref())?;
         // Convert the file URI to a local file path.
         let file_path = doc.uri.to_file_path().unwrap_or_default();
         debug!("Spell-checking file: {file_path:?}");
@@ -577,38 +1076,109 @@ impl Backend {
         let lang_type = lang.and_then(|lang| LanguageType::from_str(lang).ok());
         debug!("Document identified as type {lang_type:?} from {lang:?}");
 
-        let severity = self.initialize_options.read().unwrap().diagnostic_severity;
-        let workspace_dir = self.workspace_dir.clone();
-        let workspace_dir_canonical = self.workspace_dir_canonical.clone();
-        let cb = self.codebook_handle();
+        let (severity, extra_dictionaries) = {
+            let options = self.initialize_options.read().unwrap();
+            (
+                options.diagnostic_severity,
+                options.extra_dictionaries.clone(),
+            )
+        };
+        if self.exceeds_max_file_size(doc.text.len()) {
+            info!(
+                "Not spell-checking {uri}: {} bytes is over the maxFileSizeBytes limit",
+                doc.text.len()
+            );
+            // Clear anything published while the file was smaller
+            if self.published.record(&doc.uri, &[]) {
+                self.client
+                    .publish_diagnostics(doc.uri.clone(), Vec::new(), doc.version)
+                    .await;
+            }
+            return Some(0);
+        }
+        let encoding = self.position_encoding();
+        let root = self.root_for_path(&file_path);
+        let inner_root = root.clone();
+        let cb = root.codebook.clone();
         let checked_version = doc.version;
         let doc_uri = doc.uri.clone();
 
-        // Everything document-sized — canonicalization, the UTF-8→UTF-16
-        // offset table, the check itself, diagnostic conversion — runs on
+        // Everything document-sized — canonicalization, the check itself, the
+        // byte offset to position table, diagnostic conversion — runs on
         // the blocking pool so large documents don't stall the event loop.
         let diagnostics = task::spawn_blocking(move || {
-            let relative_path = compute_relative_path(
-                &workspace_dir,
-                workspace_dir_canonical.as_deref(),
-                &file_path,
+            let relative_path = inner_root.relative_path(&file_path);
+            let start = Instant::now();
+            let spell_results = cb.spell_check_with_dictionaries(
+                &doc.text,
+                lang_type,
+                Some(&relative_path),
+                &extra_dictionaries,
             );
-            let offsets = StringOffsets::<AllConfig>::new(&doc.text);
-            let spell_results = cb.spell_check(&doc.text, lang_type, Some(&relative_path));
-            spell_results
+            let check_time = start.elapsed();
+            // Only the text up to the last misspelling needs scanning for
+            // positions; a clean document isn't scanned a second time at all
+            let last_end = spell_results
+                .iter()
+                .flat_map(|res| &res.locations)
+                .map(|loc| loc.end_byte)
+                .max()
+                .unwrap_or(0);
+            let offsets = OffsetTable::covering(&doc.text, last_end, encoding);
+            let diagnostics = spell_results
                 .into_iter()
                 .flat_map(|res| {
+                    let confusable = (res.kind == IssueKind::Confusable)
+                        .then(|| find_confusable(&res.word))
+                        .flatten();
+                    let (code, hint) = if confusable.is_some() {
+                        (CODE_CONFUSABLE, String::new())
+                    } else if cb.is_flagged_word(&res.word, Some(&relative_path)) {
+                        (CODE_FLAGGED, String::new())
+                    } else {
+                        (CODE_SPELLING, language_hint(&cb.guess_languages(&res.word)))
+                    };
                     // For each misspelling, create a diagnostic for each location.
                     res.locations
                         .iter()
                         .map(|loc| {
-                            let start_pos = offsets.utf8_to_utf16_pos(loc.start_byte);
-                            let end_pos = offsets.utf8_to_utf16_pos(loc.end_byte);
-                            make_diagnostic(&res.word, &start_pos, &end_pos, severity)
+                            let start_pos = offsets.pos(loc.start_byte);
+                            let end_pos = offsets.pos(loc.end_byte);
+                            let mut diagnostic = make_diagnostic(
+                                &res.word, &hint, &start_pos, &end_pos, severity, code,
+                            );
+                            // Lets code actions case suggestions for prose
+                            if let Some(tag) = &res.tag
+                                && let Some(data) = diagnostic
+                                    .data
+                                    .as_mut()
+                                    .and_then(serde_json::Value::as_object_mut)
+                            {
+                                data.insert("tag".to_string(), tag.as_str().into());
+                            }
+                            match confusable {
+                                Some(confusable) => Diagnostic {
+                                    message: format!(
+                                        "Mixed scripts in '{}': {confusable}.",
+                                        res.word
+                                    ),
+                                    ..diagnostic
+                                },
+                                None => diagnostic,
+                            }
                         })
                         .collect::<Vec<_>>()
                 })
-                .collect::<Vec<Diagnostic>>()
+                .collect::<Vec<Diagnostic>>();
+            debug!(
+                "Built {} diagnostics for {relative_path} ({} bytes) in {:?}: \
+                 check {check_time:?}, diagnostics {:?}",
+                diagnostics.len(),
+                doc.text.len(),
+                start.elapsed(),
+                start.elapsed() - check_time,
+            );
+            diagnostics
         })
         .await;
 
@@ -616,7 +1186,7 @@ impl Backend {
             Ok(diagnostics) => diagnostics,
             Err(err) => {
                 error!("Spell-checking failed for '{uri}': {err}");
-                return;
+                return None;
             }
         };
 
@@ -628,23 +1198,89 @@ impl Backend {
             Some(current) if current.version == checked_version => {}
             _ => {
                 debug!("Skipping stale diagnostics for {uri}");
-                return;
+                return None;
             }
         }
-        self.client
-            .publish_diagnostics(doc_uri, diagnostics, checked_version)
-            .await;
+        let count = diagnostics.len();
+        if self.published.record(&doc_uri, &diagnostics) {
+            self.client
+                .publish_diagnostics(doc_uri, diagnostics, checked_version)
+                .await;
+        } else {
+            debug!("Diagnostics for {uri} unchanged, not republishing");
+        }
+        self.report_dictionary_errors(&root).await;
+        Some(count)
+    }
+
+    /// Tell the user about dictionaries that failed to load, once per error,
+    /// so missing dictionaries don't silently weaken the results.
+    async fn report_dictionary_errors(&self, root: &WorkspaceRoot) {
+        let errors = root.codebook.dictionary_load_errors();
+        let new_errors: Vec<String> = {
+            let mut reported = self.reported_load_errors.lock().unwrap();
+            errors
+                .into_iter()
+                .filter(|e| reported.insert(e.clone()))
+                .collect()
+        };
+        if new_errors.is_empty() {
+            return;
+        }
+        let message_type = if root.config.strict_dictionaries() {
+            MessageType::ERROR
+        } else {
+            MessageType::WARNING
+        };
+        for error in new_errors {
+            self.client
+                .show_message(message_type, format!("Codebook: {error}"))
+                .await;
+        }
     }
 }
 
-/// Build an LSP diagnostic for one misspelled-word location.
+/// "Codebook: 42 spelling issues across 7 files." from the diagnostic
+/// count of each rechecked document.
+fn recheck_summary(counts: &[usize]) -> String {
+    let plural =
+        |n: usize, one: &str, many: &str| format!("{n} {}", if n == 1 { one } else { many });
+    let issues: usize = counts.iter().sum();
+    if issues == 0 {
+        return format!(
+            "Codebook: no spelling issues in {}.",
+            plural(counts.len(), "open file", "open files")
+        );
+    }
+    let files = counts.iter().filter(|&&n| n > 0).count();
+    format!(
+        "Codebook: {} across {}.",
+        plural(issues, "spelling issue", "spelling issues"),
+        plural(files, "file", "files")
+    )
+}
+
+/// " (possibly French?)" for a misspelling that `detect_languages`
+/// dictionaries know, or an empty string.
+pub(crate) fn language_hint(guesses: &[String]) -> String {
+    match guesses {
+        [] => String::new(),
+        [only] => format!(" (possibly {only}?)"),
+        [rest @ .., last] => format!(" (possibly {} or {last}?)", rest.join(", ")),
+    }
+}
+
+/// Build an LSP diagnostic for one misspelled-word location. `hint` is
+/// appended to the word in the message (see `language_hint`).
 fn make_diagnostic(
     word: &str,
+    hint: &str,
     start_pos: &Pos,
     end_pos: &Pos,
     severity: DiagnosticSeverity,
+    code: &str,
 ) -> Diagnostic {
-    let message = format!("Possible spelling issue '{word}'.");
+    let message = format!("Possible spelling issue '{word}'{hint}.");
     Diagnostic {
         range: Range {
             start: Position {
@@ -657,14 +1293,79 @@ fn make_diagnostic(
             },
         },
         severity: Some(severity),
-        code: None,
-        code_description: None,
+        code: Some(NumberOrString::String(code.to_string())),
+        code_description: Url::parse(CODES_DOCS_URL)
+            .ok()
+            .map(|href| CodeDescription { href }),
         source: Some(SOURCE_NAME.to_string()),
         message,
         related_information: None,
         tags: None,
-        data: None,
+        // The word as checked, for actions on the published diagnostics
+        data: Some(serde_json::json!({ "word": word })),
+    }
+}
+
+/// Byte offset of the start of line `line` in `text`, or the end of `text`
+/// when it has fewer lines.
+fn line_start_byte(text: &str, line: u32) -> usize {
+    text.split_inclusive('\n')
+        .take(line as usize)
+        .map(str::len)
+        .sum()
+}
+
+/// The identifier (a run of alphanumerics and `_`) in `line` that contains
+/// the column range `start..end`, as its column bounds and text.
+fn identifier_around(
+    line: &str,
+    start: usize,
+    end: usize,
+    encoding: PositionEncoding,
+) -> Option<(usize, usize, String)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let mut pos = 0;
+    let mut current_start = 0;
+    let mut current = String::new();
+    for c in line.chars().chain(std::iter::once(' ')) {
+        if is_ident(c) {
+            if current.is_empty() {
+                current_start = pos;
+            }
+            current.push(c);
+        } else if !current.is_empty() {
+            if current_start <= start && end <= pos {
+                return Some((current_start, pos, current));
+            }
+            current.clear();
+        }
+        pos += encoding.char_len(c);
     }
+    None
+}
+
+/// The run of letters (and apostrophes inside it) in `line` that touches
+/// column `character`.
+fn word_around(line: &str, character: usize, encoding: PositionEncoding) -> Option<String> {
+    let mut pos = 0;
+    let mut current_start = 0;
+    let mut current = String::new();
+    for c in line.chars().chain(std::iter::once(' ')) {
+        if c.is_alphabetic() || (c == '\'' && !current.is_empty()) {
+            if current.is_empty() {
+                current_start = pos;
+            }
+            current.push(c);
+        } else if !current.is_empty() {
+            if current_start <= character && character <= pos {
+                let word = current.trim_end_matches('\'');
+                return Some(word.to_string());
+            }
+            current.clear();
+        }
+        pos += encoding.char_len(c);
+    }
+    None
 }
 
 #[cfg(test)]
@@ -673,6 +1374,230 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_make_diagnostic_code() {
+        let start = Pos { line: 1, col: 4 };
+        let end = Pos { line: 1, col: 8 };
+        let diagnostic = make_diagnostic(
+            "wrod",
+            "",
+            &start,
+            &end,
+            DiagnosticSeverity::HINT,
+            CODE_FLAGGED,
+        );
+        assert_eq!(
+            diagnostic.code,
+            Some(NumberOrString::String("codebook.flagged".to_string()))
+        );
+        assert_eq!(
+            diagnostic.code_description.unwrap().href.as_str(),
+            CODES_DOCS_URL
+        );
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(diagnostic.range.start.character, 4);
+        assert_eq!(diagnostic.message, "Possible spelling issue 'wrod'.");
+    }
+
+    #[test]
+    fn test_published_diagnostics_skip_unchanged() {
+        let published = PublishedDiagnostics::default();
+        let uri = Url::parse("file:///project/notes.md").unwrap();
+        let diagnostic = |col| {
+            let start = Pos { line: 0, col };
+            let end = Pos {
+                line: 0,
+                col: col + 4,
+            };
+            make_diagnostic(
+                "wrod",
+                "",
+                &start,
+                &end,
+                DiagnosticSeverity::INFORMATION,
+                CODE_SPELLING,
+            )
+        };
+        let first = vec![diagnostic(0)];
+
+        assert!(published.record(&uri, &first));
+        // Two identical checks publish once
+        assert!(!published.record(&uri, &first.clone()));
+        // A moved squiggle is a change
+        assert!(published.record(&uri, &[diagnostic(2)]));
+        assert!(published.record(&uri, &[]));
+        assert!(!published.record(&uri, &[]));
+        // Reopened documents always get a fresh publish
+        published.forget(&uri);
+        assert!(published.record(&uri, &[]));
+    }
+
+    #[test]
+    fn test_published_diagnostics_words() {
+        let published = PublishedDiagnostics::default();
+        let uri = Url::parse("file:///project/notes.md").unwrap();
+        let diagnostic = |word, line, col, code| {
+            make_diagnostic(
+                word,
+                "",
+                &Pos { line, col },
+                &Pos { line, col: col + 4 },
+                DiagnosticSeverity::INFORMATION,
+                code,
+            )
+        };
+        assert!(published.words(&uri).is_empty());
+        published.record(
+            &uri,
+            &[
+                diagnostic("wrod", 0, 0, CODE_SPELLING),
+                diagnostic("Wrod", 0, 9, CODE_SPELLING),
+                diagnostic("darn", 1, 0, CODE_FLAGGED),
+                diagnostic("tset", 1, 5, CODE_SPELLING),
+                diagnostic("userName", 1, 11, CODE_CONFUSABLE),
+                // Ranges aren't read from the text, which may have changed
+                diagnostic("wrod", 5, 0, CODE_SPELLING),
+            ],
+        );
+        assert_eq!(published.words(&uri), ["wrod", "tset"]);
+    }
+
+    #[test]
+    fn test_published_diagnostics_word_at() {
+        let published = PublishedDiagnostics::default();
+        let uri = Url::parse("file:///project/main.rs").unwrap();
+        let text = "fn calculaateScore() {}";
+        let at = |character| Position { line: 0, character };
+        assert_eq!(
+            published.word_at(&uri, text, at(5), PositionEncoding::Utf16),
+            None
+        );
+        published.record(
+            &uri,
+            &[make_diagnostic(
+                "",
+                "",
+                &Pos { line: 0, col: 3 },
+                &Pos { line: 0, col: 13 },
+                DiagnosticSeverity::INFORMATION,
+                CODE_SPELLING,
+            )],
+        );
+        for character in [3, 8, 13] {
+            assert_eq!(
+                published.word_at(&uri, text, at(character), PositionEncoding::Utf16),
+                Some("calculaate".to_string())
+            );
+        }
+        assert_eq!(
+            published.word_at(&uri, text, at(15), PositionEncoding::Utf16),
+            None
+        );
+    }
+
+    #[test]
+    fn test_word_around() {
+        let line = "// it's a tset, 🦀 wrod";
+        assert_eq!(
+            word_around(line, 4, PositionEncoding::Utf16),
+            Some("it's".to_string())
+        );
+        assert_eq!(
+            word_around(line, 14, PositionEncoding::Utf16),
+            Some("tset".to_string())
+        );
+        assert_eq!(word_around(line, 1, PositionEncoding::Utf16), None);
+        // The emoji is two UTF-16 units and four UTF-8 bytes
+        assert_eq!(
+            word_around(line, 19, PositionEncoding::Utf16),
+            Some("wrod".to_string())
+        );
+        assert_eq!(
+            word_around(line, 21, PositionEncoding::Utf8),
+            Some("wrod".to_string())
+        );
+    }
+
+    #[test]
+    fn test_recheck_summary() {
+        assert_eq!(
+            recheck_summary(&[40, 0, 2]),
+            "Codebook: 42 spelling issues across 2 files."
+        );
+        assert_eq!(
+            recheck_summary(&[1]),
+            "Codebook: 1 spelling issue across 1 file."
+        );
+        assert_eq!(
+            recheck_summary(&[0, 0]),
+            "Codebook: no spelling issues in 2 open files."
+        );
+        assert_eq!(
+            recheck_summary(&[]),
+            "Codebook: no spelling issues in 0 open files."
+        );
+    }
+
+    #[test]
+    fn test_language_hint() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(language_hint(&[]), "");
+        assert_eq!(language_hint(&names(&["French"])), " (possibly French?)");
+        assert_eq!(
+            language_hint(&names(&["French", "Italian", "Spanish"])),
+            " (possibly French, Italian or Spanish?)"
+        );
+        let start = Pos { line: 0, col: 0 };
+        let diagnostic = make_diagnostic(
+            "bonjour",
+            &language_hint(&names(&["French"])),
+            &start,
+            &start,
+            DiagnosticSeverity::INFORMATION,
+            CODE_SPELLING,
+        );
+        assert_eq!(
+            diagnostic.message,
+            "Possible spelling issue 'bonjour' (possibly French?)."
+        );
+    }
+
+    #[test]
+    fn test_identifier_around() {
+        let line = "let total = calculaateScoore(x);";
+        assert_eq!(
+            identifier_around(line, 12, 22, PositionEncoding::Utf16),
+            Some((12, 28, "calculaateScoore".to_string()))
+        );
+        assert_eq!(
+            identifier_around(line, 22, 28, PositionEncoding::Utf16),
+            Some((12, 28, "calculaateScoore".to_string()))
+        );
+        // UTF-16 columns: the emoji takes two units
+        assert_eq!(
+            identifier_around("🦀 snake_caes", 9, 13, PositionEncoding::Utf16),
+            Some((3, 13, "snake_caes".to_string()))
+        );
+        assert_eq!(
+            identifier_around(line, 9, 11, PositionEncoding::Utf16),
+            None
+        );
+        // UTF-8 columns: the emoji takes four bytes
+        assert_eq!(
+            identifier_around("🦀 snake_caes", 11, 15, PositionEncoding::Utf8),
+            Some((5, 15, "snake_caes".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_line_start_byte() {
+        let text = "one\r\ntwo\nthree";
+        assert_eq!(line_start_byte(text, 0), 0);
+        assert_eq!(line_start_byte(text, 1), 5);
+        assert_eq!(line_start_byte(text, 2), 9);
+        assert_eq!(line_start_byte(text, 7), text.len());
+    }
+
     #[test]
     fn test_compute_relative_path_within_workspace() {
         let workspace = tempdir().unwrap();
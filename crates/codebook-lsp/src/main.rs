@@ -1,16 +1,22 @@
+mod config_check;
 mod file_cache;
+mod git_diff;
 mod init_options;
 mod lint;
 mod lsp;
 mod lsp_logger;
+mod position_encoding;
+mod workspace;
 
 use clap::{Parser, Subcommand};
-use codebook_config::{CodebookConfig, CodebookConfigFile, ConfigError};
+use codebook::{Codebook, FetchStatus};
+use codebook_config::{CodebookConfig, CodebookConfigFile, ConfigError, ConfigSettings};
 use log::{LevelFilter, debug, info};
 use lsp::Backend;
 use lsp_logger::LspLogger;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tower_lsp::{LspService, Server};
 
 #[derive(Parser)]
@@ -21,6 +27,24 @@ struct Cli {
     #[arg(short, long, value_name = "FOLDER")]
     root: Option<PathBuf>,
 
+    /// Project config file to use instead of searching up from --root.
+    /// Path globs in it stay relative to its own directory, and the global
+    /// config is still merged in unless it sets `use_global = false`. The
+    /// language server takes `configPath` from the editor instead.
+    #[arg(short, long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Most threads to check with. Defaults to the number of logical CPUs;
+    /// 1 checks serially
+    #[arg(
+        long,
+        value_name = "N",
+        env = "CODEBOOK_THREADS",
+        global = true,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    threads: Option<u16>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -32,26 +56,110 @@ enum Commands {
     /// Remove server cache
     Clean {},
     /// Check files for spelling errors
+    #[command(visible_alias = "check")]
     Lint {
-        /// Files or glob patterns to spell-check
-        #[arg(required = true)]
+        /// Files or glob patterns to spell-check. With --diff, defaults to
+        /// every changed file
+        #[arg(required_unless_present_any = ["stdin", "diff"], conflicts_with = "stdin")]
         files: Vec<String>,
+        /// Read the text to check from stdin instead of files
+        #[arg(long)]
+        stdin: bool,
+        /// File name for stdin content, used to detect its language and
+        /// match ignore/include paths
+        #[arg(long, value_name = "PATH", requires = "stdin")]
+        stdin_filename: Option<String>,
         /// Only report each misspelled word once, ignoring duplicates across files
         #[arg(short = 'u', long)]
         unique: bool,
         /// Show spelling suggestions for each misspelled word
         #[arg(short = 's', long)]
         suggest: bool,
+        /// Show the line of each misspelling with the word underlined
+        #[arg(long)]
+        snippets: bool,
+        /// Instead of each finding, list misspelled words by how often they
+        /// occur, with where each was first seen
+        #[arg(long, conflicts_with_all = ["unique", "suggest", "snippets"])]
+        summary: bool,
+        /// Also use this dictionary for this run (repeatable)
+        #[arg(long = "dictionary", value_name = "ID")]
+        dictionaries: Vec<String>,
+        /// Allow this word for this run (repeatable)
+        #[arg(long = "allow", value_name = "WORD")]
+        words: Vec<String>,
+        /// Flag this word for this run (repeatable)
+        #[arg(long = "flag", value_name = "WORD")]
+        flag_words: Vec<String>,
+        /// Only report misspellings on lines changed since --diff-base, per
+        /// `git diff`
+        #[arg(long, conflicts_with = "stdin")]
+        diff: bool,
+        /// Git revision to diff against
+        #[arg(long, value_name = "REV", default_value = "HEAD", requires = "diff")]
+        diff_base: String,
+        /// Also check files in directories and globs that `ignore_paths` or
+        /// `include_paths` leave out. Files named directly are always
+        /// checked
+        #[arg(short, long)]
+        force: bool,
+        /// Review each misspelling and pick a suggestion, add the word to
+        /// the dictionary, ignore or skip it. Accepted fixes are written back
+        #[arg(short, long, conflicts_with_all = ["stdin", "diff", "summary"])]
+        interactive: bool,
+    },
+    /// Replace misspellings that have a clear correction
+    Fix {
+        /// Files or glob patterns to fix
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Rewrite the files; without this, only show what would change
+        #[arg(short, long)]
+        write: bool,
     },
+    /// List the built-in dictionary IDs that can be used in `dictionaries`
+    Dictionaries {},
+    /// Download every dictionary the config may need, for later offline use
+    Fetch {},
+    /// Print a JSON Schema for codebook.toml, for editors to validate and
+    /// complete config files
+    Schema {},
     /// Add words to the dictionary
     Add {
         /// Words to add to the allowlist
-        #[arg(required = true)]
+        #[arg(required_unless_present = "from")]
         words: Vec<String>,
+        /// Also add the words listed in this file, one per line. Blank lines
+        /// and lines starting with `#` are skipped.
+        #[arg(long, value_name = "FILE")]
+        from: Option<PathBuf>,
         /// Add to the global config instead of the project config
         #[arg(short, long)]
         global: bool,
     },
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Check that the config loads, its patterns compile, its dictionary IDs
+    /// exist and its dictionary files are there. Exits nonzero on problems
+    Check {
+        /// Config file to check instead of --config or the one found from
+        /// --root
+        path: Option<PathBuf>,
+    },
+    /// Print the settings in effect, merged from every config, as TOML, with
+    /// comments saying which config set each one
+    Show {
+        /// Config file to show instead of --config or the one found from
+        /// --root
+        path: Option<PathBuf>,
+    },
 }
 
 #[tokio::main(flavor = "current_thread")]
@@ -60,7 +168,10 @@ async fn main() {
 
     // Initialize logger early with stderr output and buffering.
     // Default to INFO for LSP, WARN for lint (to suppress LSP-oriented noise).
-    let is_lint = matches!(cli.command, Some(Commands::Lint { .. }));
+    let is_lint = matches!(
+        cli.command,
+        Some(Commands::Lint { .. } | Commands::Fix { .. })
+    );
     let log_level = match env::var("RUST_LOG").as_deref() {
         Ok("debug") => LevelFilter::Debug,
         Ok("info") => LevelFilter::Info,
@@ -74,54 +185,317 @@ async fn main() {
         Some(path) => path,
         None => Path::new("."),
     };
+    let config_path = cli.config.as_deref();
+    // Loading falls back to defaults for a missing file, which would quietly
+    // check against the wrong config. `add` creates it; the `config`
+    // commands report it themselves.
+    if let Some(path) = config_path
+        && !path.is_file()
+        && matches!(
+            cli.command,
+            Some(
+                Commands::Lint { .. }
+                    | Commands::Fix { .. }
+                    | Commands::Fetch {}
+                    | Commands::Clean {}
+            )
+        )
+    {
+        eprintln!("error: config file {} not found", path.display());
+        std::process::exit(2);
+    }
+
+    // Caps the pool dictionary lookups run on; unset leaves rayon's default
+    if let Some(threads) = cli.threads
+        && let Err(e) = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.into())
+            .build_global()
+    {
+        eprintln!("error: failed to start {threads} threads: {e}");
+        std::process::exit(2);
+    }
 
     match &cli.command {
         Some(Commands::Serve {}) => {
             serve_lsp(root).await;
         }
         Some(Commands::Clean {}) => {
-            let config = CodebookConfigFile::default();
+            // Loaded for a configured cache_dir; defaults if the config is broken
+            let config = lint::load_config(root, config_path).unwrap_or_default();
             info!("Cleaning: {:?}", config.cache_dir);
             config.clean_cache()
         }
         Some(Commands::Lint {
             files,
+            stdin,
+            stdin_filename,
             unique,
             suggest,
+            snippets,
+            summary,
+            dictionaries,
+            words,
+            flag_words,
+            diff,
+            diff_base,
+            force,
+            interactive,
         }) => {
-            let code = match lint::run_lint(files, root, *unique, *suggest) {
+            // Linting is synchronous and may download dictionaries with a
+            // blocking HTTP client, which panics when driven from inside the
+            // async runtime; run it on the blocking pool instead.
+            let (files, stdin_filename, root) =
+                (files.clone(), stdin_filename.clone(), root.to_path_buf());
+            let config_path = cli.config.clone();
+            let diff_base = diff.then(|| diff_base.clone());
+            let (stdin, interactive) = (*stdin, *interactive);
+            let options = lint::LintOptions {
+                force: *force,
+                unique: *unique,
+                suggest: *suggest,
+                snippets: *snippets,
+                summary: *summary,
+            };
+            // Merged over the loaded config in memory; never saved
+            let overrides = (!dictionaries.is_empty()
+                || !words.is_empty()
+                || !flag_words.is_empty())
+            .then(|| ConfigSettings {
+                dictionaries: dictionaries.clone(),
+                words: words.clone(),
+                flag_words: flag_words.clone(),
+                ..Default::default()
+            });
+            let result = tokio::task::spawn_blocking(move || {
+                if interactive {
+                    lint::run_lint_interactive(
+                        &files,
+                        &root,
+                        config_path.as_deref(),
+                        overrides,
+                        options.force,
+                    )
+                } else if stdin {
+                    lint::run_lint_stdin(
+                        stdin_filename.as_deref(),
+                        &root,
+                        config_path.as_deref(),
+                        overrides,
+                        options,
+                    )
+                } else {
+                    lint::run_lint(
+                        &files,
+                        &root,
+                        config_path.as_deref(),
+                        overrides,
+                        diff_base.as_deref(),
+                        options,
+                    )
+                }
+            })
+            .await
+            .unwrap_or(lint::LintResult::Failure);
+            let code = match result {
+                lint::LintResult::Clean => 0,
+                lint::LintResult::Errors => 1,
+                lint::LintResult::Failure => 2,
+            };
+            std::process::exit(code);
+        }
+        Some(Commands::Fix { files, write }) => {
+            // Blocking HTTP for dictionary downloads; see Lint above.
+            let (files, root, write) = (files.clone(), root.to_path_buf(), *write);
+            let config_path = cli.config.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                lint::run_fix(&files, &root, config_path.as_deref(), write)
+            })
+            .await
+            .unwrap_or(lint::LintResult::Failure);
+            let code = match result {
                 lint::LintResult::Clean => 0,
                 lint::LintResult::Errors => 1,
                 lint::LintResult::Failure => 2,
             };
             std::process::exit(code);
         }
-        Some(Commands::Add { words, global }) => {
-            if let Err(e) = add_words(root, words, *global) {
+        Some(Commands::Dictionaries {}) => print_dictionaries(),
+        Some(Commands::Schema {}) => print_schema(),
+        Some(Commands::Fetch {}) => {
+            // Downloads use a blocking HTTP client; keep them off the runtime.
+            let (root, config_path) = (root.to_path_buf(), cli.config.clone());
+            let ok = tokio::task::spawn_blocking(move || {
+                fetch_dictionaries(&root, config_path.as_deref())
+            })
+            .await
+            .unwrap_or(false);
+            if !ok {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Show { path },
+        }) => {
+            let path = path.clone().or_else(|| cli.config.clone());
+            if let Some(path) = &path
+                && !path.is_file()
+            {
+                eprintln!("error: config file {} not found", path.display());
+                std::process::exit(2);
+            }
+            if let Err(e) = show_config(root, path) {
                 eprintln!("error: {e}");
                 std::process::exit(2);
             }
         }
+        Some(Commands::Add {
+            words,
+            from,
+            global,
+        }) => {
+            let glossary = match from {
+                Some(path) => std::fs::read_to_string(path).unwrap_or_else(|e| {
+                    eprintln!("error: failed to read {}: {e}", path.display());
+                    std::process::exit(2);
+                }),
+                None => String::new(),
+            };
+            let words: Vec<&str> = words
+                .iter()
+                .map(String::as_str)
+                .chain(
+                    glossary
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#')),
+                )
+                .collect();
+            if let Err(e) = add_words(root, config_path, &words, *global) {
+                eprintln!("error: {e}");
+                std::process::exit(2);
+            }
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Check { path },
+        }) => {
+            let ok = check_config(root, path.clone().or_else(|| cli.config.clone()));
+            if !ok {
+                std::process::exit(1);
+            }
+        }
         None => {}
     }
 }
 
+/// Load the project config (or `path`) with the global one, as a check
+/// would, and print any problems. Returns false if there were some.
+fn show_config(root: &Path, path: Option<PathBuf>) -> Result<(), ConfigError> {
+    let config = lint::load_config(root, path.as_deref())?;
+    print!("{}", config.effective_toml()?);
+    Ok(())
+}
+
+fn check_config(root: &Path, path: Option<PathBuf>) -> bool {
+    // Loading falls back to defaults for a missing override, which would pass
+    if let Some(path) = &path
+        && !path.is_file()
+    {
+        println!("error: {} not found", path.display());
+        return false;
+    }
+    let config = match CodebookConfigFile::load_with_overrides(Some(root), None, path) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("error: {e}");
+            return false;
+        }
+    };
+    let problems = config_check::check_config(&config);
+    for problem in &problems {
+        println!("error: {problem}");
+    }
+    let checked: Vec<String> = [
+        config.global_config_path(),
+        config.project_config_path(),
+        config.local_config_path(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|path| path.is_file())
+    .map(|path| path.display().to_string())
+    .collect();
+    let checked = match checked.as_slice() {
+        [] => "default settings".to_string(),
+        paths => paths.join(", "),
+    };
+    if problems.is_empty() {
+        println!("ok: {checked}");
+    } else {
+        println!("{} problem(s) in {checked}", problems.len());
+    }
+    problems.is_empty()
+}
+
+/// Prints every built-in dictionary as an aligned ID / type / name table.
+fn print_dictionaries() {
+    let dictionaries = codebook::dictionaries::available_dictionaries();
+    let id_width = dictionaries.iter().map(|d| d.id.len()).max().unwrap_or(0);
+    for d in dictionaries {
+        println!("{:id_width$}  {:8}  {}", d.id, d.kind.to_string(), d.name);
+    }
+}
+
+/// Prints the config file's JSON Schema, allowing the built-in dictionary IDs.
+fn print_schema() {
+    let ids: Vec<String> = codebook::dictionaries::available_dictionaries()
+        .into_iter()
+        .map(|d| d.id)
+        .collect();
+    let schema = ConfigSettings::json_schema(&ids);
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
+
+/// Downloads the dictionaries the project's config may use, printing what
+/// happened to each. Returns false if the config or any dictionary failed.
+fn fetch_dictionaries(root: &Path, config_path: Option<&Path>) -> bool {
+    let config = match lint::load_config(root, config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: failed to load config: {e}");
+            return false;
+        }
+    };
+    let codebook = Codebook::new(Arc::new(config));
+    let mut ok = true;
+    for (id, status) in codebook.fetch_dictionaries() {
+        match status {
+            FetchStatus::AlreadyCached => println!("cached      {id}"),
+            FetchStatus::Downloaded => println!("downloaded  {id}"),
+            FetchStatus::Failed(reason) => {
+                ok = false;
+                println!("failed      {id}: {reason}");
+            }
+        }
+    }
+    ok
+}
+
 /// Adds words to the project (or global) config's allowlist and saves the file,
 /// creating it if it doesn't exist yet.
-fn add_words(root: &Path, words: &[String], global: bool) -> Result<(), ConfigError> {
-    let config = CodebookConfigFile::load(Some(root))?;
-    let mut added = 0;
-    for word in words {
-        let inserted = if global {
-            config.add_word_global(word)
-        } else {
-            config.add_word(word)
-        };
-        if inserted {
-            added += 1;
-        } else {
-            println!("'{word}' is already in the dictionary");
-        }
+fn add_words(
+    root: &Path,
+    config_path: Option<&Path>,
+    words: &[&str],
+    global: bool,
+) -> Result<(), ConfigError> {
+    let config = lint::load_config(root, config_path)?;
+    let added = if global {
+        config.add_words_global(words)?
+    } else {
+        config.add_words(words)?
+    };
+    if added < words.len() {
+        println!("{} word(s) already in the dictionary", words.len() - added);
     }
 
     if added == 0 {
@@ -151,6 +525,10 @@ async fn serve_lsp(root: &Path) {
     info!("Starting Codebook Language Server v{version}-{build_profile}...");
     let (stdin, stdout) = (tokio::io::stdin(), tokio::io::stdout());
     let inner_root = root.to_owned();
-    let (service, socket) = LspService::new(|client| Backend::new(client, &inner_root));
+    let (service, socket) = LspService::build(|client| Backend::new(client, &inner_root))
+        .custom_method("codebook/isIgnored", Backend::is_ignored)
+        .custom_method("codebook/suggestions", Backend::suggestions)
+        .custom_method("codebook/dictionaries", Backend::dictionaries)
+        .finish();
     Server::new(stdin, stdout, socket).serve(service).await;
 }
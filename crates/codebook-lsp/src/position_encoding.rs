@@ -0,0 +1,190 @@
+use codebook::parser::get_word_from_string;
+use string_offsets::{AllConfig, OnlyLines, Pos, StringOffsets};
+use tower_lsp::lsp_types::{ClientCapabilities, PositionEncodingKind};
+
+/// How LSP positions count columns, negotiated with the client in
+/// `initialize`. UTF-16 is the protocol default; UTF-8 columns are plain
+/// byte offsets, so no UTF-16 table has to be built per check.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PositionEncoding {
+    Utf8,
+    #[default]
+    Utf16,
+}
+
+impl PositionEncoding {
+    /// Pick UTF-8 when the client offers it, else the UTF-16 default.
+    pub fn negotiate(capabilities: &ClientCapabilities) -> Self {
+        let offered = capabilities
+            .general
+            .as_ref()
+            .and_then(|general| general.position_encodings.as_deref())
+            .unwrap_or_default();
+        if offered.contains(&PositionEncodingKind::UTF8) {
+            PositionEncoding::Utf8
+        } else {
+            PositionEncoding::Utf16
+        }
+    }
+
+    /// The kind to advertise back in the server capabilities.
+    pub fn kind(self) -> PositionEncodingKind {
+        match self {
+            PositionEncoding::Utf8 => PositionEncodingKind::UTF8,
+            PositionEncoding::Utf16 => PositionEncodingKind::UTF16,
+        }
+    }
+
+    /// How many columns `c` takes up.
+    pub fn char_len(self, c: char) -> usize {
+        match self {
+            PositionEncoding::Utf8 => c.len_utf8(),
+            PositionEncoding::Utf16 => c.len_utf16(),
+        }
+    }
+
+    /// The text of `line` between columns `start` and `end`. Columns that
+    /// don't fall on character boundaries give an empty string.
+    pub fn slice(self, line: &str, start: usize, end: usize) -> String {
+        match self {
+            PositionEncoding::Utf8 => line
+                .get(start.min(line.len())..end.min(line.len()))
+                .unwrap_or_default()
+                .to_string(),
+            PositionEncoding::Utf16 => get_word_from_string(start, end, line),
+        }
+    }
+}
+
+/// Converts byte offsets in a document to positions in the negotiated
+/// encoding. Build once per document check.
+pub enum OffsetTable {
+    Utf8(StringOffsets<OnlyLines>),
+    Utf16(StringOffsets<AllConfig>),
+}
+
+impl OffsetTable {
+    pub fn new(text: &str, encoding: PositionEncoding) -> Self {
+        match encoding {
+            PositionEncoding::Utf8 => OffsetTable::Utf8(StringOffsets::new(text)),
+            PositionEncoding::Utf16 => OffsetTable::Utf16(StringOffsets::new(text)),
+        }
+    }
+
+    /// A table for byte offsets up to `end` only, which gives the same
+    /// positions as the full one without scanning the text after `end`.
+    pub fn covering(text: &str, end: usize, encoding: PositionEncoding) -> Self {
+        // One character past `end`, since the table puts the very end of
+        // its text on a line of its own
+        let mut end = (end + 1).min(text.len());
+        while !text.is_char_boundary(end) {
+            end += 1;
+        }
+        Self::new(&text[..end], encoding)
+    }
+
+    /// Zero-based line and column of the byte offset `byte`.
+    pub fn pos(&self, byte: usize) -> Pos {
+        match self {
+            OffsetTable::Utf8(offsets) => {
+                let line = offsets.utf8_to_line(byte);
+                Pos {
+                    line,
+                    col: byte - offsets.line_to_utf8_begin(line),
+                }
+            }
+            OffsetTable::Utf16(offsets) => offsets.utf8_to_utf16_pos(byte),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::GeneralClientCapabilities;
+
+    fn capabilities(encodings: Option<Vec<PositionEncodingKind>>) -> ClientCapabilities {
+        ClientCapabilities {
+            general: Some(GeneralClientCapabilities {
+                position_encodings: encodings,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            PositionEncoding::negotiate(&ClientCapabilities::default()),
+            PositionEncoding::Utf16
+        );
+        assert_eq!(
+            PositionEncoding::negotiate(&capabilities(Some(vec![PositionEncodingKind::UTF16]))),
+            PositionEncoding::Utf16
+        );
+        assert_eq!(
+            PositionEncoding::negotiate(&capabilities(Some(vec![
+                PositionEncodingKind::UTF16,
+                PositionEncodingKind::UTF8,
+            ]))),
+            PositionEncoding::Utf8
+        );
+    }
+
+    #[test]
+    fn test_multibyte_positions() {
+        // "é" is 2 bytes and 1 UTF-16 unit, "🦀" is 4 bytes and 2 units
+        let text = "first\né 🦀 wrod\n";
+        let start = text.find("wrod").unwrap();
+        let end = start + "wrod".len();
+
+        let utf16 = OffsetTable::new(text, PositionEncoding::Utf16);
+        assert_eq!(utf16.pos(start), Pos { line: 1, col: 5 });
+        assert_eq!(utf16.pos(end), Pos { line: 1, col: 9 });
+
+        let utf8 = OffsetTable::new(text, PositionEncoding::Utf8);
+        assert_eq!(utf8.pos(start), Pos { line: 1, col: 8 });
+        assert_eq!(utf8.pos(end), Pos { line: 1, col: 12 });
+
+        // A table covering only up to the word gives the same positions
+        for encoding in [PositionEncoding::Utf8, PositionEncoding::Utf16] {
+            let full = OffsetTable::new(text, encoding);
+            let covering = OffsetTable::covering(text, end, encoding);
+            assert_eq!(covering.pos(start), full.pos(start));
+            assert_eq!(covering.pos(end), full.pos(end));
+        }
+
+        // Columns read back to the same word in either encoding
+        let line = text.lines().nth(1).unwrap();
+        assert_eq!(PositionEncoding::Utf16.slice(line, 5, 9), "wrod");
+        assert_eq!(PositionEncoding::Utf8.slice(line, 8, 12), "wrod");
+        // Mid-character columns don't panic
+        assert_eq!(PositionEncoding::Utf8.slice(line, 1, 12), "");
+    }
+
+    #[test]
+    fn test_crlf_positions() {
+        // Editors put `\r\n` at the end of a line, so columns on the next
+        // line start from zero and match the `\n`-only text
+        let crlf = "first\r\né 🦀 wrod\r\nlast wrod\r\n";
+        let lf = crlf.replace("\r\n", "\n");
+        for encoding in [PositionEncoding::Utf8, PositionEncoding::Utf16] {
+            let crlf_table = OffsetTable::new(crlf, encoding);
+            let lf_table = OffsetTable::new(&lf, encoding);
+            let crlf_words = crlf.match_indices("wrod").map(|(i, _)| i);
+            let lf_words = lf.match_indices("wrod").map(|(i, _)| i);
+            for (crlf_start, lf_start) in crlf_words.zip(lf_words) {
+                let start = crlf_table.pos(crlf_start);
+                let end = crlf_table.pos(crlf_start + 4);
+                assert_eq!(start, lf_table.pos(lf_start), "{encoding:?}");
+                assert_eq!(end, lf_table.pos(lf_start + 4), "{encoding:?}");
+                // The columns read back to the word on the editor's line
+                let line = crlf.lines().nth(start.line).unwrap();
+                assert_eq!(encoding.slice(line, start.col, end.col), "wrod");
+            }
+            // The end of a line is before its `\r`
+            assert_eq!(crlf_table.pos(5), Pos { line: 0, col: 5 });
+        }
+    }
+}
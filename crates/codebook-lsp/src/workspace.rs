@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use codebook::Codebook;
+use codebook::dictionaries::manager::DictionaryManager;
+use codebook_config::CodebookConfigFile;
+
+use crate::lsp::compute_relative_path;
+
+/// A workspace folder, with the config and checker for the files in it.
+pub struct WorkspaceRoot {
+    pub dir: PathBuf,
+    /// Cached canonicalized `dir`, for efficient relative path computation
+    dir_canonical: Option<PathBuf>,
+    pub config: Arc<CodebookConfigFile>,
+    pub codebook: Arc<Codebook>,
+}
+
+impl WorkspaceRoot {
+    pub fn new(dir: &Path, config: Arc<CodebookConfigFile>, codebook: Arc<Codebook>) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            dir_canonical: dir.canonicalize().ok(),
+            config,
+            codebook,
+        }
+    }
+
+    /// `file_path` relative to this root, or absolute when it's outside.
+    pub fn relative_path(&self, file_path: &Path) -> String {
+        compute_relative_path(&self.dir, self.dir_canonical.as_deref(), file_path)
+    }
+}
+
+/// The client's workspace folders, each loaded with its own config the
+/// first time one of its files is checked. Files in no folder belong to
+/// the default root, the directory the server was started in. A directory
+/// inside a folder with a project config of its own is a root too.
+#[derive(Default)]
+pub struct WorkspaceRoots {
+    folders: RwLock<Vec<PathBuf>>,
+    loaded: RwLock<HashMap<PathBuf, Arc<WorkspaceRoot>>>,
+    /// `owning_dir` by the directory of the file, so checking a document
+    /// doesn't look for config files in every directory above it each time
+    owning_dirs: RwLock<HashMap<PathBuf, PathBuf>>,
+}
+
+impl WorkspaceRoots {
+    pub fn add_folder(&self, dir: PathBuf) {
+        let mut folders = self.folders.write().unwrap();
+        if !folders.contains(&dir) {
+            folders.push(dir);
+            self.forget_owning_dirs();
+        }
+    }
+
+    /// Stop using `dir` as a root, along with the roots nested in it. Its
+    /// files go to the next folder up, or the default root.
+    pub fn remove_folder(&self, dir: &Path) {
+        self.folders.write().unwrap().retain(|d| d != dir);
+        self.loaded
+            .write()
+            .unwrap()
+            .retain(|loaded, _| !loaded.starts_with(dir));
+        self.forget_owning_dirs();
+    }
+
+    /// Look for project configs again on the next `owning_dir`, after one
+    /// may have been created or deleted.
+    pub fn forget_owning_dirs(&self) {
+        self.owning_dirs.write().unwrap().clear();
+    }
+
+    /// The directory of the root `file_path` belongs to: the nearest
+    /// directory with a project config between the file and the innermost
+    /// folder containing it, else that folder, else `default_dir`. Cached per
+    /// directory until `forget_owning_dirs`.
+    pub fn owning_dir(&self, file_path: &Path, default_dir: &Path) -> PathBuf {
+        let Some(file_dir) = file_path.parent() else {
+            return default_dir.to_path_buf();
+        };
+        if let Some(dir) = self.owning_dirs.read().unwrap().get(file_dir) {
+            return dir.clone();
+        }
+        let folder = self
+            .folders
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|dir| file_path.starts_with(dir))
+            .max_by_key(|dir| dir.components().count())
+            .map_or_else(|| default_dir.to_path_buf(), Clone::clone);
+        // The folder's own config, or one above it, is found when the root
+        // loads; only those below it need looking for here
+        let dir = file_dir
+            .ancestors()
+            .take_while(|dir| *dir != folder && dir.starts_with(&folder))
+            .find(|dir| CodebookConfigFile::project_config_in(dir).is_some())
+            .map_or(folder, Path::to_path_buf);
+        self.owning_dirs
+            .write()
+            .unwrap()
+            .insert(file_dir.to_path_buf(), dir.clone());
+        dir
+    }
+
+    /// The root for `dir`, loaded with `load` the first time it's asked
+    /// for. `load` runs without any lock held, so it may look up other
+    /// roots; if two callers race, the first root stored wins.
+    pub fn get_or_load(
+        &self,
+        dir: &Path,
+        load: impl FnOnce(&Path) -> Arc<WorkspaceRoot>,
+    ) -> Arc<WorkspaceRoot> {
+        if let Some(root) = self.loaded.read().unwrap().get(dir) {
+            return root.clone();
+        }
+        let root = load(dir);
+        self.loaded
+            .write()
+            .unwrap()
+            .entry(dir.to_path_buf())
+            .or_insert(root)
+            .clone()
+    }
+
+    /// Every root loaded so far.
+    pub fn loaded(&self) -> Vec<Arc<WorkspaceRoot>> {
+        self.loaded.read().unwrap().values().cloned().collect()
+    }
+}
+
+/// One dictionary manager per cache dir, so the checkers of roots that
+/// share a cache dir load each dictionary once, and a root with its own
+/// `cache_dir` downloads there.
+#[derive(Default)]
+pub struct DictionaryManagers(Mutex<HashMap<PathBuf, Arc<DictionaryManager>>>);
+
+impl DictionaryManagers {
+    /// The manager for `cache_dir`, made the first time it's asked for.
+    pub fn get(&self, cache_dir: &Path) -> Arc<DictionaryManager> {
+        self.0
+            .lock()
+            .unwrap()
+            .entry(cache_dir.to_path_buf())
+            .or_insert_with(|| Arc::new(DictionaryManager::new(&cache_dir.to_path_buf())))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codebook::queries::LanguageType;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn load(dir: &Path) -> Arc<WorkspaceRoot> {
+        let config = Arc::new(CodebookConfigFile::load(Some(dir)).unwrap());
+        // Test builds deny network access (deny-network feature)
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../codebook/tests/fixtures/dictionaries");
+        let codebook = Arc::new(Codebook::with_dictionary_dir(
+            config.clone(),
+            Some(fixtures),
+        ));
+        Arc::new(WorkspaceRoot::new(dir, config, codebook))
+    }
+
+    #[test]
+    fn test_roots_use_their_own_config() {
+        let workspace = tempdir().unwrap();
+        let frontend = workspace.path().join("frontend");
+        let backend = workspace.path().join("backend");
+        for (dir, word) in [(&frontend, "vuexy"), (&backend, "tokioz")] {
+            fs::create_dir_all(dir.join("src")).unwrap();
+            fs::write(dir.join("codebook.toml"), format!("words = [\"{word}\"]\n")).unwrap();
+        }
+        let roots = WorkspaceRoots::default();
+        roots.add_folder(frontend.clone());
+        roots.add_folder(backend.clone());
+
+        let text = "vuexy and tokioz";
+        let flagged = |file: &Path| {
+            let dir = roots.owning_dir(file, workspace.path());
+            let root = roots.get_or_load(&dir, load);
+            let relative = root.relative_path(file);
+            root.codebook
+                .spell_check(text, Some(LanguageType::Text), Some(&relative))
+                .into_iter()
+                .map(|r| r.word)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(flagged(&frontend.join("src/app.txt")), ["tokioz"]);
+        assert_eq!(flagged(&backend.join("src/main.txt")), ["vuexy"]);
+        assert_eq!(roots.loaded().len(), 2);
+        // Loaded once, then reused
+        let dir = roots.owning_dir(&frontend.join("a.txt"), workspace.path());
+        let first = roots.get_or_load(&dir, load);
+        assert!(Arc::ptr_eq(&first, &roots.get_or_load(&dir, load)));
+    }
+
+    #[test]
+    fn test_nested_config_is_its_own_root() {
+        let workspace = tempdir().unwrap();
+        let app = workspace.path().join("app");
+        let docs = app.join("docs");
+        fs::create_dir_all(docs.join("guide")).unwrap();
+        fs::write(app.join("codebook.toml"), "words = [\"vuexy\"]\n").unwrap();
+        fs::write(docs.join("codebook.toml"), "words = [\"tokioz\"]\n").unwrap();
+        let roots = WorkspaceRoots::default();
+        roots.add_folder(app.clone());
+
+        let file = docs.join("guide/intro.txt");
+        fs::write(&file, "").unwrap();
+        assert_eq!(roots.owning_dir(&file, workspace.path()), docs);
+        assert_eq!(
+            roots.owning_dir(&app.join("main.txt"), workspace.path()),
+            app
+        );
+
+        let root = roots.get_or_load(&docs, load);
+        let relative = root.relative_path(&file);
+        assert_eq!(relative, "guide/intro.txt");
+        let flagged: Vec<_> = root
+            .codebook
+            .spell_check(
+                "vuexy and tokioz",
+                Some(LanguageType::Text),
+                Some(&relative),
+            )
+            .into_iter()
+            .map(|r| r.word)
+            .collect();
+        assert_eq!(flagged, ["vuexy"]);
+    }
+
+    #[test]
+    fn test_owning_dir_cached_until_forgotten() {
+        let workspace = tempdir().unwrap();
+        let app = workspace.path().join("app");
+        let docs = app.join("docs");
+        fs::create_dir_all(&docs).unwrap();
+        let roots = WorkspaceRoots::default();
+        roots.add_folder(app.clone());
+        let file = docs.join("intro.txt");
+        assert_eq!(roots.owning_dir(&file, workspace.path()), app);
+
+        fs::write(docs.join("codebook.toml"), "").unwrap();
+        assert_eq!(roots.owning_dir(&file, workspace.path()), app);
+        roots.forget_owning_dirs();
+        assert_eq!(roots.owning_dir(&file, workspace.path()), docs);
+
+        // Removing the folder drops the roots nested in it too
+        roots.get_or_load(&app, load);
+        roots.get_or_load(&docs, load);
+        roots.remove_folder(&app);
+        assert!(roots.loaded().is_empty());
+        assert_eq!(roots.owning_dir(&file, workspace.path()), docs);
+    }
+
+    #[test]
+    fn test_dictionary_managers_by_cache_dir() {
+        let managers = DictionaryManagers::default();
+        let shared = managers.get(Path::new("/cache/a"));
+        assert!(Arc::ptr_eq(&shared, &managers.get(Path::new("/cache/a"))));
+        assert!(!Arc::ptr_eq(&shared, &managers.get(Path::new("/cache/b"))));
+    }
+
+    #[test]
+    fn test_owning_dir() {
+        let roots = WorkspaceRoots::default();
+        let default = Path::new("/work");
+        roots.add_folder(PathBuf::from("/work/app"));
+        roots.add_folder(PathBuf::from("/work/app/packages/ui"));
+        let owner = |file: &str| roots.owning_dir(Path::new(file), default);
+        assert_eq!(owner("/work/app/src/main.rs"), Path::new("/work/app"));
+        assert_eq!(
+            owner("/work/app/packages/ui/button.ts"),
+            Path::new("/work/app/packages/ui")
+        );
+        // A folder name that is only a prefix doesn't own the file
+        assert_eq!(owner("/work/application/x.rs"), default);
+
+        roots.remove_folder(Path::new("/work/app/packages/ui"));
+        assert_eq!(
+            owner("/work/app/packages/ui/button.ts"),
+            Path::new("/work/app")
+        );
+    }
+}
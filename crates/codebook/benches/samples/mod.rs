@@ -0,0 +1,163 @@
+//! Sample documents shared by the throughput benchmarks and the
+//! `codebook --benchmark` binary.
+
+// Each target that includes this module only uses part of it
+#![allow(dead_code)]
+
+use codebook::queries::LanguageType;
+
+/// The Beowulf translation: a large plain-text document.
+pub const LARGE_TEXT: &str = include_str!("../../tests/examples/wulf.txt");
+
+/// One representative document per language, with a few misspellings.
+pub const SAMPLES: &[(LanguageType, &str)] = &[
+    (
+        LanguageType::Bash,
+        include_str!("../../tests/examples/example.sh"),
+    ),
+    (
+        LanguageType::C,
+        include_str!("../../tests/examples/example.c"),
+    ),
+    (
+        LanguageType::CSharp,
+        include_str!("../../tests/examples/example.cs"),
+    ),
+    (
+        LanguageType::Cpp,
+        include_str!("../../tests/examples/example.cpp"),
+    ),
+    (
+        LanguageType::Css,
+        include_str!("../../tests/examples/example.css"),
+    ),
+    (
+        LanguageType::Dart,
+        include_str!("../../tests/examples/example.dart"),
+    ),
+    (
+        LanguageType::Elixir,
+        include_str!("../../tests/examples/example.ex"),
+    ),
+    (
+        LanguageType::Erlang,
+        include_str!("../../tests/examples/example.erl"),
+    ),
+    (
+        LanguageType::Gettext,
+        include_str!("../../tests/examples/example.po"),
+    ),
+    (
+        LanguageType::Go,
+        include_str!("../../tests/examples/example.go"),
+    ),
+    (
+        LanguageType::Graphql,
+        include_str!("../../tests/examples/example.graphql"),
+    ),
+    (
+        LanguageType::HTML,
+        include_str!("../../tests/examples/example.html"),
+    ),
+    (
+        LanguageType::Haskell,
+        include_str!("../../tests/examples/example.hs"),
+    ),
+    (
+        LanguageType::Java,
+        include_str!("../../tests/examples/example.java"),
+    ),
+    (
+        LanguageType::Javascript,
+        include_str!("../../tests/examples/example.js"),
+    ),
+    (
+        LanguageType::Just,
+        include_str!("../../tests/examples/example.just"),
+    ),
+    (
+        LanguageType::Latex,
+        include_str!("../../tests/examples/example.tex"),
+    ),
+    (
+        LanguageType::Lua,
+        include_str!("../../tests/examples/example.lua"),
+    ),
+    (
+        LanguageType::Markdown,
+        include_str!("../../tests/examples/example.md"),
+    ),
+    (
+        LanguageType::OCaml,
+        include_str!("../../tests/examples/example.ml"),
+    ),
+    (
+        LanguageType::Odin,
+        include_str!("../../tests/examples/example.odin"),
+    ),
+    (
+        LanguageType::Php,
+        include_str!("../../tests/examples/example.php"),
+    ),
+    (
+        LanguageType::Python,
+        include_str!("../../tests/examples/example.py"),
+    ),
+    (
+        LanguageType::R,
+        include_str!("../../tests/examples/example.r"),
+    ),
+    (
+        LanguageType::Ruby,
+        include_str!("../../tests/examples/example.rb"),
+    ),
+    (
+        LanguageType::Rust,
+        include_str!("../../tests/examples/example.rs"),
+    ),
+    (
+        LanguageType::Swift,
+        include_str!("../../tests/examples/example.swift"),
+    ),
+    (
+        LanguageType::TOML,
+        include_str!("../../tests/examples/example.toml"),
+    ),
+    (
+        LanguageType::Text,
+        include_str!("../../tests/examples/example.txt"),
+    ),
+    (
+        LanguageType::Typescript,
+        include_str!("../../tests/examples/example.ts"),
+    ),
+    (
+        LanguageType::Tsx,
+        include_str!("../../tests/examples/example.tsx"),
+    ),
+    (
+        LanguageType::Typst,
+        include_str!("../../tests/examples/example.typ"),
+    ),
+    (
+        LanguageType::VHDL,
+        include_str!("../../tests/examples/example.vhd"),
+    ),
+    (
+        LanguageType::YAML,
+        include_str!("../../tests/examples/example.yaml"),
+    ),
+    (
+        LanguageType::Zig,
+        include_str!("../../tests/examples/example.zig"),
+    ),
+];
+
+/// The sample document for `language`.
+pub fn sample(language: LanguageType) -> &'static str {
+    SAMPLES
+        .iter()
+        .find(|(l, _)| *l == language)
+        .map(|(_, text)| *text)
+        .unwrap_or_else(|| panic!("no sample for {language:?}"))
+}
@@ -0,0 +1,170 @@
+//! Throughput benchmarks for `spell_check`, the parser and the splitter,
+//! the time a new `Codebook` takes to its first diagnostics, and
+//! suggestions for words with and without close edits.
+//!
+//! Run with `cargo bench -p codebook`. Pass a substring to only run
+//! matching benchmarks, e.g. `cargo bench -p codebook -- parser/rust`.
+//! Dictionaries come from the test fixtures, so no network is needed.
+
+mod samples;
+
+use codebook::Codebook;
+use codebook::dictionaries::dictionary::{Dictionary, HunspellDictionary};
+use codebook::parser::extract_all_words;
+use codebook::queries::LanguageType;
+use codebook::splitter::{SplitRef, split_into};
+use codebook_config::CodebookConfigMemory;
+use samples::{LARGE_TEXT, SAMPLES};
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Each benchmark runs for at least this long and this many iterations.
+const MIN_DURATION: Duration = Duration::from_millis(500);
+const MIN_ITERATIONS: u32 = 10;
+
+fn main() {
+    // cargo passes `--bench`; the first other argument is the filter
+    let filter = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .unwrap_or_default()
+        .to_lowercase();
+    let enabled = |name: &str| name.contains(&filter);
+
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dictionaries");
+    let new_codebook = || {
+        Codebook::with_dictionary_dir(
+            Arc::new(CodebookConfigMemory::default()),
+            Some(fixtures.clone()),
+        )
+    };
+    let codebook = new_codebook();
+
+    println!("{:<28} {:>14} {:>12}", "benchmark", "time/iter", "MB/s");
+
+    for (language, text) in SAMPLES {
+        let name = format!("spell_check/{language:?}").to_lowercase();
+        if enabled(&name) {
+            bench(&name, text.len(), || {
+                black_box(codebook.spell_check(text, Some(*language), None));
+            });
+        }
+    }
+    if enabled("spell_check/large_text") {
+        bench("spell_check/large_text", LARGE_TEXT.len(), || {
+            black_box(codebook.spell_check(LARGE_TEXT, Some(LanguageType::Text), None));
+        });
+    }
+
+    for (language, text) in SAMPLES {
+        let name = format!("parser/{language:?}").to_lowercase();
+        if enabled(&name) {
+            bench(&name, text.len(), || {
+                black_box(extract_all_words(text, *language, &|_, _| true, &[]));
+            });
+        }
+    }
+
+    // A fresh Codebook parses its dictionaries on the first check, unless
+    // `preload_dictionaries` did already, as the server does at start
+    let (language, text) = SAMPLES[0];
+    if enabled("first_check/cold") {
+        bench_setup("first_check/cold", text.len(), new_codebook, |codebook| {
+            black_box(codebook.spell_check(text, Some(language), None));
+        });
+    }
+    if enabled("first_check/preloaded") {
+        let preloaded = || {
+            let codebook = new_codebook();
+            codebook.preload_dictionaries();
+            codebook
+        };
+        bench_setup("first_check/preloaded", text.len(), preloaded, |codebook| {
+            black_box(codebook.spell_check(text, Some(language), None));
+        });
+    }
+
+    // `suggest_filtered` isn't cached, so every iteration searches
+    let suggest = [
+        ("suggest/typo", "internationalizaton"),
+        (
+            "suggest/long_mangled",
+            "qwxzjkvbnmplqwxzjkvbnmplqwxzjkvbnmpl",
+        ),
+    ];
+    if suggest.iter().any(|(name, _)| enabled(name)) {
+        let dictionary = HunspellDictionary::new(
+            &fixtures.join("en_us.aff").to_string_lossy(),
+            &fixtures.join("en_us.dic").to_string_lossy(),
+        )
+        .unwrap();
+        for (name, word) in suggest {
+            if enabled(name) {
+                bench(name, word.len(), || {
+                    black_box(dictionary.suggest_filtered(word, 5, &|_| true));
+                });
+            }
+        }
+    }
+
+    if enabled("splitter") {
+        // The splitter expects whitespace-free tokens, as the parser gives it
+        let tokens: Vec<&str> = SAMPLES
+            .iter()
+            .flat_map(|(_, text)| text.split_whitespace())
+            .collect();
+        let bytes = tokens.iter().map(|t| t.len()).sum();
+        let mut parts: Vec<SplitRef> = Vec::new();
+        bench("splitter", bytes, || {
+            for token in &tokens {
+                split_into(token, &mut parts);
+                black_box(&parts);
+            }
+        });
+    }
+}
+
+/// Time `f` over `bytes` of input and print the mean time and throughput.
+fn bench(name: &str, bytes: usize, mut f: impl FnMut()) {
+    // Warm up caches, lazily compiled queries and dictionary loads
+    f();
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    while iterations < MIN_ITERATIONS || start.elapsed() < MIN_DURATION {
+        f();
+        iterations += 1;
+    }
+    report(name, bytes, iterations, start.elapsed());
+}
+
+/// Like `bench`, but each iteration gets a fresh value from `setup`, which
+/// isn't timed.
+fn bench_setup<T>(name: &str, bytes: usize, mut setup: impl FnMut() -> T, mut f: impl FnMut(T)) {
+    // Warm up lazily compiled queries
+    f(setup());
+
+    let start = Instant::now();
+    let mut elapsed = Duration::ZERO;
+    let mut iterations = 0;
+    while iterations < MIN_ITERATIONS || start.elapsed() < MIN_DURATION {
+        let value = setup();
+        let timed = Instant::now();
+        f(value);
+        elapsed += timed.elapsed();
+        iterations += 1;
+    }
+    report(name, bytes, iterations, elapsed);
+}
+
+/// Print the mean time and throughput of `iterations` runs over `bytes`.
+fn report(name: &str, bytes: usize, iterations: u32, elapsed: Duration) {
+    let per_iteration = elapsed / iterations;
+    let mb_per_sec = (bytes as f64 * f64::from(iterations)) / elapsed.as_secs_f64() / 1_000_000.0;
+    println!(
+        "{name:<28} {:>14} {mb_per_sec:>12.2}",
+        format!("{per_iteration:.2?}")
+    );
+}
@@ -3,6 +3,32 @@ use std::collections::{HashMap, HashSet};
 use crate::dictionaries::dictionary::Dictionary;
 use crate::parser::{TextRange, WordLocation};
 use codebook_config::{CodebookConfig, ConfigSettings};
+use rayon::prelude::*;
+
+/// Below this many unique words, dictionary lookups run on the calling
+/// thread: for typical edits the per-word checks are cache hits, and
+/// fanning out to the thread pool would cost more than it saves.
+const PARALLEL_CHECK_THRESHOLD: usize = 256;
+
+/// The category of capture a word came from, so prose and code can follow
+/// different rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordKind {
+    /// Comments, strings, and plain text
+    Text,
+    /// Identifiers (`@identifier.*` captures)
+    Code,
+}
+
+impl WordKind {
+    pub fn from_tag(tag: &str) -> Self {
+        if tag == "identifier" || tag.starts_with("identifier.") {
+            WordKind::Code
+        } else {
+            WordKind::Text
+        }
+    }
+}
 
 /// A candidate word extracted from a text node, with its position
 /// in original-document byte offsets. Borrows the word text from the
@@ -12,6 +38,12 @@ pub struct WordCandidate<'a> {
     pub word: &'a str,
     pub start_byte: usize,
     pub end_byte: usize,
+    pub kind: WordKind,
+    /// The query capture name the word came from; `None` for plain text
+    pub tag: Option<&'a str>,
+    /// The hyphenated compound the word is part of (`e-mail` for `mail`),
+    /// when `hyphenated_compounds` is on. A known compound isn't reported.
+    pub compound: Option<&'a str>,
 }
 
 /// Check candidate words against dictionaries and config rules.
@@ -24,59 +56,117 @@ pub fn check_words(
     config: &dyn CodebookConfig,
     resolved: Option<&ConfigSettings>,
 ) -> Vec<WordLocation> {
-    // Group candidates by word text, deduplicating identical spans.
-    let mut word_positions: HashMap<&str, HashSet<TextRange>> = HashMap::new();
-    for candidate in candidates {
-        let location = TextRange {
-            start_byte: candidate.start_byte,
-            end_byte: candidate.end_byte,
-        };
-        let added = word_positions
-            .entry(candidate.word)
-            .or_default()
-            .insert(location);
-
-        debug_assert!(
-            added,
-            "Two of the same locations found. Make a better query. Word: {}, Location: {:?}",
-            candidate.word, location
-        );
-    }
-
     let should_flag = |w: &str| match resolved {
         Some(s) => s.should_flag_word(w),
         None => config.should_flag_word(w),
     };
-    let min_word_length = match resolved {
-        Some(s) => s.min_word_length(),
-        None => config.get_min_word_length(),
+    let (min_length_code, min_length_text) = match resolved {
+        Some(s) => (s.min_word_length_code(), s.min_word_length_text()),
+        None => (
+            config.get_min_word_length_code(),
+            config.get_min_word_length_text(),
+        ),
     };
     let is_allowed = |w: &str| match resolved {
         Some(s) => s.is_allowed_word(w),
         None => config.is_allowed_word(w),
     };
 
-    // Check each unique word once
-    let mut results = Vec::new();
-    for (word, positions) in word_positions {
-        let positions: Vec<TextRange> = positions.into_iter().collect();
-        if should_flag(word) {
-            results.push(WordLocation::new(word.to_string(), positions));
+    // Hyphenated compounds are checked whole first; the parts of a known
+    // one are never looked up on their own
+    let mut compounds: HashMap<&str, bool> = HashMap::new();
+    for compound in candidates.iter().filter_map(|c| c.compound) {
+        compounds.entry(compound).or_insert_with(|| {
+            !should_flag(compound)
+                && (is_allowed(compound) || dictionaries.iter().any(|dict| dict.check(compound)))
+        });
+    }
+
+    // Group candidates by word text, deduplicating identical spans. The
+    // length threshold depends on where each occurrence was found, so it
+    // is applied here rather than per unique word; flagged words are
+    // reported whatever their length.
+    let mut word_positions: HashMap<&str, HashSet<TextRange>> = HashMap::new();
+    // The tag of each word's first occurrence in the document
+    let mut first_tags: HashMap<&str, (usize, Option<&str>)> = HashMap::new();
+    for candidate in candidates {
+        if candidate.compound.is_some_and(|c| compounds[c]) {
             continue;
         }
+        let min_word_length = match candidate.kind {
+            WordKind::Code => min_length_code,
+            WordKind::Text => min_length_text,
+        };
         // Compare characters, not bytes: multi-byte scripts (Cyrillic, Greek,
         // accented Latin) would otherwise pass the length filter early.
-        if word.chars().count() < min_word_length {
+        if candidate.word.chars().count() < min_word_length && !should_flag(candidate.word) {
             continue;
         }
-        if is_allowed(word) {
+        let location = TextRange {
+            start_byte: candidate.start_byte,
+            end_byte: candidate.end_byte,
+        };
+        // A query that captures the same node twice repeats its words;
+        // the set keeps each span once so nothing is reported twice
+        word_positions
+            .entry(candidate.word)
+            .or_default()
+            .insert(location);
+        let first = first_tags
+            .entry(candidate.word)
+            .or_insert((candidate.start_byte, candidate.tag));
+        if candidate.start_byte < first.0 {
+            *first = (candidate.start_byte, candidate.tag);
+        }
+    }
+
+    // Apply config rules to each unique word once, leaving the words that
+    // need a dictionary lookup
+    let mut results = Vec::new();
+    let mut to_check = Vec::new();
+    for (word, positions) in word_positions {
+        if should_flag(word) {
+            results.push(
+                WordLocation::new(word.to_string(), positions.into_iter().collect())
+                    .with_tag(first_tags[word].1),
+            );
             continue;
         }
-        let is_correct = dictionaries.iter().any(|dict| dict.check(word));
-        if !is_correct {
-            results.push(WordLocation::new(word.to_string(), positions));
+        if is_allowed(word) {
+            continue;
         }
+        to_check.push((word, positions));
     }
+
+    // Dictionaries are read-only (their caches lock internally) and result
+    // order doesn't matter, so large files check words in parallel.
+    let is_misspelled =
+        |(word, _): &(&str, HashSet<TextRange>)| !dictionaries.iter().any(|dict| dict.check(word));
+    let into_location = |(word, positions): (&str, HashSet<TextRange>)| {
+        WordLocation::new(word.to_string(), positions.into_iter().collect())
+            .with_tag(first_tags[word].1)
+    };
+    if to_check.len() >= PARALLEL_CHECK_THRESHOLD {
+        results.par_extend(
+            to_check
+                .into_par_iter()
+                .filter(is_misspelled)
+                .map(into_location),
+        );
+    } else {
+        results.extend(
+            to_check
+                .into_iter()
+                .filter(is_misspelled)
+                .map(into_location),
+        );
+    }
+
+    // Return results in document order so output is stable across runs
+    for result in &mut results {
+        result.locations.sort_unstable_by_key(|r| r.start_byte);
+    }
+    results.sort_unstable_by_key(|l| l.locations.first().map(|r| r.start_byte));
     results
 }
 
@@ -93,6 +183,9 @@ mod tests {
                 word,
                 start_byte: *start,
                 end_byte: *end,
+                kind: WordKind::Text,
+                tag: None,
+                compound: None,
             })
             .collect()
     }
@@ -118,6 +211,68 @@ mod tests {
         assert_eq!(results[0].locations.len(), 2);
     }
 
+    #[test]
+    fn test_check_words_overlapping_query() {
+        use streaming_iterator::StreamingIterator;
+        use tree_sitter::{Parser, Query, QueryCursor};
+        use unicode_segmentation::UnicodeSegmentation;
+
+        // Both patterns capture every comment, so each word comes up twice
+        let text = "// a wrld of wrds\nfn main() {}\n";
+        let language = tree_sitter_rust::LANGUAGE.into();
+        let query = Query::new(
+            &language,
+            "(line_comment) @comment\n(line_comment) @comment",
+        )
+        .unwrap();
+        let mut parser = Parser::new();
+        parser.set_language(&language).unwrap();
+        let tree = parser.parse(text, None).unwrap();
+        let mut candidates = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&query, tree.root_node(), text.as_bytes());
+        while let Some(match_) = matches.next() {
+            for capture in match_.captures {
+                let start = capture.node.start_byte();
+                let node_text = &text[start..capture.node.end_byte()];
+                let words: Vec<_> = node_text
+                    .split_word_bound_indices()
+                    .filter(|(_, word)| word.chars().all(char::is_alphabetic))
+                    .map(|(offset, word)| (word, start + offset, start + offset + word.len()))
+                    .collect();
+                candidates.extend(make_candidates(&words));
+            }
+        }
+        assert_eq!(candidates.len(), 8);
+
+        let dict = Arc::new(TextDictionary::new("a\nof\n"));
+        let config = Arc::new(codebook_config::CodebookConfigMemory::default());
+        let results = check_words(&candidates, &[dict], config.as_ref(), None);
+        let found: Vec<(&str, usize)> = results
+            .iter()
+            .map(|r| (r.word.as_str(), r.locations.len()))
+            .collect();
+        assert_eq!(found, vec![("wrld", 1), ("wrds", 1)]);
+    }
+
+    #[test]
+    fn test_check_words_returns_document_order() {
+        let dict = Arc::new(TextDictionary::new(""));
+        let config = Arc::new(codebook_config::CodebookConfigMemory::default());
+        let candidates = make_candidates(&[
+            ("zebr", 30, 34),
+            ("wrld", 20, 24),
+            ("abcx", 10, 14),
+            ("wrld", 5, 9),
+            ("qwer", 0, 4),
+        ]);
+        let results = check_words(&candidates, &[dict], config.as_ref(), None);
+        let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        assert_eq!(words, vec!["qwer", "wrld", "abcx", "zebr"]);
+        let starts: Vec<usize> = results[1].locations.iter().map(|r| r.start_byte).collect();
+        assert_eq!(starts, vec![5, 20]);
+    }
+
     #[test]
     fn test_check_words_respects_min_length() {
         let dict = Arc::new(TextDictionary::new(""));
@@ -141,6 +296,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_words_min_length_per_kind() {
+        let dict = Arc::new(TextDictionary::new(""));
+        let config = Arc::new(codebook_config::CodebookConfigMemory::new(
+            codebook_config::ConfigSettings {
+                min_word_length_code: Some(3),
+                min_word_length_text: Some(2),
+                ..Default::default()
+            },
+        ));
+        let mut candidates = make_candidates(&[("qx", 0, 2), ("db", 3, 5), ("db", 10, 12)]);
+        candidates[1].kind = WordKind::Code;
+        let mut results = check_words(&candidates, &[dict], config.as_ref(), None);
+        results.sort_by(|a, b| a.word.cmp(&b.word));
+        assert_eq!(results.len(), 2);
+        // "db" is only reported where it appeared in prose
+        assert_eq!(results[0].word, "db");
+        assert_eq!(results[0].locations[0].start_byte, 10);
+        assert_eq!(results[0].locations.len(), 1);
+        assert_eq!(results[1].word, "qx");
+    }
+
+    #[test]
+    fn test_check_words_flags_short_words() {
+        let dict = Arc::new(TextDictionary::new(""));
+        let config = Arc::new(codebook_config::CodebookConfigMemory::new(
+            codebook_config::ConfigSettings {
+                flag_words: vec!["ok".to_string()],
+                ..Default::default()
+            },
+        ));
+        let candidates = make_candidates(&[("ok", 0, 2)]);
+        let results = check_words(&candidates, &[dict], config.as_ref(), None);
+        assert_eq!(results.len(), 1, "Flagged words ignore the length limit");
+    }
+
+    #[test]
+    fn test_check_words_parallel_matches_sequential() {
+        // Enough unique words to cross PARALLEL_CHECK_THRESHOLD, half known
+        let known: Vec<String> = (0..PARALLEL_CHECK_THRESHOLD)
+            .map(|i| format!("known{i}"))
+            .collect();
+        let unknown: Vec<String> = (0..PARALLEL_CHECK_THRESHOLD)
+            .map(|i| format!("unknown{i}"))
+            .collect();
+        let dict = Arc::new(TextDictionary::new(&known.join("\n")));
+        let config = Arc::new(codebook_config::CodebookConfigMemory::default());
+        let mut words: Vec<(&str, usize, usize)> = Vec::new();
+        for (i, w) in known.iter().chain(unknown.iter()).enumerate() {
+            words.push((w.as_str(), i * 20, i * 20 + w.len()));
+        }
+        let candidates = make_candidates(&words);
+
+        let results = check_words(&candidates, &[dict], config.as_ref(), None);
+        let mut flagged: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+        flagged.sort_unstable();
+        let mut expected: Vec<&str> = unknown.iter().map(String::as_str).collect();
+        expected.sort_unstable();
+        assert_eq!(flagged, expected);
+    }
+
+    #[test]
+    fn test_check_words_same_for_any_thread_count() {
+        let known: Vec<String> = (0..PARALLEL_CHECK_THRESHOLD)
+            .map(|i| format!("known{i}"))
+            .collect();
+        let dictionaries: Vec<Arc<dyn Dictionary>> =
+            vec![Arc::new(TextDictionary::new(&known.join("\n")))];
+        let config = Arc::new(codebook_config::CodebookConfigMemory::default());
+        let words: Vec<String> = (0..PARALLEL_CHECK_THRESHOLD * 2)
+            .map(|i| format!("{}{i}", if i % 3 == 0 { "unknown" } else { "known" }))
+            .collect();
+        let spans: Vec<(&str, usize, usize)> = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.as_str(), i * 20, i * 20 + w.len()))
+            .collect();
+        let candidates = make_candidates(&spans);
+
+        let check_with = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| check_words(&candidates, &dictionaries, config.as_ref(), None))
+                .into_iter()
+                .map(|r| (r.word, r.locations))
+                .collect::<Vec<_>>()
+        };
+        let serial = check_with(1);
+        assert!(!serial.is_empty());
+        for threads in [2, 4, 8] {
+            assert_eq!(check_with(threads), serial, "{threads} threads");
+        }
+    }
+
     #[test]
     fn test_check_words_respects_allowed_words() {
         let dict = Arc::new(TextDictionary::new(""));
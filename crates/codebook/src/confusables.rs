@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use unicode_script::{Script, UnicodeScript};
+
+/// Letters that look like a letter of another script: Latin ones with a
+/// Cyrillic or Greek twin, and those twins. Mixing scripts is only
+/// suspicious when one of the letters could pass for the other script.
+const LOOK_ALIKES: &str = "ABCEHIJKMNOPSTXYZabcdehijlopsvxy\
+                           АВЕЅІЈКМНОРСТХУаеѕіјорсхуԁһӏ\
+                           ΑΒΕΖΗΙΚΜΝΟΡΤΥΧαιονρ";
+
+/// A letter from another script hiding in a word, like the Cyrillic `а`
+/// in `pаypal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Confusable {
+    pub character: char,
+    /// The script of `character`
+    pub script: Script,
+    /// The script most of the word's letters are in
+    pub word_script: Script,
+}
+
+impl fmt::Display for Confusable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is {}, the rest is {}",
+            self.character,
+            self.script.full_name(),
+            self.word_script.full_name()
+        )
+    }
+}
+
+/// The first look-alike letter in `word` that isn't in the script most of
+/// its letters are in, or `None` when the word sticks to one script or its
+/// other-script letters don't resemble anything.
+pub fn find_confusable(word: &str) -> Option<Confusable> {
+    if word.is_ascii() {
+        return None;
+    }
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for script in word.chars().map(|c| c.script()) {
+        if !matches!(script, Script::Common | Script::Inherited | Script::Unknown) {
+            *counts.entry(script).or_default() += 1;
+        }
+    }
+    if counts.len() < 2 {
+        return None;
+    }
+    // Ties go to Latin
+    let (&word_script, _) = counts
+        .iter()
+        .max_by_key(|&(&script, &count)| (count, script == Script::Latin))?;
+    word.chars()
+        .find(|&c| c.script() != word_script && LOOK_ALIKES.contains(c))
+        .map(|character| Confusable {
+            character,
+            script: character.script(),
+            word_script,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_confusable() {
+        // Cyrillic а (U+0430) in a Latin word
+        let found = find_confusable("p\u{430}ypal").unwrap();
+        assert_eq!(found.character, '\u{430}');
+        assert_eq!(found.script, Script::Cyrillic);
+        assert_eq!(found.word_script, Script::Latin);
+        assert_eq!(
+            found.to_string(),
+            "'\u{430}' is Cyrillic, the rest is Latin"
+        );
+        // A Latin e in a Russian word
+        let found = find_confusable("\u{43f}\u{440}\u{438}\u{432}e\u{442}").unwrap();
+        assert_eq!(found.character, 'e');
+        assert_eq!(found.word_script, Script::Cyrillic);
+        // Greek omicron in a Latin word
+        assert_eq!(
+            find_confusable("c\u{3bf}nfig").map(|c| c.script),
+            Some(Script::Greek)
+        );
+
+        assert_eq!(find_confusable("paypal"), None);
+        assert_eq!(find_confusable("привет"), None);
+        assert_eq!(find_confusable("café"), None);
+        // Mixed, but nothing looks like the other script
+        assert_eq!(find_confusable("abcжзд"), None);
+    }
+}
@@ -2,15 +2,48 @@ use lru::LruCache;
 use unicase::UniCase;
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     num::NonZeroUsize,
     path::PathBuf,
     sync::{Arc, RwLock},
 };
 
+/// Most suggestions `HunspellDictionary::suggest` returns.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Longest word, in chars, that `HunspellDictionary` runs its ngram search
+/// for. The search scans the whole word list and slows with the word's
+/// length; longer words are rarely real ones and only get edit suggestions.
+const MAX_NGRAM_WORD_LEN: usize = 30;
+
+/// (word, most suggestions asked for)
+type SuggestionKey = (String, usize);
+
 pub trait Dictionary: Send + Sync {
     fn check(&self, word: &str) -> bool;
+    /// Whether the dictionary has `word` in exactly this casing: unlike
+    /// `check`, `paris` doesn't match an entry for `Paris`.
+    fn check_exact_case(&self, word: &str) -> bool {
+        self.check(word)
+    }
     fn suggest(&self, word: &str) -> Vec<String>;
+    /// At most `max` suggestions, best first. Dictionaries with an
+    /// expensive search stop once they have `max`; others truncate
+    /// `suggest`.
+    fn suggest_limited(&self, word: &str, max: usize) -> Vec<String> {
+        let mut suggestions = self.suggest(word);
+        suggestions.truncate(max);
+        suggestions
+    }
+    /// At most `max` suggestions that `keep` accepts, best first. Rejected
+    /// candidates don't count towards `max`, so good ones further down the
+    /// list aren't cut off by bad ones above them.
+    fn suggest_filtered(&self, word: &str, max: usize, keep: &dyn Fn(&str) -> bool) -> Vec<String> {
+        let mut suggestions = self.suggest(word);
+        suggestions.retain(|s| keep(s));
+        suggestions.truncate(max);
+        suggestions
+    }
 }
 
 /// Failure to load a Hunspell dictionary from its `.aff`/`.dic` file pair.
@@ -39,7 +72,7 @@ enum WordCase {
 #[derive(Debug)]
 pub struct HunspellDictionary {
     dictionary: spellbook::Dictionary,
-    suggestion_cache: Arc<RwLock<LruCache<String, Vec<String>>>>,
+    suggestion_cache: Arc<RwLock<LruCache<SuggestionKey, Vec<String>>>>,
     check_cache: Arc<RwLock<LruCache<String, bool>>>,
 }
 
@@ -69,6 +102,50 @@ impl HunspellDictionary {
             ))),
         })
     }
+    /// Up to `max` suggestions that `keep` accepts, cased like `word`.
+    /// Edits of the word are cheap to try; the ngram search scans the whole
+    /// word list, and for long mangled words takes most of the time, so it
+    /// only runs when too few edits are kept, and never for words longer
+    /// than `MAX_NGRAM_WORD_LEN`.
+    fn search(&self, word: &str, max: usize, keep: &dyn Fn(&str) -> bool) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        self.dictionary
+            .suggester()
+            .with_ngram_suggestions(false)
+            .suggest(word, &mut suggestions);
+        self.match_word_case(word, &mut suggestions);
+        suggestions.retain(|s| keep(s));
+        if suggestions.len() < max && word.chars().count() <= MAX_NGRAM_WORD_LEN {
+            self.dictionary.suggest(word, &mut suggestions);
+            self.match_word_case(word, &mut suggestions);
+            suggestions.retain(|s| keep(s));
+        }
+        suggestions.truncate(max);
+        suggestions
+    }
+
+    /// Give suggestions for an all-caps or all-lowercase word its casing.
+    fn match_word_case(&self, word: &str, suggestions: &mut [String]) {
+        if suggestions.is_empty() {
+            return;
+        }
+        let word_case = self.get_word_case(word);
+        for suggestion in suggestions {
+            match word_case {
+                WordCase::AllCaps => {
+                    suggestion.make_ascii_uppercase();
+                }
+                WordCase::AllLower => {
+                    suggestion.make_ascii_lowercase();
+                }
+                WordCase::TitleCase => {
+                    // Leave it alone if it's a title case
+                }
+                WordCase::Unknown => {}
+            }
+        }
+    }
+
     fn get_word_case(&self, word: &str) -> WordCase {
         if word.chars().all(char::is_uppercase) {
             return WordCase::AllCaps;
@@ -92,7 +169,9 @@ impl Dictionary for HunspellDictionary {
             }
         }
 
-        // If not in cache, perform the check
+        // If not in cache, perform the check. spellbook applies the .aff
+        // compound rules (COMPOUNDFLAG, COMPOUNDRULE, ...), so compounds
+        // like German "Haustür" pass without being in the word list.
         let result = self.dictionary.check(word)
             || self
                 .dictionary
@@ -110,49 +189,39 @@ impl Dictionary for HunspellDictionary {
         result
     }
 
+    fn check_exact_case(&self, word: &str) -> bool {
+        self.dictionary.check(word)
+    }
+
     fn suggest(&self, word: &str) -> Vec<String> {
+        self.suggest_limited(word, MAX_SUGGESTIONS)
+    }
+
+    fn suggest_limited(&self, word: &str, max: usize) -> Vec<String> {
+        let key = (word.to_string(), max);
         {
             let mut cache = self.suggestion_cache.write().unwrap();
-            if let Some(suggestions) = cache.get(word) {
+            if let Some(suggestions) = cache.get(&key) {
                 return suggestions.clone();
             }
         }
 
-        // If not in cache, generate suggestions
-        let mut suggestions = Vec::new();
-        self.dictionary.suggest(word, &mut suggestions);
-        suggestions.truncate(5);
-
-        if !suggestions.is_empty() {
-            let word_case = self.get_word_case(word);
-
-            // Apply case transformations
-            for suggestion in &mut suggestions {
-                match word_case {
-                    WordCase::AllCaps => {
-                        suggestion.make_ascii_uppercase();
-                    }
-                    WordCase::AllLower => {
-                        suggestion.make_ascii_lowercase();
-                    }
-                    WordCase::TitleCase => {
-                        // Leave it alone if it's a title case
-                    }
-                    WordCase::Unknown => {}
-                }
-            }
-        }
+        let suggestions = self.search(word, max, &|_| true);
 
         // Cache the result if non-empty
         if !suggestions.is_empty() {
             self.suggestion_cache
                 .write()
                 .unwrap()
-                .put(word.to_string(), suggestions.clone());
+                .put(key, suggestions.clone());
         }
 
         suggestions
     }
+
+    fn suggest_filtered(&self, word: &str, max: usize, keep: &dyn Fn(&str) -> bool) -> Vec<String> {
+        self.search(word, max, keep)
+    }
 }
 
 #[derive(Debug)]
@@ -164,6 +233,11 @@ impl Dictionary for TextDictionary {
     fn check(&self, word: &str) -> bool {
         self.words.contains(&UniCase::new(word.to_string()))
     }
+    fn check_exact_case(&self, word: &str) -> bool {
+        self.words
+            .get(&UniCase::new(word.to_string()))
+            .is_some_and(|entry| entry.as_str() == word)
+    }
     fn suggest(&self, _word: &str) -> Vec<String> {
         vec![]
     }
@@ -178,6 +252,15 @@ impl TextDictionary {
             .collect();
         Self { words }
     }
+
+    /// Build a dictionary from words supplied directly, e.g. by an app
+    /// embedding Codebook or by a test that needs a fixed word set.
+    pub fn from_words(words: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            words: words.into_iter().map(UniCase::new).collect(),
+        }
+    }
+
     pub fn new_from_path(path: &PathBuf) -> Self {
         let word_list = std::fs::read_to_string(path)
             .unwrap_or_else(|_| panic!("Failed to read dictionary file: {}", path.display()));
@@ -190,6 +273,42 @@ impl TextDictionary {
     }
 }
 
+/// Word frequencies from a `word<TAB>count` list, used to rank suggestions
+/// rather than to check words. Blank lines, `#` comments and lines without
+/// a numeric count are skipped.
+pub struct FrequencyDictionary {
+    counts: HashMap<UniCase<String>, u64>,
+}
+
+impl FrequencyDictionary {
+    pub fn new(frequency_list: &str) -> Self {
+        let counts = frequency_list
+            .lines()
+            .filter(|s| !s.is_empty() && !s.starts_with('#'))
+            .filter_map(|line| {
+                let (word, count) = line.split_once('\t')?;
+                let count = count.trim().parse().ok()?;
+                Some((UniCase::new(word.trim().to_string()), count))
+            })
+            .collect();
+        Self { counts }
+    }
+
+    /// How often `word` occurs; 0 if it isn't listed.
+    pub fn count(&self, word: &str) -> u64 {
+        self.counts
+            .get(&UniCase::new(word.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Sort `words` most frequent first. Unlisted words keep their order,
+    /// after the listed ones.
+    pub fn rank(&self, words: &mut [String]) {
+        words.sort_by_key(|word| std::cmp::Reverse(self.count(word)));
+    }
+}
+
 #[cfg(test)]
 mod dictionary_tests {
     use super::*;
@@ -215,6 +334,24 @@ mod dictionary_tests {
         assert!(suggestions.contains(&"alice".to_string()));
     }
 
+    #[test]
+    fn test_hunspell_compounds() {
+        // German-style compounding: COMPOUNDFLAG, COMPOUNDBEGIN/COMPOUNDEND
+        // and ONLYINCOMPOUND from the .aff file are honored.
+        let dict =
+            HunspellDictionary::new("./tests/de_compound.aff", "./tests/de_compound.dic").unwrap();
+        assert!(dict.check("haustür"));
+        assert!(dict.check("Haustür"));
+        assert!(dict.check("Haustürschlüssel"));
+        assert!(dict.check("Arbeitszimmer"));
+        // Parts marked ONLYINCOMPOUND aren't words on their own
+        assert!(!dict.check("zimmer"));
+        // Order matters for begin/end parts
+        assert!(!dict.check("Zimmerarbeits"));
+        // Words without a compound flag don't combine
+        assert!(!dict.check("Hundhaus"));
+    }
+
     #[test]
     fn test_text_dictionary_unicode_ignore_case() {
         let dict = TextDictionary::new("Апгрейдить\nИИ\n");
@@ -252,4 +389,21 @@ mod dictionary_tests {
         assert!(dict.check("ΣΊΓΜΑ"));
         assert!(dict.check("σίγμα"));
     }
+
+    #[test]
+    fn test_frequency_dictionary_rank() {
+        let freq = FrequencyDictionary::new(
+            "# word\tcount\nthe\t500\nthen\t90\nThey\t120\nbad line\nodd\tmany\n",
+        );
+        assert_eq!(freq.count("THE"), 500);
+        assert_eq!(freq.count("they"), 120);
+        assert_eq!(freq.count("odd"), 0);
+
+        let mut words: Vec<String> = ["thew", "then", "thee", "the", "they"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        freq.rank(&mut words);
+        assert_eq!(words, vec!["the", "they", "then", "thew", "thee"]);
+    }
 }
@@ -1,16 +1,19 @@
 use std::{
-    collections::HashMap,
-    path::PathBuf,
-    sync::{Arc, RwLock},
-    time::{Duration, Instant},
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant, SystemTime},
 };
 
 use super::{
-    dictionary::{self, TextDictionary},
-    repo::{DictionaryRepo, HunspellRepo, TextRepo, get_repo},
+    dictionary::{self, FrequencyDictionary, TextDictionary},
+    repo::{DictionaryRepo, HunspellRepo, TextRepo, available_dictionaries, get_repo},
     transliteration::TransliteratingDictionary,
 };
-use codebook_downloader::{Downloader, PermanentHttpError};
+use codebook_config::CustomDictionary;
+#[cfg(test)]
+use codebook_downloader::HttpTransport;
+use codebook_downloader::{Downloader, OfflineError, PermanentHttpError};
 use dictionary::{Dictionary, HunspellDictionary};
 use log::{debug, error, warn};
 
@@ -23,16 +26,49 @@ const TRANSIENT_FAILURE_COOLDOWN: Duration = Duration::from_secs(5 * 60);
 
 /// A dictionary load failure. Permanent failures (the server definitively
 /// answered 4xx) are not retried for the life of the process; transient ones
-/// retry after a cooldown.
+/// retry after a cooldown. Offline misses aren't failures of the dictionary
+/// and are tried again on the next load, which may be online.
 struct LoadError {
     permanent: bool,
+    offline: bool,
+    message: String,
+}
+
+/// Modification time and size of each file a dictionary was loaded from,
+/// `None` for files that couldn't be read, to notice edits on disk.
+type FileStamp = Vec<Option<(Option<SystemTime>, u64)>>;
+
+pub(crate) fn file_stamp(paths: &[&Path]) -> FileStamp {
+    paths
+        .iter()
+        .map(|path| {
+            let meta = std::fs::metadata(path).ok()?;
+            Some((meta.modified().ok(), meta.len()))
+        })
+        .collect()
 }
 
 pub struct DictionaryManager {
     dictionary_cache: RwLock<HashMap<String, Arc<dyn Dictionary>>>,
+    /// Loaded frequency lists by path, with the stamp of the file as loaded
+    frequency_cache: RwLock<HashMap<PathBuf, (FileStamp, Arc<FrequencyDictionary>)>>,
     failed_loads: RwLock<HashMap<String, (Instant, Duration)>>,
+    /// The files behind each custom dictionary as of its last load attempt,
+    /// and behind each dictionary loaded from the local directory, by cache
+    /// key, so edits are picked up on the next check.
+    custom_stamps: RwLock<HashMap<String, FileStamp>>,
+    /// Why each dictionary that last failed to load did so, by ID, until it
+    /// loads successfully.
+    load_errors: RwLock<HashMap<String, String>>,
+    /// Unknown dictionary IDs already warned about, so a typo in the config
+    /// is reported once rather than on every check.
+    warned_unknown: RwLock<HashSet<String>>,
     downloader: Downloader,
     local_dir: Option<PathBuf>,
+    /// One lock per dictionary ID, held while that dictionary loads, so a
+    /// background preload and a spell check that both miss the cache parse
+    /// it once without blocking loads of other dictionaries.
+    load_locks: Mutex<HashMap<String, Arc<Mutex<()>>>>,
 }
 
 impl DictionaryManager {
@@ -43,30 +79,65 @@ impl DictionaryManager {
     /// Create a manager that resolves dictionaries from a local directory of
     /// `{id}.txt` word lists or `{id}.aff` + `{id}.dic` Hunspell pairs before
     /// falling back to the download repo. Tests use this with checked-in
-    /// fixtures so `cargo test` never touches the network.
+    /// fixtures so `cargo test` never touches the network. Local files are
+    /// re-read when their modification time or size changes.
     pub fn with_local_dir(cache_dir: &PathBuf, local_dir: Option<PathBuf>) -> Self {
         Self {
             dictionary_cache: RwLock::new(HashMap::new()),
+            frequency_cache: RwLock::new(HashMap::new()),
             failed_loads: RwLock::new(HashMap::new()),
+            custom_stamps: RwLock::new(HashMap::new()),
+            load_errors: RwLock::new(HashMap::new()),
+            warned_unknown: RwLock::new(HashSet::new()),
             downloader: Downloader::new(cache_dir),
             local_dir,
+            load_locks: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn get_dictionary(&self, id: &str) -> Option<Arc<dyn Dictionary>> {
-        {
-            let cache = self.dictionary_cache.read().unwrap();
-            if let Some(dictionary) = cache.get(id) {
-                return Some(dictionary.clone());
-            }
+    /// The dictionary `id`, loading it on first use. With `offline`, files
+    /// not already downloaded aren't fetched, and the dictionary doesn't
+    /// load.
+    pub fn get_dictionary(&self, id: &str, offline: bool) -> Option<Arc<dyn Dictionary>> {
+        let local_changed = self
+            .custom_stamps
+            .read()
+            .unwrap()
+            .get(id)
+            .is_some_and(|loaded| self.local_stamp(id).as_ref() != Some(loaded));
+        if local_changed {
+            debug!("Local dictionary '{id}' changed on disk, reloading");
+            self.invalidate_cache_entry(id);
+        }
+        if let Some(dictionary) = self.dictionary_cache.read().unwrap().get(id) {
+            return Some(dictionary.clone());
+        }
+        let load_lock = self
+            .load_locks
+            .lock()
+            .unwrap()
+            .entry(id.to_string())
+            .or_default()
+            .clone();
+        let _loading = load_lock.lock().unwrap();
+        // Another thread may have loaded it while we waited
+        if let Some(dictionary) = self.dictionary_cache.read().unwrap().get(id) {
+            return Some(dictionary.clone());
         }
 
-        if let Some(d) = self.get_local_dictionary(id) {
-            self.dictionary_cache
-                .write()
-                .unwrap()
-                .insert(id.to_string(), d.clone());
-            return Some(d);
+        if let Some(files) = self.local_files(id) {
+            let stamp = file_stamp(&files.iter().map(PathBuf::as_path).collect::<Vec<_>>());
+            if let Some(d) = load_local(id, &files) {
+                self.dictionary_cache
+                    .write()
+                    .unwrap()
+                    .insert(id.to_string(), d.clone());
+                self.custom_stamps
+                    .write()
+                    .unwrap()
+                    .insert(id.to_string(), stamp);
+                return Some(d);
+            }
         }
 
         let repo = match get_repo(id) {
@@ -88,8 +159,8 @@ impl DictionaryManager {
         }
 
         let dictionary: Result<Arc<dyn Dictionary>, LoadError> = match repo {
-            DictionaryRepo::Hunspell(r) => self.get_hunspell_dictionary(r),
-            DictionaryRepo::Text(r) => self.get_text_dictionary(r),
+            DictionaryRepo::Hunspell(r) => self.get_hunspell_dictionary(r, offline),
+            DictionaryRepo::Text(r) => self.get_text_dictionary(r, offline),
         };
 
         match dictionary {
@@ -97,8 +168,16 @@ impl DictionaryManager {
                 let mut cache = self.dictionary_cache.write().unwrap();
                 cache.insert(id.to_string(), d.clone());
                 self.failed_loads.write().unwrap().remove(id);
+                self.load_errors.write().unwrap().remove(id);
                 Some(d)
             }
+            Err(e) if e.offline => {
+                self.load_errors
+                    .write()
+                    .unwrap()
+                    .insert(id.to_string(), e.message);
+                None
+            }
             Err(e) => {
                 let cooldown = if e.permanent {
                     warn!("Dictionary '{id}' does not exist upstream, not retrying");
@@ -114,34 +193,276 @@ impl DictionaryManager {
                     .write()
                     .unwrap()
                     .insert(id.to_string(), (Instant::now(), cooldown));
+                self.load_errors
+                    .write()
+                    .unwrap()
+                    .insert(id.to_string(), e.message);
+                None
+            }
+        }
+    }
+
+    /// Load a Hunspell dictionary from the local `.aff`/`.dic` pair named in
+    /// the config. Failures are recorded under the entry's name and retried
+    /// after the transient cooldown. The files are re-read when their
+    /// modification time or size changes, so a failed load is also retried
+    /// as soon as the files are fixed.
+    pub fn get_custom_dictionary(&self, entry: &CustomDictionary) -> Option<Arc<dyn Dictionary>> {
+        let key = format!(
+            "custom:{}:{}:{}",
+            entry.name,
+            entry.aff_path.display(),
+            entry.dic_path.display()
+        );
+        let stamp = file_stamp(&[&entry.aff_path, &entry.dic_path]);
+        let changed = self
+            .custom_stamps
+            .read()
+            .unwrap()
+            .get(&key)
+            .is_some_and(|loaded| *loaded != stamp);
+        if changed {
+            debug!(
+                "Custom dictionary '{}' changed on disk, reloading",
+                entry.name
+            );
+            self.invalidate_cache_entry(&key);
+        }
+        if let Some(dictionary) = self.dictionary_cache.read().unwrap().get(&key) {
+            return Some(dictionary.clone());
+        }
+        {
+            let failed = self.failed_loads.read().unwrap();
+            if let Some((failed_at, cooldown)) = failed.get(&key)
+                && failed_at.elapsed() < *cooldown
+            {
+                return None;
+            }
+        }
+
+        let result = [&entry.aff_path, &entry.dic_path]
+            .into_iter()
+            .find(|path| !path.is_file())
+            .map_or_else(
+                || load_hunspell(&entry.aff_path, &entry.dic_path),
+                |missing| {
+                    Err(LoadError {
+                        permanent: false,
+                        offline: false,
+                        message: format!("file not found: {}", missing.display()),
+                    })
+                },
+            );
+        self.custom_stamps
+            .write()
+            .unwrap()
+            .insert(key.clone(), stamp);
+        match result {
+            Ok(d) => {
+                self.dictionary_cache
+                    .write()
+                    .unwrap()
+                    .insert(key.clone(), d.clone());
+                self.failed_loads.write().unwrap().remove(&key);
+                self.load_errors.write().unwrap().remove(&entry.name);
+                Some(d)
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to load custom dictionary '{}': {}",
+                    entry.name, e.message
+                );
+                self.failed_loads
+                    .write()
+                    .unwrap()
+                    .insert(key, (Instant::now(), TRANSIENT_FAILURE_COOLDOWN));
+                self.load_errors
+                    .write()
+                    .unwrap()
+                    .insert(entry.name.clone(), e.message);
+                None
+            }
+        }
+    }
+
+    /// Forget the dictionary cached under `key`, and any recent failure to
+    /// load it, so the next lookup loads it afresh.
+    fn invalidate_cache_entry(&self, key: &str) {
+        self.dictionary_cache.write().unwrap().remove(key);
+        self.failed_loads.write().unwrap().remove(key);
+        self.custom_stamps.write().unwrap().remove(key);
+    }
+
+    /// Load the `word<TAB>count` list at `path` used to rank suggestions.
+    /// It's re-read when the file's modification time or size changes.
+    /// Failures are recorded as a `frequency_list` load error and retried
+    /// after the transient cooldown.
+    pub fn get_frequency_dictionary(&self, path: &Path) -> Option<Arc<FrequencyDictionary>> {
+        let stamp = file_stamp(&[path]);
+        if let Some((loaded, frequencies)) = self.frequency_cache.read().unwrap().get(path)
+            && *loaded == stamp
+        {
+            return Some(frequencies.clone());
+        }
+        let key = format!("frequency:{}", path.display());
+        {
+            let failed = self.failed_loads.read().unwrap();
+            if let Some((failed_at, cooldown)) = failed.get(&key)
+                && failed_at.elapsed() < *cooldown
+            {
+                return None;
+            }
+        }
+
+        match std::fs::read_to_string(path) {
+            Ok(list) => {
+                let frequencies = Arc::new(FrequencyDictionary::new(&list));
+                self.frequency_cache
+                    .write()
+                    .unwrap()
+                    .insert(path.to_path_buf(), (stamp, frequencies.clone()));
+                self.failed_loads.write().unwrap().remove(&key);
+                self.load_errors.write().unwrap().remove("frequency_list");
+                Some(frequencies)
+            }
+            Err(e) => {
+                let message = format!("failed to read {}: {e}", path.display());
+                warn!("Failed to load frequency list: {message}");
+                self.failed_loads
+                    .write()
+                    .unwrap()
+                    .insert(key, (Instant::now(), TRANSIENT_FAILURE_COOLDOWN));
+                self.load_errors
+                    .write()
+                    .unwrap()
+                    .insert("frequency_list".to_string(), message);
                 None
             }
         }
     }
 
-    /// Load a dictionary from the local override directory, if configured.
-    fn get_local_dictionary(&self, id: &str) -> Option<Arc<dyn Dictionary>> {
+    /// A manager whose downloads go through `transport`, for tests.
+    #[cfg(test)]
+    fn with_transport(cache_dir: &PathBuf, transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            downloader: Downloader::with_transport(cache_dir, transport),
+            ..Self::new(cache_dir)
+        }
+    }
+
+    /// Describe every dictionary that failed to load and hasn't loaded since,
+    /// sorted by ID.
+    pub fn load_errors(&self) -> Vec<String> {
+        let errors = self.load_errors.read().unwrap();
+        let mut messages: Vec<String> = errors
+            .iter()
+            .map(|(id, message)| format!("Failed to load dictionary '{id}': {message}"))
+            .collect();
+        messages.sort();
+        messages
+    }
+
+    /// Warn, once per ID, when a dictionary ID from the config matches no
+    /// built-in or local dictionary. Returns true if the ID is unknown.
+    pub fn warn_if_unknown(&self, id: &str) -> bool {
+        if self.is_known(id) {
+            return false;
+        }
+        if self.warned_unknown.write().unwrap().insert(id.to_string()) {
+            let valid: Vec<String> = available_dictionaries().into_iter().map(|d| d.id).collect();
+            warn!(
+                "Unknown dictionary '{id}', skipping. Valid dictionaries: {}",
+                valid.join(", ")
+            );
+        }
+        true
+    }
+
+    /// Whether `id` names a built-in, local or registered dictionary.
+    pub fn is_known(&self, id: &str) -> bool {
+        get_repo(id).is_some()
+            || self.has_local_dictionary(id)
+            || self.dictionary_cache.read().unwrap().contains_key(id)
+    }
+
+    /// Whether `id` can be loaded without downloading anything.
+    pub fn is_cached(&self, id: &str) -> bool {
+        if self.dictionary_cache.read().unwrap().contains_key(id) || self.has_local_dictionary(id) {
+            return true;
+        }
+        let cached = |url: &str| self.downloader.cached_path(url).is_some();
+        match get_repo(id) {
+            Some(DictionaryRepo::Hunspell(r)) => cached(&r.aff_url) && cached(&r.dict_url),
+            Some(DictionaryRepo::Text(r)) => {
+                r.text.is_some() || r.url.as_deref().is_some_and(cached)
+            }
+            None => false,
+        }
+    }
+
+    /// Why `id` last failed to load, if it hasn't loaded since.
+    pub fn load_error(&self, id: &str) -> Option<String> {
+        self.load_errors.read().unwrap().get(id).cloned()
+    }
+
+    fn has_local_dictionary(&self, id: &str) -> bool {
+        self.local_files(id).is_some()
+    }
+
+    /// The files `id` loads from in the local override directory: `{id}.txt`,
+    /// or else the `{id}.aff` + `{id}.dic` pair.
+    fn local_files(&self, id: &str) -> Option<Vec<PathBuf>> {
         let dir = self.local_dir.as_ref()?;
         let txt = dir.join(format!("{id}.txt"));
         if txt.is_file() {
-            return Some(Arc::new(TextDictionary::new_from_path(&txt)));
+            return Some(vec![txt]);
         }
         let aff = dir.join(format!("{id}.aff"));
         let dic = dir.join(format!("{id}.dic"));
-        if aff.is_file() && dic.is_file() {
-            match HunspellDictionary::new(aff.to_str()?, dic.to_str()?) {
-                Ok(dict) => return Some(Arc::new(dict)),
-                Err(e) => error!("Failed to load local dictionary '{id}': {e}"),
-            }
-        }
-        None
+        (aff.is_file() && dic.is_file()).then(|| vec![aff, dic])
+    }
+
+    /// The current stamp of the local files behind `id`, if it has any.
+    pub(crate) fn local_stamp(&self, id: &str) -> Option<FileStamp> {
+        let files = self.local_files(id)?;
+        Some(file_stamp(
+            &files.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Use a ready-made dictionary for `id`, bypassing loading. It replaces
+    /// any dictionary already loaded under that ID, and the ID counts as
+    /// known from then on, so configs can name it like a built-in one.
+    pub fn register_dictionary(&self, id: &str, dictionary: Arc<dyn Dictionary>) {
+        self.custom_stamps.write().unwrap().remove(id);
+        self.dictionary_cache
+            .write()
+            .unwrap()
+            .insert(id.to_string(), dictionary);
     }
 
-    fn download(&self, url: &str) -> Result<PathBuf, LoadError> {
-        self.downloader.get(url).map_err(|e| {
+    /// The downloaded file at `url`; with `offline`, only an already cached
+    /// one (see `Downloader::get_cached`).
+    fn download(&self, url: &str, offline: bool) -> Result<PathBuf, LoadError> {
+        let result = if offline {
+            self.downloader.get_cached(url)
+        } else {
+            self.downloader.get(url)
+        };
+        result.map_err(|e| {
+            if e.downcast_ref::<OfflineError>().is_some() {
+                warn!("{e}");
+                return LoadError {
+                    permanent: false,
+                    offline: true,
+                    message: e.to_string(),
+                };
+            }
             error!("Failed to download dictionary file {url}: {e:?}");
             LoadError {
                 permanent: e.downcast_ref::<PermanentHttpError>().is_some(),
+                offline: false,
+                message: format!("download of {url} failed: {e}"),
             }
         })
     }
@@ -149,28 +470,22 @@ impl DictionaryManager {
     fn get_hunspell_dictionary(
         &self,
         repo: HunspellRepo,
+        offline: bool,
     ) -> Result<Arc<dyn Dictionary>, LoadError> {
-        let aff_path = self.download(&repo.aff_url)?;
-        let dic_path = self.download(&repo.dict_url)?;
-        let (Some(aff), Some(dic)) = (aff_path.to_str(), dic_path.to_str()) else {
-            error!("Dictionary cache path is not valid UTF-8: {aff_path:?}");
-            return Err(LoadError { permanent: true });
-        };
-        let dict = match HunspellDictionary::new(aff, dic) {
-            Ok(dict) => dict,
-            Err(e) => {
-                error!("Failed to load Hunspell dictionary: {e}");
-                return Err(LoadError { permanent: false });
-            }
-        };
-        let base: Arc<dyn Dictionary> = Arc::new(dict);
+        let aff_path = self.download(&repo.aff_url, offline)?;
+        let dic_path = self.download(&repo.dict_url, offline)?;
+        let base = load_hunspell(&aff_path, &dic_path)?;
         Ok(match repo.transliteration {
             Some(t) => Arc::new(TransliteratingDictionary::new(base, t.variants_fn())),
             None => base,
         })
     }
 
-    fn get_text_dictionary(&self, repo: TextRepo) -> Result<Arc<dyn Dictionary>, LoadError> {
+    fn get_text_dictionary(
+        &self,
+        repo: TextRepo,
+        offline: bool,
+    ) -> Result<Arc<dyn Dictionary>, LoadError> {
         if let Some(text) = repo.text {
             return Ok(Arc::new(TextDictionary::new(text)));
         }
@@ -179,10 +494,267 @@ impl DictionaryManager {
                 "Text dictionary repo '{}' has neither embedded text nor a URL",
                 repo.name
             );
-            return Err(LoadError { permanent: true });
+            return Err(LoadError {
+                permanent: true,
+                offline: false,
+                message: "no embedded text or URL".to_string(),
+            });
         };
-        let text_path = self.download(&url)?;
+        let text_path = self.download(&url, offline)?;
         let dict = TextDictionary::new_from_path(&text_path);
         Ok(Arc::new(dict))
     }
 }
+
+/// Load `id` from its local override `files` (see `local_files`).
+fn load_local(id: &str, files: &[PathBuf]) -> Option<Arc<dyn Dictionary>> {
+    match files {
+        [txt] => Some(Arc::new(TextDictionary::new_from_path(txt))),
+        [aff, dic] => match HunspellDictionary::new(aff.to_str()?, dic.to_str()?) {
+            Ok(dict) => Some(Arc::new(dict)),
+            Err(e) => {
+                error!("Failed to load local dictionary '{id}': {e}");
+                None
+            }
+        },
+        _ => None,
+    }
+}
+
+fn load_hunspell(aff_path: &Path, dic_path: &Path) -> Result<Arc<dyn Dictionary>, LoadError> {
+    let (Some(aff), Some(dic)) = (aff_path.to_str(), dic_path.to_str()) else {
+        error!("Dictionary path is not valid UTF-8: {aff_path:?}");
+        return Err(LoadError {
+            permanent: true,
+            offline: false,
+            message: format!("path is not valid UTF-8: {aff_path:?}"),
+        });
+    };
+    match HunspellDictionary::new(aff, dic) {
+        Ok(dict) => Ok(Arc::new(dict)),
+        Err(e) => {
+            error!("Failed to load Hunspell dictionary: {e}");
+            Err(LoadError {
+                permanent: false,
+                offline: false,
+                message: e.to_string(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codebook_config::{CodebookConfig, CodebookConfigMemory, ConfigSettings};
+    use codebook_downloader::TransportResponse;
+
+    /// Answers every request with 404 Not Found.
+    struct NotFoundTransport;
+
+    impl HttpTransport for NotFoundTransport {
+        fn get(&self, _url: &str, _since: Option<&str>) -> anyhow::Result<TransportResponse> {
+            Ok(TransportResponse {
+                status: 404,
+                last_modified: None,
+                content_length: None,
+                body: Box::new(std::io::empty()),
+            })
+        }
+    }
+
+    /// Answers every request with a one-word text dictionary.
+    struct WordListTransport;
+
+    impl HttpTransport for WordListTransport {
+        fn get(&self, _url: &str, _since: Option<&str>) -> anyhow::Result<TransportResponse> {
+            Ok(TransportResponse {
+                status: 200,
+                last_modified: None,
+                content_length: None,
+                body: Box::new(std::io::Cursor::new(b"word\n".to_vec())),
+            })
+        }
+    }
+
+    /// Answers like [`WordListTransport`], but only once released, after
+    /// announcing each request.
+    struct GatedTransport {
+        started: std::sync::mpsc::SyncSender<()>,
+        release: Mutex<std::sync::mpsc::Receiver<()>>,
+    }
+
+    impl HttpTransport for GatedTransport {
+        fn get(&self, url: &str, since: Option<&str>) -> anyhow::Result<TransportResponse> {
+            self.started.send(()).unwrap();
+            self.release.lock().unwrap().recv().unwrap();
+            WordListTransport.get(url, since)
+        }
+    }
+
+    #[test]
+    fn test_slow_load_doesnt_block_other_dictionaries() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let (started, on_start) = std::sync::mpsc::sync_channel(1);
+        let (release, on_release) = std::sync::mpsc::channel();
+        let manager = Arc::new(DictionaryManager::with_transport(
+            &cache_dir.path().to_path_buf(),
+            Arc::new(GatedTransport {
+                started,
+                release: Mutex::new(on_release),
+            }),
+        ));
+
+        let downloading = {
+            let manager = manager.clone();
+            std::thread::spawn(move || manager.get_dictionary("rust", false).is_some())
+        };
+        on_start.recv().unwrap();
+
+        // The embedded dictionary must load while "rust" is mid-download
+        let (done, on_done) = std::sync::mpsc::channel();
+        {
+            let manager = manager.clone();
+            std::thread::spawn(move || {
+                done.send(manager.get_dictionary("codebook", false).is_some())
+            });
+        }
+        let loaded = on_done.recv_timeout(Duration::from_secs(10));
+        release.send(()).unwrap();
+        assert_eq!(loaded, Ok(true));
+        assert!(downloading.join().unwrap());
+    }
+
+    #[test]
+    fn test_is_cached() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let manager = DictionaryManager::with_transport(
+            &cache_dir.path().to_path_buf(),
+            Arc::new(WordListTransport),
+        );
+        assert!(manager.is_cached("codebook"));
+        assert!(!manager.is_cached("rust"));
+        assert!(manager.get_dictionary("rust", false).is_some());
+        assert!(manager.is_cached("rust"));
+        // A fresh manager sees the files the first one downloaded
+        let manager = DictionaryManager::with_transport(
+            &cache_dir.path().to_path_buf(),
+            Arc::new(NotFoundTransport),
+        );
+        assert!(manager.is_cached("rust"));
+        assert!(!manager.is_cached("en_us"));
+        assert!(!manager.is_cached("not_a_dictionary"));
+    }
+
+    #[test]
+    fn test_downloads_go_to_configured_cache_dir() {
+        // CODEBOOK_CACHE_DIR, when set, wins over the setting
+        if std::env::var_os("CODEBOOK_CACHE_DIR").is_some() {
+            return;
+        }
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = CodebookConfigMemory::new(ConfigSettings {
+            cache_dir: Some(temp_dir.path().join("ci-cache")),
+            ..Default::default()
+        });
+        let cache_dir = config.cache_dir().to_path_buf();
+        assert_eq!(cache_dir, temp_dir.path().join("ci-cache"));
+        let manager = DictionaryManager::with_transport(&cache_dir, Arc::new(WordListTransport));
+        assert!(manager.get_dictionary("rust", false).is_some());
+        let downloaded = std::fs::read_dir(&cache_dir).unwrap().count();
+        assert!(downloaded > 0, "nothing downloaded into {cache_dir:?}");
+    }
+
+    #[test]
+    fn test_load_errors_recorded() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let manager = DictionaryManager::with_transport(
+            &cache_dir.path().to_path_buf(),
+            Arc::new(NotFoundTransport),
+        );
+        assert!(manager.load_errors().is_empty());
+        assert!(manager.get_dictionary("en_gb", false).is_none());
+        let errors = manager.load_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("'en_gb'"), "{errors:?}");
+        // Embedded dictionaries never fail
+        assert!(manager.get_dictionary("codebook", false).is_some());
+        assert_eq!(manager.load_errors().len(), 1);
+    }
+
+    #[test]
+    fn test_offline_fails_fast_for_uncached() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        // NotFoundTransport would answer, but offline must not ask it
+        let manager = DictionaryManager::with_transport(
+            &cache_dir.path().to_path_buf(),
+            Arc::new(NotFoundTransport),
+        );
+        assert!(manager.get_dictionary("en_gb", true).is_none());
+        let errors = manager.load_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("offline mode"), "{errors:?}");
+    }
+
+    #[test]
+    fn test_offline_miss_doesnt_delay_online_load() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let manager = DictionaryManager::with_transport(
+            &cache_dir.path().to_path_buf(),
+            Arc::new(WordListTransport),
+        );
+        assert!(manager.get_dictionary("rust", true).is_none());
+        // No cooldown: the next online load downloads it
+        assert!(manager.get_dictionary("rust", false).is_some());
+        assert!(manager.load_errors().is_empty());
+    }
+
+    #[test]
+    fn test_warn_if_unknown() {
+        let local_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(local_dir.path().join("team_words.txt"), "word\n").unwrap();
+        let manager = DictionaryManager::with_local_dir(
+            &local_dir.path().join("cache"),
+            Some(local_dir.path().to_path_buf()),
+        );
+        assert!(!manager.warn_if_unknown("en_us"));
+        assert!(!manager.warn_if_unknown("team_words"));
+        assert!(manager.warn_if_unknown("en_usa"));
+        // Still unknown on repeat calls, though only warned about once
+        assert!(manager.warn_if_unknown("en_usa"));
+    }
+
+    #[test]
+    fn test_local_dictionary_reloaded_when_edited() {
+        let local_dir = tempfile::TempDir::new().unwrap();
+        let words = local_dir.path().join("team_words.txt");
+        std::fs::write(&words, "word\n").unwrap();
+        let manager = DictionaryManager::with_local_dir(
+            &local_dir.path().join("cache"),
+            Some(local_dir.path().to_path_buf()),
+        );
+        let dictionary = manager.get_dictionary("team_words", true).unwrap();
+        assert!(dictionary.check("word"));
+        assert!(!dictionary.check("codebook"));
+
+        std::fs::write(&words, "word\ncodebook\n").unwrap();
+        let dictionary = manager.get_dictionary("team_words", true).unwrap();
+        assert!(dictionary.check("codebook"));
+    }
+
+    #[test]
+    fn test_register_dictionary() {
+        let cache_dir = tempfile::TempDir::new().unwrap();
+        let manager = DictionaryManager::new(&cache_dir.path().to_path_buf());
+        assert!(!manager.is_known("embedded"));
+        manager.register_dictionary(
+            "embedded",
+            Arc::new(TextDictionary::from_words(["Codebook".to_string()])),
+        );
+        assert!(manager.is_known("embedded"));
+        assert!(manager.is_cached("embedded"));
+        let dictionary = manager.get_dictionary("embedded", false).unwrap();
+        assert!(dictionary.check("codebook"));
+        assert!(!dictionary.check("codex"));
+    }
+}
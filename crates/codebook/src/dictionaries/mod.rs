@@ -2,3 +2,5 @@ pub mod dictionary;
 pub mod manager;
 pub mod repo;
 pub mod transliteration;
+
+pub use repo::{DictionaryInfo, DictionaryKind, available_dictionaries};
@@ -233,6 +233,90 @@ static TEXT_DICTIONARIES: LazyLock<Vec<TextRepo>> = LazyLock::new(|| {
     ]
 });
 
+/// The format a built-in dictionary is distributed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DictionaryKind {
+    Hunspell,
+    Text,
+}
+
+impl std::fmt::Display for DictionaryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DictionaryKind::Hunspell => write!(f, "hunspell"),
+            DictionaryKind::Text => write!(f, "text"),
+        }
+    }
+}
+
+/// A built-in dictionary that can be listed in `dictionaries = [...]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DictionaryInfo {
+    pub id: String,
+    pub name: &'static str,
+    pub kind: DictionaryKind,
+}
+
+/// Every built-in dictionary ID, Hunspell languages first.
+pub fn available_dictionaries() -> Vec<DictionaryInfo> {
+    let hunspell = HUNSPELL_DICTIONARIES
+        .iter()
+        .map(|r| (&r.name, DictionaryKind::Hunspell));
+    let text = TEXT_DICTIONARIES
+        .iter()
+        .map(|r| (&r.name, DictionaryKind::Text));
+    hunspell
+        .chain(text)
+        .map(|(id, kind)| DictionaryInfo {
+            id: id.clone(),
+            name: display_name(id),
+            kind,
+        })
+        .collect()
+}
+
+/// Human-readable name for a built-in dictionary ID.
+pub(crate) fn display_name(id: &str) -> &'static str {
+    match id {
+        "en_us" => "English (US)",
+        "en" => "English",
+        "en_gb" => "English (UK)",
+        "cs" => "Czech",
+        "es" => "Spanish",
+        "de" => "German",
+        "de_at" => "German (Austria)",
+        "de_ch" => "German (Switzerland)",
+        "fr" => "French",
+        "ru" => "Russian",
+        "it" => "Italian",
+        "sv" => "Swedish",
+        "pt_br" => "Portuguese (Brazil)",
+        "nl_nl" => "Dutch",
+        "da" => "Danish",
+        "lv" => "Latvian",
+        "la" => "Latin",
+        "sa" => "Sanskrit",
+        "grc" => "Ancient Greek",
+        "vi_vn" => "Vietnamese",
+        "pl" => "Polish",
+        "uk" => "Ukrainian",
+        "nb_no" => "Norwegian Bokmål",
+        "nn_no" => "Norwegian Nynorsk",
+        "pt_pt" => "Portuguese (Portugal)",
+        "pt" => "Portuguese",
+        "fa_ir" => "Persian",
+        "sl" => "Slovenian",
+        "dart" => "Dart",
+        "go" => "Go",
+        "rust" => "Rust",
+        "software_terms" => "Software terms",
+        "computing_acronyms" => "Computing acronyms",
+        "codebook" => "Codebook built-in words",
+        "csharp" => "C#",
+        _ => "",
+    }
+}
+
 pub fn get_repo(name: &str) -> Option<DictionaryRepo> {
     let res = HUNSPELL_DICTIONARIES.iter().find(|d| d.name == name);
     if let Some(res1) = res {
@@ -308,6 +392,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_available_dictionaries() {
+        let dictionaries = available_dictionaries();
+        assert_eq!(
+            dictionaries.len(),
+            HUNSPELL_DICTIONARIES.len() + TEXT_DICTIONARIES.len()
+        );
+        for info in &dictionaries {
+            assert!(get_repo(&info.id).is_some(), "{} has no repo", info.id);
+            assert!(!info.name.is_empty(), "{} has no display name", info.id);
+        }
+        let en_us = dictionaries.iter().find(|d| d.id == "en_us").unwrap();
+        assert_eq!(en_us.kind, DictionaryKind::Hunspell);
+        let rust = dictionaries.iter().find(|d| d.id == "rust").unwrap();
+        assert_eq!(rust.kind, DictionaryKind::Text);
+    }
+
     #[test]
     fn german_dictionaries_opt_into_german_transliteration() {
         let german_ids = ["de", "de_at", "de_ch"];
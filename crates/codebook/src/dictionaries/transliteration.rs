@@ -126,6 +126,10 @@ impl Dictionary for TransliteratingDictionary {
         (self.variants_fn)(word).iter().any(|v| self.inner.check(v))
     }
 
+    fn check_exact_case(&self, word: &str) -> bool {
+        self.inner.check_exact_case(word)
+    }
+
     fn suggest(&self, word: &str) -> Vec<String> {
         let variants = (self.variants_fn)(word);
         if variants.is_empty() {
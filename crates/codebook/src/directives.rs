@@ -0,0 +1,141 @@
+//! Inline comments that turn spell checking off for parts of a file, such as
+//! `// codebook:ignore` at the end of a line or a `codebook:disable` ...
+//! `codebook:enable` block.
+
+use log::debug;
+use std::ops::Range;
+
+/// Directives that suppress the line after the comment.
+const IGNORE_NEXT_LINE: &[&str] = &["codebook:ignore-next-line", "spellcheck:disable-next-line"];
+
+/// Directives that suppress the line the comment is on.
+const IGNORE_LINE: &[&str] = &["codebook:ignore", "spellcheck:disable-line"];
+
+/// Directives that start and end a suppressed block, both lines included.
+const DISABLE: &[&str] = &["codebook:disable", "spellcheck:disable"];
+const ENABLE: &[&str] = &["codebook:enable", "spellcheck:enable"];
+
+/// Byte ranges of `text` whose words shouldn't be reported, from the
+/// directives found in `comments` (byte ranges of comment nodes).
+///
+/// Blocks don't nest: a `disable` inside a disabled block is ignored, and
+/// the first `enable` ends the block. A block left open runs to the end of
+/// the file.
+pub(crate) fn suppressed_ranges(text: &str, comments: &[(usize, usize)]) -> Vec<Range<usize>> {
+    let mut directives: Vec<(usize, &str)> = comments
+        .iter()
+        .flat_map(|&(start, end)| {
+            find_directives(&text[start..end]).map(move |(offset, d)| (start + offset, d))
+        })
+        .collect();
+    // The same comment can be captured by more than one pattern
+    directives.sort();
+    directives.dedup();
+
+    let mut ranges = Vec::new();
+    let mut disabled_from: Option<usize> = None;
+    for (pos, directive) in directives {
+        let line = line_at(text, pos);
+        if DISABLE.contains(&directive) {
+            disabled_from.get_or_insert(line.start);
+        } else if ENABLE.contains(&directive) {
+            if let Some(start) = disabled_from.take() {
+                ranges.push(start..line.end);
+            }
+        } else if IGNORE_LINE.contains(&directive) {
+            ranges.push(line);
+        } else if IGNORE_NEXT_LINE.contains(&directive) && line.end < text.len() {
+            ranges.push(line_at(text, line.end));
+        }
+    }
+    if let Some(start) = disabled_from {
+        debug!("codebook:disable without a matching codebook:enable, skipping to end of file");
+        ranges.push(start..text.len());
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges.dedup();
+    ranges
+}
+
+/// Every `codebook:…` or `spellcheck:…` token in `comment`, with its offset.
+fn find_directives(comment: &str) -> impl Iterator<Item = (usize, &str)> {
+    ["codebook:", "spellcheck:"]
+        .into_iter()
+        .flat_map(move |prefix| {
+            comment.match_indices(prefix).map(move |(offset, _)| {
+                let rest = &comment[offset..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == ':' || c == '-'))
+                    .unwrap_or(rest.len());
+                (offset, &rest[..len])
+            })
+        })
+}
+
+/// The line containing byte `pos`, including its line terminator.
+fn line_at(text: &str, pos: usize) -> Range<usize> {
+    let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let end = text[pos..].find('\n').map_or(text.len(), |i| pos + i + 1);
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment_ranges(text: &str, marker: &str) -> Vec<(usize, usize)> {
+        text.match_indices(marker)
+            .map(|(start, _)| {
+                let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+                (start, end)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_suppressed_ranges() {
+        let text = "one // codebook:ignore\ntwo\n// codebook:ignore-next-line\nthree\nfour\n";
+        let ranges = suppressed_ranges(text, &comment_ranges(text, "//"));
+        let lines: Vec<&str> = ranges.iter().map(|r| &text[r.clone()]).collect();
+        assert_eq!(lines, vec!["one // codebook:ignore\n", "three\n"]);
+    }
+
+    #[test]
+    fn test_suppressed_ranges_crlf() {
+        let text =
+            "one // codebook:ignore\r\ntwo\r\n// codebook:ignore-next-line\r\nthree\r\nfour\r\n";
+        let ranges = suppressed_ranges(text, &comment_ranges(text, "//"));
+        let lines: Vec<&str> = ranges.iter().map(|r| &text[r.clone()]).collect();
+        assert_eq!(lines, vec!["one // codebook:ignore\r\n", "three\r\n"]);
+    }
+
+    #[test]
+    fn test_directive_must_match_exactly() {
+        let text = "a # codebook:ignored\nb # spellcheck:disable-line\n";
+        let ranges = suppressed_ranges(text, &comment_ranges(text, "#"));
+        let lines: Vec<&str> = ranges.iter().map(|r| &text[r.clone()]).collect();
+        assert_eq!(lines, vec!["b # spellcheck:disable-line\n"]);
+    }
+
+    #[test]
+    fn test_disabled_blocks() {
+        let text = "a\n// codebook:disable\nb\n// codebook:disable\nc\n// codebook:enable\nd\n// codebook:enable\ne\n// spellcheck:disable\nf\n";
+        let ranges = suppressed_ranges(text, &comment_ranges(text, "//"));
+        let blocks: Vec<&str> = ranges.iter().map(|r| &text[r.clone()]).collect();
+        // The nested disable is ignored, the first enable closes the block,
+        // a stray enable does nothing and an open block runs to the end
+        assert_eq!(
+            blocks,
+            vec![
+                "// codebook:disable\nb\n// codebook:disable\nc\n// codebook:enable\n",
+                "// spellcheck:disable\nf\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignore_next_line_at_end_of_file() {
+        let text = "x // codebook:ignore-next-line";
+        assert!(suppressed_ranges(text, &comment_ranges(text, "//")).is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+//! Gettext `.po`/`.pot` catalogs. There's no tree-sitter grammar for them
+//! that works with the tree-sitter version codebook uses, and the format is
+//! line based, so the translatable strings are picked out here instead.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Escapes (`\n`, `\"`) and format placeholders (`%s`, `%(name)s`, `%1$d`,
+/// Qt's `%1`, `{name}`) inside a string, which aren't words.
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\\.|%(?:\([^)]*\))?(?:\d+\$)?[-+#0']*(?:\d+|\*)?(?:\.(?:\d+|\*))?(?:hh|ll|[hlLqjzt])?[diouxXeEfFgGaAcspnm%]|%\d+|\{[^{}\s]*\}",
+    )
+    .expect("Valid placeholder regex")
+});
+
+/// Where the text of a catalog is, as byte ranges.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Catalog {
+    /// Runs of `msgid`/`msgstr` text between placeholders, each with the
+    /// tag it's checked under: `string.msgid` or `string.msgstr`
+    pub(crate) strings: Vec<(usize, usize, &'static str)>,
+    /// `#` comment lines: translator notes, references, flags and obsolete
+    /// entries. Not checked, but directives in them apply.
+    pub(crate) comments: Vec<(usize, usize)>,
+}
+
+/// Find the strings and comments of the catalog `text`. `msgctxt` strings
+/// and the header entry (the `msgstr` of the empty `msgid`) are skipped.
+pub(crate) fn parse(text: &str) -> Catalog {
+    let mut catalog = Catalog::default();
+    // The tag of the keyword the current string belongs to, None to skip it
+    let mut tag = None;
+    // Whether the current entry's msgid has been empty so far
+    let mut header = false;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        let content = line.trim_end_matches(['\r', '\n']);
+        let trimmed = content.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            if !trimmed.is_empty() {
+                catalog
+                    .comments
+                    .push((start + content.len() - trimmed.len(), start + content.len()));
+            }
+            tag = None;
+            continue;
+        }
+        // A line that is only a string continues the previous keyword's
+        let rest = if trimmed.starts_with('"') {
+            trimmed
+        } else {
+            let keyword_len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+            tag = match &trimmed[..keyword_len] {
+                "msgid" => {
+                    header = true;
+                    Some("string.msgid")
+                }
+                "msgid_plural" => Some("string.msgid"),
+                keyword if keyword.starts_with("msgstr") => Some("string.msgstr"),
+                _ => None,
+            };
+            trimmed[keyword_len..].trim_start()
+        };
+        let Some(body) = rest.strip_prefix('"') else {
+            continue;
+        };
+        let body = &body[..closing_quote(body)];
+        let Some(tag) = tag else {
+            continue;
+        };
+        if tag == "string.msgid" && !body.is_empty() {
+            header = false;
+        } else if tag == "string.msgstr" && header {
+            continue;
+        }
+        let body_start = start + content.len() - rest.len() + 1;
+        let mut gap_start = 0;
+        let placeholders = PLACEHOLDER.find_iter(body).map(|m| m.range());
+        for placeholder in placeholders.chain(std::iter::once(body.len()..body.len())) {
+            if placeholder.start > gap_start {
+                catalog
+                    .strings
+                    .push((body_start + gap_start, body_start + placeholder.start, tag));
+            }
+            gap_start = placeholder.end;
+        }
+    }
+    catalog
+}
+
+/// Byte offset of the `"` that ends a string starting right after its
+/// opening quote, or the end of `body` if it isn't closed.
+fn closing_quote(body: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return i,
+            _ => {}
+        }
+    }
+    body.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(text: &str) -> Vec<(&str, &str)> {
+        parse(text)
+            .strings
+            .into_iter()
+            .map(|(start, end, tag)| (&text[start..end], tag))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = r#"# Translator note
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: src/main.c:12
+#, c-format
+msgctxt "menu"
+msgid "Open %s file"
+msgstr "Ouvrir le fichier %s"
+
+msgid "one item"
+msgid_plural "%(count)d items"
+msgstr[0] "un \"objet\""
+msgstr[1] ""
+"{count} objets"
+
+#~ msgid "obsolete"
+"#;
+        assert_eq!(
+            strings(text),
+            vec![
+                ("Open ", "string.msgid"),
+                (" file", "string.msgid"),
+                ("Ouvrir le fichier ", "string.msgstr"),
+                ("one item", "string.msgid"),
+                (" items", "string.msgid"),
+                ("un ", "string.msgstr"),
+                ("objet", "string.msgstr"),
+                (" objets", "string.msgstr"),
+            ]
+        );
+        let comments: Vec<&str> = parse(text)
+            .comments
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect();
+        assert_eq!(
+            comments,
+            vec![
+                "# Translator note",
+                "#: src/main.c:12",
+                "#, c-format",
+                "#~ msgid \"obsolete\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        for (placeholder, text) in [
+            ("%s", "a %s b"),
+            ("%d", "a %d b"),
+            ("%5.2f", "a %5.2f b"),
+            ("%1$s", "a %1$s b"),
+            ("%(name)s", "a %(name)s b"),
+            ("%lu", "a %lu b"),
+            ("%%", "a %% b"),
+            ("%1", "a %1 b"),
+            ("{name}", "a {name} b"),
+            ("\\n", "a \\n b"),
+        ] {
+            let found: Vec<&str> = PLACEHOLDER.find_iter(text).map(|m| m.as_str()).collect();
+            assert_eq!(found, vec![placeholder], "{text}");
+        }
+        // Prose with percentages keeps its words
+        assert!(PLACEHOLDER.find("50% done").is_none());
+    }
+}
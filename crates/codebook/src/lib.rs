@@ -1,25 +1,90 @@
 pub mod checker;
+pub mod confusables;
 pub mod dictionaries;
+mod directives;
+mod gettext;
 mod logging;
 pub mod parser;
 pub mod queries;
 pub mod regexes;
-mod splitter;
+mod sniff;
+// Public only so the throughput bench can time it; not part of the API
+#[doc(hidden)]
+pub mod splitter;
 
 use crate::regexes::get_default_skip_patterns;
-use std::collections::HashSet;
-use std::path::Path;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
 
-use codebook_config::{CodebookConfig, ConfigSettings};
-use dictionaries::{dictionary, manager::DictionaryManager};
+use codebook_config::{CheckScope, CodebookConfig, ConfigSettings};
+use dictionaries::dictionary;
+use dictionaries::manager::{DictionaryManager, file_stamp};
 use dictionary::Dictionary;
 use log::debug;
-use parser::WordLocation;
+use lru::LruCache;
+use parser::{IssueKind, WordLocation};
+
+/// How many words' suggestions `get_suggestions` remembers.
+const SUGGESTION_CACHE_SIZE: usize = 1000;
+
+/// How many times more candidates than are shown each dictionary offers
+/// for ranking by `frequency_list`.
+const FREQUENCY_POOL_FACTOR: usize = 4;
+
+/// Captures whose words `learn_project_symbols` learns: the names of
+/// defined functions and types.
+const PROJECT_SYMBOL_TAGS: [&str; 2] = ["identifier.function", "identifier.type"];
+
+/// (word, hash of the dictionaries fingerprint and suggestion settings)
+type SuggestionKey = (String, u64);
+
+/// Errors from Codebook's fallible APIs. Dictionaries that fail to load
+/// aren't errors here: checks go on without them, and they are reported by
+/// `Codebook::dictionary_load_errors`.
+#[derive(Debug, thiserror::Error)]
+pub enum CodebookError {
+    #[error("failed to read {path}: {source}")]
+    ReadFile { path: PathBuf, source: io::Error },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+/// What `Codebook::fetch_dictionaries` did for one dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchStatus {
+    AlreadyCached,
+    Downloaded,
+    Failed(String),
+}
+
+/// Result of `Codebook::autofix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Autofix {
+    /// The text with every confident fix applied
+    pub text: String,
+    /// Each misspelled word that was replaced, with its replacement
+    pub fixed: Vec<(String, String)>,
+    /// Misspelled words left alone: no suggestion, or no clear winner
+    pub unfixed: Vec<String>,
+}
 
 pub struct Codebook {
     config: Arc<dyn CodebookConfig>,
-    manager: DictionaryManager,
+    manager: Arc<DictionaryManager>,
+    /// Keyed by dictionary set too, so a config reload that changes
+    /// dictionaries never serves stale suggestions.
+    suggestion_cache: Mutex<LruCache<SuggestionKey, Option<Vec<String>>>>,
+    /// Lowercased words of the function and type names each file defines,
+    /// by path, for the files checked or learned from with
+    /// `learn_project_symbols` on. Replaced when a file is checked again;
+    /// text without a path is kept under `""`.
+    project_symbols: RwLock<HashMap<String, HashSet<String>>>,
 }
 
 // Custom 'codebook' dictionary could be removed later for a more general solution.
@@ -40,71 +105,374 @@ impl Codebook {
     ) -> Self {
         let manager =
             DictionaryManager::with_local_dir(&config.cache_dir().to_path_buf(), dictionary_dir);
-        Self { config, manager }
+        Self::with_manager(config, Arc::new(manager))
+    }
+
+    /// Like `new`, but dictionaries come from `manager`, which may be shared
+    /// with other checkers (e.g. one per config in a workspace) so each
+    /// dictionary is loaded once. Share it only between configs with the
+    /// same `cache_dir`: downloads go to the manager's.
+    pub fn with_manager(config: Arc<dyn CodebookConfig>, manager: Arc<DictionaryManager>) -> Self {
+        Self {
+            config,
+            manager,
+            suggestion_cache: Mutex::new(LruCache::new(
+                NonZeroUsize::new(SUGGESTION_CACHE_SIZE).unwrap(),
+            )),
+            project_symbols: RwLock::new(HashMap::new()),
+        }
     }
 
     /// Get WordLocations for a block of text.
     /// Supply LanguageType, file path or both to use the correct code parser.
+    /// Results are in document order: each word's locations are sorted by
+    /// byte offset, and words are sorted by their first location.
     pub fn spell_check(
         &self,
         text: &str,
         language: Option<queries::LanguageType>,
         file_path: Option<&str>,
     ) -> Vec<parser::WordLocation> {
-        // ignore_paths and include_paths are evaluated BEFORE overrides
-        if let Some(file_path) = file_path {
-            if self.config.should_ignore_path(Path::new(file_path)) {
-                return Vec::new();
-            }
-            if !self.config.should_include_path(Path::new(file_path)) {
-                return Vec::new();
-            }
+        self.spell_check_with_dictionaries(text, language, file_path, &[])
+    }
+
+    /// Like `spell_check`, but also looks words up in `extra_dictionaries`
+    /// (dictionary IDs such as `"medical"`) for this call only, without
+    /// touching the config.
+    pub fn spell_check_with_dictionaries(
+        &self,
+        text: &str,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+        extra_dictionaries: &[String],
+    ) -> Vec<parser::WordLocation> {
+        if file_path.is_some_and(|fp| self.skips_path(fp)) {
+            return Vec::new();
+        }
+        self.check_text(text, language, file_path, extra_dictionaries)
+    }
+
+    /// Like `spell_check`, but checks the file even if `ignore_paths` or
+    /// `include_paths` leave it out, for files the user asked for by name.
+    /// The path still picks the language and the overrides that apply.
+    pub fn spell_check_ignoring_paths(
+        &self,
+        text: &str,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+    ) -> Vec<parser::WordLocation> {
+        self.check_text(text, language, file_path, &[])
+    }
+
+    fn check_text(
+        &self,
+        text: &str,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+        extra_dictionaries: &[String],
+    ) -> Vec<parser::WordLocation> {
+        if self.skips_content(text, file_path) {
+            return Vec::new();
         }
 
         // Resolve per-file settings (applies matching overrides)
         let resolved = file_path.and_then(|fp| self.config.resolve_for_file(Path::new(fp)));
 
-        let language = self.resolve_language(language, file_path);
+        let start = Instant::now();
+        let (candidates, languages_found) =
+            self.extract_words(text, language, file_path, resolved.as_deref());
+        let parse_time = start.elapsed();
+
+        // Load dictionaries for all languages encountered (using resolved settings if any)
+        let dictionaries = self.get_dictionaries_for_languages(
+            &languages_found,
+            resolved.as_deref(),
+            extra_dictionaries,
+        );
+        let load_time = start.elapsed() - parse_time;
+
+        // Check words against dictionaries
+        let mut results = checker::check_words(
+            &candidates,
+            &dictionaries,
+            self.config.as_ref(),
+            resolved.as_deref(),
+        );
+        if self.learns_project_symbols(resolved.as_deref()) {
+            self.learn_project_symbols(file_path, &candidates);
+            self.allow_project_symbols(&candidates, &mut results, resolved.as_deref());
+        }
+        if self.config.flag_confusables() {
+            self.report_confusables(&candidates, &mut results, resolved.as_deref());
+        }
+        let total_time = start.elapsed();
+        debug!(
+            "Checked {} ({} bytes, {} words, {} misspelled) in {total_time:?}: \
+             parse {parse_time:?}, dictionary load {load_time:?}, lookup {:?}",
+            file_path.unwrap_or("<text>"),
+            text.len(),
+            candidates.len(),
+            results.len(),
+            total_time - parse_time - load_time,
+        );
+        results
+    }
+
+    /// Learn the function and type names `text` defines as project symbols
+    /// without checking it, so that files checked before it already know
+    /// them. Does nothing unless `learn_project_symbols` is on for the file.
+    /// Like `spell_check`, this replaces what the file taught before. Like
+    /// `spell_check_ignoring_paths`, it leaves picking the files to the caller.
+    pub fn learn_symbols(
+        &self,
+        text: &str,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+    ) {
+        if self.skips_content(text, file_path) {
+            return;
+        }
+        let resolved = file_path.and_then(|fp| self.config.resolve_for_file(Path::new(fp)));
+        if !self.learns_project_symbols(resolved.as_deref()) {
+            return;
+        }
+        let (candidates, _) = self.extract_words(text, language, file_path, resolved.as_deref());
+        self.learn_project_symbols(file_path, &candidates);
+    }
+
+    /// Extract the words to check from `text`, recursively following
+    /// injections, with the languages they were found in.
+    fn extract_words<'a>(
+        &self,
+        text: &'a str,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+        resolved: Option<&ConfigSettings>,
+    ) -> (
+        Vec<checker::WordCandidate<'a>>,
+        HashSet<queries::LanguageType>,
+    ) {
+        let language = self.resolve_language(language, file_path, text);
 
         // Combine default and user skip patterns
         let mut all_patterns = get_default_skip_patterns().clone();
-        if let Some(ref settings) = resolved {
+        if let Some(settings) = resolved {
             all_patterns.extend(settings.ignore_patterns.iter().cloned());
         } else {
             all_patterns.extend(self.config.get_ignore_patterns());
         }
 
+        let split = match resolved {
+            Some(settings) => splitter::SplitOptions {
+                camel_case: settings.split_camel_case(),
+                hyphenated_compounds: settings.hyphenated_compounds(),
+            },
+            None => splitter::SplitOptions {
+                camel_case: self.config.split_camel_case(),
+                hyphenated_compounds: self.config.hyphenated_compounds(),
+            },
+        };
+
         // Extract all words, recursively following injections
-        let (candidates, languages_found) = parser::extract_all_words(
+        parser::extract_all_words_with(
             text,
             language,
-            &|tag| self.config.should_check_tag(tag),
+            &|language, tag| {
+                self.config.should_check_tag(tag)
+                    && (!CheckScope::Strings.covers_tag(tag)
+                        || self.config.check_strings(language.ids()))
+            },
             &all_patterns,
-        );
+            &self.config.get_keep_together_patterns(),
+            split,
+        )
+    }
 
-        // Load dictionaries for all languages encountered (using resolved settings if any)
-        let dictionaries =
-            self.get_dictionaries_for_languages(&languages_found, resolved.as_deref());
+    /// Add a confusable result for each word in `candidates` that mixes
+    /// look-alike letters from different scripts, in place of its
+    /// misspelling if it has one. Allowed words aren't reported.
+    fn report_confusables(
+        &self,
+        candidates: &[checker::WordCandidate],
+        results: &mut Vec<WordLocation>,
+        resolved: Option<&ConfigSettings>,
+    ) {
+        let is_allowed = |w: &str| match resolved {
+            Some(s) => s.is_allowed_word(w),
+            None => self.config.is_allowed_word(w),
+        };
+        let mut confusables: Vec<WordLocation> = Vec::new();
+        // Each word's index in `confusables`, or None if it isn't one
+        let mut seen: HashMap<&str, Option<usize>> = HashMap::new();
+        for candidate in candidates {
+            let index = *seen.entry(candidate.word).or_insert_with(|| {
+                if confusables::find_confusable(candidate.word).is_none()
+                    || is_allowed(candidate.word)
+                {
+                    return None;
+                }
+                confusables.push(
+                    WordLocation::new(candidate.word.to_string(), Vec::new())
+                        .with_tag(candidate.tag)
+                        .with_kind(IssueKind::Confusable),
+                );
+                Some(confusables.len() - 1)
+            });
+            let Some(index) = index else { continue };
+            let range = parser::TextRange {
+                start_byte: candidate.start_byte,
+                end_byte: candidate.end_byte,
+            };
+            if !confusables[index].locations.contains(&range) {
+                confusables[index].locations.push(range);
+            }
+        }
+        if confusables.is_empty() {
+            return;
+        }
+        results.retain(|r| seen.get(r.word.as_str()).is_none_or(Option::is_none));
+        for confusable in &mut confusables {
+            confusable.locations.sort_unstable_by_key(|r| r.start_byte);
+        }
+        results.extend(confusables);
+        results.sort_unstable_by_key(|l| l.locations.first().map(|r| r.start_byte));
+    }
 
-        // Check words against dictionaries
-        checker::check_words(
-            &candidates,
-            &dictionaries,
-            self.config.as_ref(),
-            resolved.as_deref(),
-        )
+    /// Whether `learn_project_symbols` is on, in the file's settings if it
+    /// has any.
+    fn learns_project_symbols(&self, resolved: Option<&ConfigSettings>) -> bool {
+        match resolved {
+            Some(settings) => settings.learn_project_symbols(),
+            None => self.config.learn_project_symbols(),
+        }
     }
 
+    /// Set the file's project symbols to the words of the function and type
+    /// names `candidates` define, forgetting the ones it defined before.
+    /// Text without a path can't be told apart, so what it defines is kept.
+    fn learn_project_symbols(
+        &self,
+        file_path: Option<&str>,
+        candidates: &[checker::WordCandidate],
+    ) {
+        let learned: HashSet<String> = candidates
+            .iter()
+            .filter(|c| c.tag.is_some_and(|tag| PROJECT_SYMBOL_TAGS.contains(&tag)))
+            .map(|c| c.word.to_lowercase())
+            .collect();
+        let mut symbols = self.project_symbols.write().unwrap();
+        match file_path {
+            Some(path) if learned.is_empty() => {
+                symbols.remove(path);
+            }
+            Some(path) => {
+                symbols.insert(path.to_string(), learned);
+            }
+            None if learned.is_empty() => {}
+            None => symbols.entry(String::new()).or_default().extend(learned),
+        }
+    }
+
+    /// Drop the reuses of learned project symbols from `results`. A word is
+    /// still reported where a function or type name defines it, so a
+    /// misspelled name is caught once, and flagged words everywhere.
+    fn allow_project_symbols(
+        &self,
+        candidates: &[checker::WordCandidate],
+        results: &mut Vec<WordLocation>,
+        resolved: Option<&ConfigSettings>,
+    ) {
+        let definitions: HashMap<parser::TextRange, &str> = candidates
+            .iter()
+            .filter_map(|c| {
+                let tag = c.tag.filter(|tag| PROJECT_SYMBOL_TAGS.contains(tag))?;
+                let range = parser::TextRange {
+                    start_byte: c.start_byte,
+                    end_byte: c.end_byte,
+                };
+                Some((range, tag))
+            })
+            .collect();
+        let symbols = self.project_symbols.read().unwrap();
+        results.retain_mut(|result| {
+            let flagged = match resolved {
+                Some(settings) => settings.should_flag_word(&result.word),
+                None => self.config.should_flag_word(&result.word),
+            };
+            let word = result.word.to_lowercase();
+            if flagged || !symbols.values().any(|words| words.contains(&word)) {
+                return true;
+            }
+            result
+                .locations
+                .retain(|range| definitions.contains_key(range));
+            let Some(first) = result.locations.first() else {
+                return false;
+            };
+            result.tag = definitions.get(first).map(|tag| tag.to_string());
+            true
+        });
+    }
+
+    /// Whether `spell_check` skips this file without checking it: the path
+    /// is excluded by `ignore_paths` or `include_paths`, the content looks
+    /// binary or minified (unless those checks are turned off), or its first
+    /// line has the `disable_file_marker`.
+    pub fn skips_file(&self, text: &str, file_path: Option<&str>) -> bool {
+        file_path.is_some_and(|fp| self.skips_path(fp)) || self.skips_content(text, file_path)
+    }
+
+    /// Whether `ignore_paths` or `include_paths` leave the file out.
+    fn skips_path(&self, file_path: &str) -> bool {
+        // ignore_paths and include_paths are evaluated BEFORE overrides
+        self.config.should_ignore_path(Path::new(file_path))
+            || !self.config.should_include_path(Path::new(file_path))
+    }
+
+    /// Whether the content is skipped: binary, minified or marked with
+    /// `disable_file_marker`. `file_path` is only for logging.
+    fn skips_content(&self, text: &str, file_path: Option<&str>) -> bool {
+        if self.config.skip_binary() && sniff::looks_binary(text) {
+            debug!("Skipping binary content in {file_path:?}");
+            return true;
+        }
+        if self.config.skip_minified() && sniff::looks_minified(text) {
+            debug!("Skipping minified content in {file_path:?}");
+            return true;
+        }
+        if let Some(marker) = self.config.disable_file_marker()
+            && sniff::has_disable_marker(text, &marker)
+        {
+            debug!("Skipping {file_path:?}: first line has '{marker}'");
+            return true;
+        }
+        false
+    }
+
+    /// Pick the parser for a file. A `language_overrides` match wins over
+    /// both the caller's language (editors only know the extension too) and
+    /// extension-based detection. Files whose name doesn't give a language
+    /// fall back to their `#!` line.
     fn resolve_language(
         &self,
         language_type: Option<queries::LanguageType>,
         path: Option<&str>,
+        text: &str,
     ) -> queries::LanguageType {
+        if let Some(path) = path
+            && let Some(name) = self.config.language_override(Path::new(path))
+        {
+            debug!("Language for {path} forced to '{name}' by language_overrides");
+            return queries::LanguageType::from_str(&name.to_ascii_lowercase())
+                .unwrap_or(queries::LanguageType::Text);
+        }
         match language_type {
             Some(lang) => lang,
-            None => match path {
-                Some(path) => queries::get_language_name_from_filename(path),
-                None => queries::LanguageType::Text,
+            None => match path.map(queries::get_language_name_from_filename) {
+                Some(lang) if lang != queries::LanguageType::Text => lang,
+                _ => {
+                    queries::get_language_from_shebang(text).unwrap_or(queries::LanguageType::Text)
+                }
             },
         }
     }
@@ -115,11 +483,47 @@ impl Codebook {
         &self,
         languages: &HashSet<queries::LanguageType>,
         resolved: Option<&ConfigSettings>,
+        extra_dictionaries: &[String],
     ) -> Vec<Arc<dyn Dictionary>> {
+        let dictionary_ids =
+            self.dictionary_ids_with_extras(languages, resolved, extra_dictionaries);
+        self.load_dictionaries(dictionary_ids)
+    }
+
+    /// `dictionary_ids_for_languages` plus the ad-hoc `extra_dictionaries`
+    /// of a single check.
+    fn dictionary_ids_with_extras(
+        &self,
+        languages: &HashSet<queries::LanguageType>,
+        resolved: Option<&ConfigSettings>,
+        extra_dictionaries: &[String],
+    ) -> Vec<String> {
+        let mut dictionary_ids = self.dictionary_ids_for_languages(languages, resolved);
+        for id in extra_dictionaries {
+            let id = id.to_lowercase();
+            self.manager.warn_if_unknown(&id);
+            if !dictionary_ids.contains(&id) {
+                dictionary_ids.push(id);
+            }
+        }
+        dictionary_ids
+    }
+
+    /// Sorted, deduplicated IDs of every dictionary that applies to a file.
+    fn dictionary_ids_for_languages(
+        &self,
+        languages: &HashSet<queries::LanguageType>,
+        resolved: Option<&ConfigSettings>,
+    ) -> Vec<String> {
         let mut dictionary_ids = match resolved {
             Some(settings) => settings.dictionary_ids(),
             None => self.config.get_dictionary_ids(),
         };
+        // Language dictionaries are optional, but a configured ID that
+        // matches nothing is almost always a typo.
+        for id in &dictionary_ids {
+            self.manager.warn_if_unknown(id);
+        }
 
         for lang in languages {
             dictionary_ids.extend(lang.dictionary_ids());
@@ -129,38 +533,575 @@ impl Codebook {
 
         dictionary_ids.sort();
         dictionary_ids.dedup();
+        dictionary_ids
+    }
 
+    fn load_dictionaries(&self, dictionary_ids: Vec<String>) -> Vec<Arc<dyn Dictionary>> {
+        self.load_named_dictionaries(dictionary_ids)
+            .into_iter()
+            .map(|(_, dictionary)| dictionary)
+            .collect()
+    }
+
+    /// Like `load_dictionaries`, keeping each dictionary's ID, or its name
+    /// for custom dictionaries. IDs that don't load are left out.
+    fn load_named_dictionaries(
+        &self,
+        dictionary_ids: Vec<String>,
+    ) -> Vec<(String, Arc<dyn Dictionary>)> {
+        // Re-read every time so a config reload can toggle it
+        let offline = self.is_offline();
         let mut dictionaries = Vec::with_capacity(dictionary_ids.len());
         debug!("Checking text with dictionaries: {dictionary_ids:?}");
         for dictionary_id in dictionary_ids {
-            if let Some(d) = self.manager.get_dictionary(&dictionary_id) {
-                dictionaries.push(d);
+            if let Some(d) = self.manager.get_dictionary(&dictionary_id, offline) {
+                dictionaries.push((dictionary_id, d));
+            }
+        }
+        for entry in self.config.custom_dictionaries() {
+            if let Some(d) = self.manager.get_custom_dictionary(&entry) {
+                dictionaries.push((entry.name, d));
             }
         }
         dictionaries
     }
 
+    /// IDs of the dictionaries a file is actually checked with: the
+    /// configured, language and default ones (plus `extra_dictionaries`)
+    /// that loaded, then custom dictionaries by name. Unlike the config,
+    /// this leaves out IDs that are unknown or failed to download or
+    /// parse. `language` and `file_path` pick the language and overrides as
+    /// in `spell_check`; languages injected into the file aren't counted.
+    pub fn active_dictionaries(
+        &self,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+        extra_dictionaries: &[String],
+    ) -> Vec<String> {
+        let resolved = file_path.and_then(|fp| self.config.resolve_for_file(Path::new(fp)));
+        let languages = HashSet::from([self.resolve_language(language, file_path, "")]);
+        let dictionary_ids =
+            self.dictionary_ids_with_extras(&languages, resolved.as_deref(), extra_dictionaries);
+        self.load_named_dictionaries(dictionary_ids)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Use `dictionary` for `id` instead of loading it, for apps that supply
+    /// their own words. Replaces a dictionary already loaded under `id`; a
+    /// new ID is only used once the config lists it in `dictionaries` or a
+    /// check is passed it, as in `spell_check_with_dictionaries`.
+    pub fn register_dictionary(&self, id: &str, dictionary: Arc<dyn Dictionary>) {
+        self.manager.register_dictionary(id, dictionary);
+        self.suggestion_cache.lock().unwrap().clear();
+    }
+
+    /// Load the dictionaries every file uses (configured ones, the defaults
+    /// and custom dictionaries) into memory ahead of the first check, which
+    /// would otherwise wait on downloading and parsing them. Loading is the
+    /// same either way, only earlier, so run it off the critical path, e.g.
+    /// in the background when a server starts. Returns how many loaded.
+    pub fn preload_dictionaries(&self) -> usize {
+        let dictionary_ids = self.dictionary_ids_for_languages(&HashSet::new(), None);
+        self.load_dictionaries(dictionary_ids).len()
+    }
+
+    /// Whether dictionaries may only come from the cache, via the `offline`
+    /// setting or a truthy `CODEBOOK_OFFLINE` environment variable.
+    pub fn is_offline(&self) -> bool {
+        self.config.offline() || env_flag("CODEBOOK_OFFLINE")
+    }
+
+    /// Load every dictionary this config could use (configured ones, the
+    /// defaults and all language dictionaries) so they are cached for
+    /// offline use. Returns each dictionary ID with what happened to it.
+    pub fn fetch_dictionaries(&self) -> Vec<(String, FetchStatus)> {
+        let all_languages: HashSet<queries::LanguageType> = queries::LANGUAGE_SETTINGS
+            .iter()
+            .map(|setting| setting.type_)
+            .collect();
+        let configured = self.config.get_dictionary_ids();
+        let dictionary_ids = self.dictionary_ids_for_languages(&all_languages, None);
+        let offline = self.is_offline();
+        dictionary_ids
+            .into_iter()
+            // Many languages name dictionaries that don't exist (yet); only
+            // configured IDs are required to resolve.
+            .filter(|id| self.manager.is_known(id) || configured.contains(id))
+            .map(|id| {
+                let was_cached = self.manager.is_cached(&id);
+                let status = match self.manager.get_dictionary(&id, offline) {
+                    Some(_) if was_cached => FetchStatus::AlreadyCached,
+                    Some(_) => FetchStatus::Downloaded,
+                    None => FetchStatus::Failed(
+                        self.manager
+                            .load_error(&id)
+                            .unwrap_or_else(|| "unknown dictionary".to_string()),
+                    ),
+                };
+                (id, status)
+            })
+            .collect()
+    }
+
+    /// Describe dictionaries that failed to load (download or parse errors)
+    /// and are missing from checks until a later attempt succeeds.
+    pub fn dictionary_load_errors(&self) -> Vec<String> {
+        self.manager.load_errors()
+    }
+
+    /// Names of the `detect_languages` dictionaries that know `word`, like
+    /// "French", to hint at the language a misspelling is written in. Meant
+    /// for words `spell_check` already reported; with no `detect_languages`
+    /// configured it does no lookups.
+    pub fn guess_languages(&self, word: &str) -> Vec<String> {
+        let ids = self.config.detect_languages();
+        if ids.is_empty() {
+            return Vec::new();
+        }
+        let offline = self.is_offline();
+        ids.into_iter()
+            .filter(|id| {
+                self.manager
+                    .get_dictionary(id, offline)
+                    .is_some_and(|d| d.check(word))
+            })
+            .map(|id| match dictionaries::repo::display_name(&id) {
+                "" => id,
+                name => name.to_string(),
+            })
+            .collect()
+    }
+
+    /// Whether `word` is reported because it is in `flag_words` (for the
+    /// file's resolved settings), rather than because no dictionary knows it.
+    pub fn is_flagged_word(&self, word: &str, file_path: Option<&str>) -> bool {
+        match file_path.and_then(|fp| self.config.resolve_for_file(Path::new(fp))) {
+            Some(settings) => settings.should_flag_word(word),
+            None => self.config.should_flag_word(word),
+        }
+    }
+
     /// Spell check a file on disk, detecting the language from its path.
     /// Errors if the file can't be read (including non-UTF-8 content).
-    pub fn spell_check_file(&self, path: &str) -> Result<Vec<WordLocation>, std::io::Error> {
-        let lang_type = queries::get_language_name_from_filename(path);
-        let file_text = std::fs::read_to_string(path)?;
-        Ok(self.spell_check(&file_text, Some(lang_type), Some(path)))
+    pub fn spell_check_file(&self, path: &str) -> Result<Vec<WordLocation>, CodebookError> {
+        let read_error = |source| CodebookError::ReadFile {
+            path: path.into(),
+            source,
+        };
+        let text = std::fs::read_to_string(path).map_err(read_error)?;
+        Ok(self.spell_check(&text, None, Some(path)))
+    }
+
+    /// Spell check everything `reader` yields, like `spell_check`. Content
+    /// that isn't valid UTF-8 is an `InvalidData` error rather than a panic.
+    pub fn spell_check_reader<R: Read>(
+        &self,
+        reader: R,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+    ) -> Result<Vec<WordLocation>, CodebookError> {
+        let text = io::read_to_string(reader)?;
+        Ok(self.spell_check(&text, language, file_path))
     }
 
     /// Get suggestions for a misspelled word. Returns None when the word is
     /// correctly spelled — same rule as check_words: any dictionary knowing
-    /// the word makes it correct.
+    /// the word makes it correct. Only words within
+    /// `max_suggestion_distance` edits are suggested, so the list can be
+    /// empty.
+    /// With a `frequency_list` configured, candidates are ranked most
+    /// frequent first before the list is cut to size.
+    /// Results are cached per word, dictionary set and state of the custom
+    /// dictionary, local dictionary and frequency list files, since editors
+    /// ask again every time the cursor lands on the same misspelling.
     pub fn get_suggestions(&self, word: &str) -> Option<Vec<String>> {
         let max_results = 5;
-        let dictionaries = self.get_dictionaries_for_languages(&HashSet::new(), None);
-        if dictionaries.is_empty() || dictionaries.iter().any(|dict| dict.check(word)) {
+        let dictionary_ids = self.dictionary_ids_for_languages(&HashSet::new(), None);
+        let frequency_list = self.config.frequency_list();
+        let mut hasher = DefaultHasher::new();
+        self.config.dictionaries_fingerprint().hash(&mut hasher);
+        frequency_list.hash(&mut hasher);
+        // Edited custom dictionaries and frequency lists are reloaded, so
+        // what was suggested from the old files mustn't be reused
+        for entry in self.config.custom_dictionaries() {
+            file_stamp(&[&entry.aff_path, &entry.dic_path]).hash(&mut hasher);
+        }
+        if let Some(path) = &frequency_list {
+            file_stamp(&[path]).hash(&mut hasher);
+        }
+        for id in &dictionary_ids {
+            self.manager.local_stamp(id).hash(&mut hasher);
+        }
+        let max_distance = self.config.max_suggestion_distance(word);
+        max_distance.hash(&mut hasher);
+        let key = (word.to_string(), hasher.finish());
+
+        if let Some(cached) = self.suggestion_cache.lock().unwrap().get(&key) {
+            return cached.clone();
+        }
+
+        let start = Instant::now();
+        let dictionaries = self.load_dictionaries(dictionary_ids);
+        // An empty set usually means dictionaries failed to load; don't
+        // remember that, so suggestions appear once they do.
+        if dictionaries.is_empty() {
             return None;
         }
-        let suggestions: Vec<Vec<String>> =
-            dictionaries.iter().map(|dict| dict.suggest(word)).collect();
-        Some(collect_round_robin(&suggestions, max_results))
+        let suggestions = if dictionaries.iter().any(|dict| dict.check(word)) {
+            None
+        } else {
+            let frequencies =
+                frequency_list.and_then(|path| self.manager.get_frequency_dictionary(&path));
+            // Ranking by frequency picks from a wider pool than is shown
+            let pool = if frequencies.is_some() {
+                max_results * FREQUENCY_POOL_FACTOR
+            } else {
+                max_results
+            };
+            // Hunspell offers far-off words for badly mangled ones; only
+            // plausibly close corrections are worth showing
+            let lowercase = word.to_lowercase();
+            let is_close = |s: &str| edit_distance(&lowercase, &s.to_lowercase()) <= max_distance;
+            let suggestions: Vec<Vec<String>> = dictionaries
+                .iter()
+                .map(|dict| dict.suggest_filtered(word, pool, &is_close))
+                .collect();
+            Some(match frequencies {
+                Some(frequencies) => {
+                    let total = suggestions.iter().map(Vec::len).sum();
+                    let mut ranked = collect_round_robin(&suggestions, total);
+                    frequencies.rank(&mut ranked);
+                    ranked.truncate(max_results);
+                    ranked
+                }
+                None => collect_round_robin(&suggestions, max_results),
+            })
+        };
+        debug!(
+            "Suggested {} words for '{word}' in {:?}",
+            suggestions.as_ref().map_or(0, Vec::len),
+            start.elapsed()
+        );
+        self.suggestion_cache
+            .lock()
+            .unwrap()
+            .put(key, suggestions.clone());
+        suggestions
     }
+
+    /// Suggestions for `word` where it was found: at `start_byte` of `text`,
+    /// under the capture `tag`. With `sentence_case_suggestions` on, a word
+    /// in a comment or plain text gets capitalized suggestions when it starts
+    /// a sentence and lowercase ones otherwise. Suggestions with capitals
+    /// past the first letter, like `GitHub`, keep their casing. Mid-sentence,
+    /// words the dictionaries have capitalized, like `I` and `Paris`, are
+    /// capitalized too.
+    pub fn get_suggestions_in_context(
+        &self,
+        word: &str,
+        text: &str,
+        start_byte: usize,
+        tag: Option<&str>,
+    ) -> Option<Vec<String>> {
+        let suggestions = self.get_suggestions(word)?;
+        let prose = tag.is_none_or(|tag| tag.starts_with("comment"));
+        if !self.config.sentence_case_suggestions() || !prose {
+            return Some(suggestions);
+        }
+        let before = text.get(..start_byte).unwrap_or_default();
+        let sentence_start = starts_sentence(before, tag.is_some());
+        let dictionary_ids = self.dictionary_ids_for_languages(&HashSet::new(), None);
+        let dictionaries = self.load_dictionaries(dictionary_ids);
+        let known = |word: &str| dictionaries.iter().any(|d| d.check_exact_case(word));
+        let mut cased: Vec<String> = Vec::with_capacity(suggestions.len());
+        for suggestion in suggestions {
+            let suggestion = if suggestion.chars().skip(1).any(char::is_uppercase) {
+                suggestion
+            } else if sentence_start {
+                recase_first(&suggestion, true)
+            } else if suggestion.starts_with(char::is_uppercase) {
+                // Capitalized to match the misspelling, unless it's a
+                // capitalized word like `Paris`. Dictionaries list `i` as
+                // the letter, so the pronoun is kept by name.
+                let lowercase = recase_first(&suggestion, false);
+                if suggestion == "I" || !known(&lowercase) {
+                    suggestion
+                } else {
+                    lowercase
+                }
+            } else {
+                // Lowercased to match the misspelling, maybe a name
+                let capitalized = recase_first(&suggestion, true);
+                if !known(&suggestion) && known(&capitalized) {
+                    capitalized
+                } else {
+                    suggestion
+                }
+            };
+            if !cased.contains(&suggestion) {
+                cased.push(suggestion);
+            }
+        }
+        Some(cased)
+    }
+
+    /// Correct every misspelled subword of `identifier` with its top
+    /// suggestion, e.g. `calculaateScoore` to `calculateScore`. Returns None
+    /// unless at least two subwords were corrected: a single fix is already
+    /// offered for the subword itself.
+    pub fn get_identifier_suggestion(&self, identifier: &str) -> Option<String> {
+        let mut fixes: Vec<(parser::TextRange, String)> = Vec::new();
+        for location in self.spell_check(identifier, Some(queries::LanguageType::Text), None) {
+            let Some(suggestion) = self
+                .get_suggestions(&location.word)
+                .and_then(|s| s.into_iter().next())
+            else {
+                continue;
+            };
+            let replacement = match_case(&location.word, &suggestion);
+            fixes.extend(
+                location
+                    .locations
+                    .into_iter()
+                    .map(|r| (r, replacement.clone())),
+            );
+        }
+        if fixes.len() < 2 {
+            return None;
+        }
+        Some(apply_edits(identifier, fixes))
+    }
+
+    /// Replace every misspelled word in `text` with its suggestion when the
+    /// suggestion is a clear winner: close to the word, and the best pick of
+    /// every dictionary that has one. Other misspellings are left as they
+    /// are and listed in `unfixed`. A fixed word is replaced everywhere it
+    /// appears in the text as a subword (see `rename_ranges`), so renaming
+    /// an identifier at its definition renames its uses in the same file
+    /// too. Uses in other files are not touched.
+    pub fn autofix(
+        &self,
+        text: &str,
+        language: Option<queries::LanguageType>,
+        file_path: Option<&str>,
+    ) -> Autofix {
+        let dictionary_ids = self.dictionary_ids_for_languages(&HashSet::new(), None);
+        let dictionaries = self.load_dictionaries(dictionary_ids);
+        let mut edits: Vec<(parser::TextRange, String)> = Vec::new();
+        let mut fixed = Vec::new();
+        let mut unfixed = Vec::new();
+        for location in self.spell_check(text, language, file_path) {
+            let Some(suggestion) = confident_suggestion(&location.word, &dictionaries) else {
+                unfixed.push(location.word);
+                continue;
+            };
+            let replacement = match_case(&location.word, &suggestion);
+            edits.extend(
+                rename_ranges(text, &location.word, &location.locations)
+                    .into_iter()
+                    .map(|r| (r, replacement.clone())),
+            );
+            fixed.push((location.word, replacement));
+        }
+        Autofix {
+            text: apply_edits(text, edits),
+            fixed,
+            unfixed,
+        }
+    }
+}
+
+/// Furthest a suggestion may be from the word (in single-character edits,
+/// counting a swap of neighbours as one) for `autofix` to apply it.
+const AUTOFIX_MAX_EDIT_DISTANCE: usize = 2;
+
+/// The suggestion `autofix` may apply for `word`: every dictionary with a
+/// suggestion must rank the same one first, and it must be a single word
+/// within `AUTOFIX_MAX_EDIT_DISTANCE`.
+fn confident_suggestion(word: &str, dictionaries: &[Arc<dyn Dictionary>]) -> Option<String> {
+    let mut best: Option<String> = None;
+    for dictionary in dictionaries {
+        let Some(top) = dictionary.suggest(word).into_iter().next() else {
+            continue;
+        };
+        match &best {
+            Some(b) if !b.eq_ignore_ascii_case(&top) => return None,
+            Some(_) => {}
+            None => best = Some(top),
+        }
+    }
+    let best = best?;
+    let single_word = best.chars().all(char::is_alphanumeric);
+    let close =
+        edit_distance(&word.to_lowercase(), &best.to_lowercase()) <= AUTOFIX_MAX_EDIT_DISTANCE;
+    (single_word && close).then_some(best)
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions
+/// and transpositions of adjacent characters each cost one.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut d = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d = d.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = d;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Where to replace `word` in `text` to fix it throughout: the `reported`
+/// ranges plus every other place it appears as a whole subword, as split
+/// for checking (`calulate` in `calulate_total()`). Matching is
+/// case-sensitive, so `Calulate` is another word. Identifier queries
+/// capture definitions, not uses, so this is what keeps a fix from renaming
+/// a function but not its callers. Sorted and without duplicates.
+pub fn rename_ranges(
+    text: &str,
+    word: &str,
+    reported: &[parser::TextRange],
+) -> Vec<parser::TextRange> {
+    let mut ranges: Vec<parser::TextRange> = reported.to_vec();
+    let mut subwords = Vec::new();
+    let mut token_start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        if c.is_alphanumeric() || c == '_' {
+            token_start.get_or_insert(i);
+            continue;
+        }
+        let Some(start) = token_start.take() else {
+            continue;
+        };
+        splitter::split_into(&text[start..i], &mut subwords);
+        ranges.extend(
+            subwords
+                .iter()
+                .filter(|subword| subword.word == word)
+                .map(|subword| parser::TextRange {
+                    start_byte: start + subword.start_byte,
+                    end_byte: start + subword.start_byte + word.len(),
+                }),
+        );
+    }
+    ranges.sort();
+    ranges.dedup();
+    ranges
+}
+
+/// Apply replacements to `text`, last first so earlier byte offsets stay
+/// valid. Of overlapping edits, only the first is applied.
+pub fn apply_edits(text: &str, mut edits: Vec<(parser::TextRange, String)>) -> String {
+    edits.sort_by_key(|(range, _)| range.start_byte);
+    let mut end = 0;
+    edits.retain(|(range, _)| {
+        let keep = range.start_byte >= end;
+        if keep {
+            end = range.end_byte;
+        }
+        keep
+    });
+    let mut fixed = text.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        fixed.replace_range(range.start_byte..range.end_byte, &replacement);
+    }
+    fixed
+}
+
+/// Give `suggestion` the casing of the subword it replaces, so `Scoore`
+/// becomes `Score` and `SCOORE` becomes `SCORE` inside an identifier.
+fn match_case(original: &str, suggestion: &str) -> String {
+    let mut chars = original.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    if first_upper && original.chars().count() > 1 && chars.all(char::is_uppercase) {
+        return suggestion.to_uppercase();
+    }
+    if first_upper {
+        let mut suggestion_chars = suggestion.chars();
+        return match suggestion_chars.next() {
+            Some(c) => c.to_uppercase().chain(suggestion_chars).collect(),
+            None => String::new(),
+        };
+    }
+    suggestion.to_string()
+}
+
+/// Comment markers a line of prose can start after, longest first.
+const COMMENT_OPENERS: &[&str] = &[
+    "<!--", "/**", "///", "//!", "/*", "//", "--", ";;", "#", "*", ";", "%",
+];
+
+/// Whether a word right after `before` starts a sentence: nothing but
+/// whitespace comes first, the last thing is a `.`, `!` or `?`, or there's
+/// a paragraph break. In a `comment`, the comment marker starts a sentence
+/// too, unless the line just continues the comment on the line above.
+fn starts_sentence(before: &str, comment: bool) -> bool {
+    let mut before = before;
+    let mut first_line = true;
+    loop {
+        let trimmed = before.trim_end();
+        if trimmed.is_empty() || trimmed.ends_with(['.', '!', '?']) {
+            return true;
+        }
+        // A blank line ends a paragraph
+        if before[trimmed.len()..].matches('\n').count() > 1 {
+            return true;
+        }
+        let Some(opener) = COMMENT_OPENERS
+            .iter()
+            .find(|o| comment && trimmed.ends_with(*o))
+        else {
+            return false;
+        };
+        let rest = &trimmed[..trimmed.len() - opener.len()];
+        let line_start = rest.rfind('\n').map_or(0, |i| i + 1);
+        // Text before the marker means it's a trailing comment, and an
+        // empty comment line is a paragraph break
+        if !rest[line_start..].trim().is_empty() || !first_line {
+            return true;
+        }
+        if line_start == 0 {
+            return true;
+        }
+        let above = &rest[..line_start - 1];
+        let above_line = &above[above.rfind('\n').map_or(0, |i| i + 1)..];
+        let above_trimmed = above_line.trim_start();
+        if !COMMENT_OPENERS.iter().any(|o| above_trimmed.starts_with(o)) {
+            return true;
+        }
+        before = above;
+        first_line = false;
+    }
+}
+
+/// `word` with its first letter upper- or lowercased.
+fn recase_first(word: &str, upper: bool) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) if upper => c.to_uppercase().chain(chars).collect(),
+        Some(c) => c.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// True when an environment variable is set to anything but "", "0" or "false".
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).is_ok_and(|v| !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false"))
 }
 
 /// Interleave suggestion lists, preserving each source's ranking: every
@@ -186,6 +1127,262 @@ fn collect_round_robin<T: Clone + PartialEq>(sources: &[Vec<T>], max_count: usiz
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codebook_config::{CodebookConfigMemory, CustomDictionary};
+    use dictionaries::dictionary::TextDictionary;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Knows no words and counts how often it is asked for suggestions.
+    struct CountingDictionary {
+        suggest_calls: Arc<AtomicUsize>,
+    }
+
+    impl Dictionary for CountingDictionary {
+        fn check(&self, _word: &str) -> bool {
+            false
+        }
+        fn suggest(&self, word: &str) -> Vec<String> {
+            self.suggest_calls.fetch_add(1, Ordering::SeqCst);
+            vec![format!("{word}s")]
+        }
+    }
+
+    /// Knows no words and always suggests the same list, best first.
+    struct FixedDictionary(&'static [&'static str]);
+
+    impl Dictionary for FixedDictionary {
+        fn check(&self, _word: &str) -> bool {
+            false
+        }
+        fn suggest(&self, _word: &str) -> Vec<String> {
+            self.0.iter().map(|s| s.to_string()).collect()
+        }
+    }
+
+    /// A Codebook whose every dictionary suggests `suggestions`.
+    fn fixed_codebook(settings: ConfigSettings, suggestions: &'static [&'static str]) -> Codebook {
+        let codebook = Codebook::new(Arc::new(CodebookConfigMemory::new(settings)));
+        for id in codebook.dictionary_ids_for_languages(&HashSet::new(), None) {
+            codebook
+                .manager
+                .register_dictionary(&id, Arc::new(FixedDictionary(suggestions)));
+        }
+        codebook
+    }
+
+    #[test]
+    fn test_codebooks_share_a_manager() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(DictionaryManager::new(&cache_dir.path().to_path_buf()));
+        let first = Codebook::with_manager(
+            Arc::new(CodebookConfigMemory::new(ConfigSettings::default())),
+            manager.clone(),
+        );
+        let second = Codebook::with_manager(
+            Arc::new(CodebookConfigMemory::new(ConfigSettings {
+                words: vec!["wrod".to_string()],
+                ..Default::default()
+            })),
+            manager,
+        );
+        for id in first.dictionary_ids_for_languages(&HashSet::new(), None) {
+            first.register_dictionary(&id, Arc::new(FixedDictionary(&["word"])));
+        }
+
+        // Each keeps its own config, but dictionaries loaded through one
+        // are there for the other
+        let flagged = |codebook: &Codebook| {
+            codebook
+                .spell_check("wrod", Some(queries::LanguageType::Text), None)
+                .len()
+        };
+        assert_eq!(flagged(&first), 1);
+        assert_eq!(flagged(&second), 0);
+        assert_eq!(
+            second.get_suggestions("wodr"),
+            Some(vec!["word".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_close_suggestions_past_the_first_few() {
+        // Far-off candidates take up the first five places
+        let codebook = fixed_codebook(
+            ConfigSettings::default(),
+            &["zzzzzz", "qqqqqq", "xxxxxx", "yyyyyy", "pppppp", "word"],
+        );
+        assert_eq!(
+            codebook.get_suggestions("wrod"),
+            Some(vec!["word".to_string()])
+        );
+
+        // A frequency list ranks candidates beyond the first five too
+        let dir = tempfile::tempdir().unwrap();
+        let frequency_list = dir.path().join("frequencies.tsv");
+        std::fs::write(&frequency_list, "word\t1000\n").unwrap();
+        let codebook = fixed_codebook(
+            ConfigSettings {
+                frequency_list: Some(frequency_list),
+                ..Default::default()
+            },
+            &["wore", "worm", "worn", "work", "wood", "word"],
+        );
+        let suggestions = codebook.get_suggestions("wrod").unwrap();
+        assert_eq!(suggestions, ["word", "wore", "worm", "worn", "work"]);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("teh", "the"), 1);
+        assert_eq!(edit_distance("recieve", "receive"), 1);
+        assert_eq!(edit_distance("calulate", "calculate"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("café", "cafe"), 1);
+    }
+
+    #[test]
+    fn test_get_suggestions_is_cached() {
+        let config = Arc::new(CodebookConfigMemory::default());
+        let codebook = Codebook::new(config.clone());
+        let suggest_calls = Arc::new(AtomicUsize::new(0));
+        for id in codebook.dictionary_ids_for_languages(&HashSet::new(), None) {
+            codebook.manager.register_dictionary(
+                &id,
+                Arc::new(CountingDictionary {
+                    suggest_calls: suggest_calls.clone(),
+                }),
+            );
+        }
+
+        let first = codebook.get_suggestions("wordz");
+        let calls_after_first = suggest_calls.load(Ordering::SeqCst);
+        assert!(calls_after_first > 0);
+
+        let second = codebook.get_suggestions("wordz");
+        assert_eq!(first, second);
+        assert_eq!(first, Some(vec!["wordzs".to_string()]));
+        assert_eq!(suggest_calls.load(Ordering::SeqCst), calls_after_first);
+
+        // A different word is a cache miss
+        codebook.get_suggestions("othr");
+        let calls_after_other = suggest_calls.load(Ordering::SeqCst);
+        assert!(calls_after_other > calls_after_first);
+
+        // So is the same word once the dictionaries fingerprint changes
+        config.add_word("frobnicate");
+        codebook.get_suggestions("wordz");
+        assert!(suggest_calls.load(Ordering::SeqCst) > calls_after_other);
+    }
+
+    #[test]
+    fn test_register_dictionary_is_used_when_configured() {
+        let config = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+            dictionaries: vec!["embedded".to_string()],
+            ..Default::default()
+        }));
+        let codebook = Codebook::new(config);
+        let languages = HashSet::from([queries::LanguageType::Text]);
+        for id in codebook.dictionary_ids_for_languages(&languages, None) {
+            codebook.register_dictionary(&id, Arc::new(TextDictionary::from_words([])));
+        }
+        codebook.register_dictionary(
+            "embedded",
+            Arc::new(TextDictionary::from_words(["frobnicate".to_string()])),
+        );
+        let misspelled: Vec<String> = codebook
+            .spell_check("frobnicate gizmoz", Some(queries::LanguageType::Text), None)
+            .into_iter()
+            .map(|w| w.word)
+            .collect();
+        assert_eq!(misspelled, ["gizmoz"]);
+    }
+
+    #[test]
+    fn test_spell_check_with_dictionaries() {
+        let config = Arc::new(CodebookConfigMemory::default());
+        let codebook = Codebook::new(config.clone());
+        let languages = HashSet::from([queries::LanguageType::Text]);
+        for id in codebook.dictionary_ids_for_languages(&languages, None) {
+            codebook.register_dictionary(&id, Arc::new(TextDictionary::from_words([])));
+        }
+        codebook.register_dictionary(
+            "medical",
+            Arc::new(TextDictionary::from_words(["tachycardia".to_string()])),
+        );
+        let misspelled = |extra: &[String]| -> Vec<String> {
+            codebook
+                .spell_check_with_dictionaries(
+                    "tachycardia gizmoz",
+                    Some(queries::LanguageType::Text),
+                    None,
+                    extra,
+                )
+                .into_iter()
+                .map(|w| w.word)
+                .collect()
+        };
+        assert_eq!(misspelled(&["Medical".to_string()]), ["gizmoz"]);
+        // Only that call used it
+        assert_eq!(misspelled(&[]), ["tachycardia", "gizmoz"]);
+        assert!(!config.get_dictionary_ids().contains(&"medical".to_string()));
+    }
+
+    #[test]
+    fn test_active_dictionaries() {
+        let config = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+            dictionaries: vec!["en_us".to_string(), "nonexistent".to_string()],
+            custom_dictionaries: vec![CustomDictionary {
+                name: "broken".to_string(),
+                aff_path: PathBuf::from("/nonexistent/broken.aff"),
+                dic_path: PathBuf::from("/nonexistent/broken.dic"),
+            }],
+            ..Default::default()
+        }));
+        let codebook = Codebook::new(config);
+        let languages = HashSet::from([queries::LanguageType::Rust]);
+        for id in codebook.dictionary_ids_for_languages(&languages, None) {
+            if id != "nonexistent" {
+                codebook.register_dictionary(&id, Arc::new(TextDictionary::from_words([])));
+            }
+        }
+        codebook.register_dictionary("medical", Arc::new(TextDictionary::from_words([])));
+
+        let active = codebook.active_dictionaries(Some(queries::LanguageType::Rust), None, &[]);
+        assert!(active.contains(&"en_us".to_string()), "{active:?}");
+        assert!(active.contains(&"rust".to_string()), "{active:?}");
+        // Configured, but unknown or failing to load
+        assert!(!active.contains(&"nonexistent".to_string()), "{active:?}");
+        assert!(!active.contains(&"broken".to_string()), "{active:?}");
+        // The language can come from the file name, and extras count
+        let extra = ["medical".to_string()];
+        let active = codebook.active_dictionaries(None, Some("src/notes.txt"), &extra);
+        assert!(active.contains(&"medical".to_string()), "{active:?}");
+        assert!(!active.contains(&"rust".to_string()), "{active:?}");
+    }
+
+    #[test]
+    fn test_starts_sentence() {
+        for before in ["", "  ", "Done. ", "Really?\n", "Stop!  ", "one\n\n"] {
+            assert!(starts_sentence(before, false), "{before:?}");
+        }
+        for before in ["the ", "one, ", "one\n", "// "] {
+            assert!(!starts_sentence(before, false), "{before:?}");
+        }
+        for before in [
+            "// ",
+            "    /// ",
+            "let x = 1; // ",
+            "fn main() {}\n// ",
+            "// Done.\n// ",
+            "// One\n//\n// ",
+            "/**\n * ",
+        ] {
+            assert!(starts_sentence(before, true), "{before:?}");
+        }
+        for before in ["// The ", "// This runs\n// ", "/** Some\n * ", "# a\n  # "] {
+            assert!(!starts_sentence(before, true), "{before:?}");
+        }
+    }
 
     #[test]
     fn test_collect_round_robin_basic() {
@@ -1,5 +1,7 @@
 use codebook::Codebook;
-use codebook::queries::{LanguageType, get_language_name_from_filename, get_language_setting};
+use codebook::queries::{
+    LanguageType, debug_captures, get_language_name_from_filename, get_language_setting,
+};
 use std::env;
 use std::fs::File;
 use std::path::Path;
@@ -7,12 +9,23 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tree_sitter::Parser;
 
+#[cfg(not(target_os = "windows"))]
+#[path = "../benches/samples/mod.rs"]
+mod samples;
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
     // --ast <file> [--lang <language>]: dump the tree-sitter AST for a file
+    // --captures <file> [--lang <language>]: list what the query captures
     // Use --lang to override language detection, or use "-" for stdin.
-    if args.contains(&"--ast".to_string()) {
+    for (flag, dump) in [
+        ("--ast", dump_ast as fn(&str, Option<&str>)),
+        ("--captures", dump_captures),
+    ] {
+        if !args.iter().any(|arg| arg == flag) {
+            continue;
+        }
         let lang_override = args
             .windows(2)
             .find(|w| w[0] == "--lang")
@@ -32,8 +45,8 @@ fn main() {
             break;
         }
         match file_arg {
-            Some(path) => dump_ast(path, lang_override),
-            None => eprintln!("Usage: codebook --ast <file> [--lang <language>]"),
+            Some(path) => dump(path, lang_override),
+            None => eprintln!("Usage: codebook {flag} <file> [--lang <language>]"),
         }
         return;
     }
@@ -83,7 +96,7 @@ fn main() {
     let results = match processor.spell_check_file(path.to_str().unwrap()) {
         Ok(results) => results,
         Err(e) => {
-            eprintln!("Can't read file {path:?}: {e}");
+            eprintln!("{e}");
             return;
         }
     };
@@ -140,19 +153,12 @@ fn fetch_fixtures(dir: &Path) {
     }
 }
 
-fn dump_ast(path: &str, lang_override: Option<&str>) {
+/// The language to parse `path` as, and its source ("-" reads stdin).
+fn read_source(path: &str, lang_override: Option<&str>) -> (LanguageType, String) {
     let lang_type = match lang_override {
         Some(lang) => lang.parse().unwrap_or(LanguageType::Text),
         None => get_language_name_from_filename(path),
     };
-    let setting = match get_language_setting(lang_type) {
-        Some(s) => s,
-        None => {
-            eprintln!("No tree-sitter grammar for {path} (detected as {lang_type:?})");
-            return;
-        }
-    };
-    let ts_lang = setting.language().unwrap();
     let source = if path == "-" {
         use std::io::Read;
         let mut buf = String::new();
@@ -161,6 +167,19 @@ fn dump_ast(path: &str, lang_override: Option<&str>) {
     } else {
         std::fs::read_to_string(path).unwrap()
     };
+    (lang_type, source)
+}
+
+fn dump_ast(path: &str, lang_override: Option<&str>) {
+    let (lang_type, source) = read_source(path, lang_override);
+    let setting = match get_language_setting(lang_type) {
+        Some(s) => s,
+        None => {
+            eprintln!("No tree-sitter grammar for {path} (detected as {lang_type:?})");
+            return;
+        }
+    };
+    let ts_lang = setting.language().unwrap();
     let mut parser = Parser::new();
     parser.set_language(&ts_lang).unwrap();
     let tree = parser.parse(&source, None).unwrap();
@@ -170,6 +189,23 @@ fn dump_ast(path: &str, lang_override: Option<&str>) {
     print_node(tree.root_node(), &source, 0);
 }
 
+/// Prints each capture of the language's query with its byte span, like
+/// `dump_ast` does for nodes.
+fn dump_captures(path: &str, lang_override: Option<&str>) {
+    let (lang_type, source) = read_source(path, lang_override);
+    if get_language_setting(lang_type).is_none() {
+        eprintln!("No tree-sitter grammar for {path} (detected as {lang_type:?})");
+        return;
+    }
+
+    println!("Language: {lang_type:?}");
+    println!("---");
+    for (name, range, text) in debug_captures(&source, lang_type) {
+        let text: String = text.chars().take(60).collect();
+        println!("@{name} [{}-{}] {text:?}", range.start_byte, range.end_byte);
+    }
+}
+
 fn print_node(node: tree_sitter::Node, source: &str, indent: usize) {
     let text: String = node
         .utf8_text(source.as_bytes())
@@ -205,6 +241,9 @@ fn run_benchmark(processor: &Codebook) {
 
     // Run JavaScript benchmark
     benchmark_javascript_file(processor, ITERATIONS);
+
+    // Compare single-threaded and parallel dictionary checking
+    benchmark_parallel_check(processor, ITERATIONS);
 }
 
 #[cfg(not(target_os = "windows"))]
@@ -216,7 +255,7 @@ fn benchmark_text_file(processor: &Codebook, iterations: usize) {
         .build()
         .unwrap();
 
-    let sample_text = include_str!("../tests/examples/wulf.txt");
+    let sample_text = samples::LARGE_TEXT;
 
     let duration = run_benchmark_iterations(
         processor,
@@ -246,7 +285,7 @@ fn benchmark_javascript_file(processor: &Codebook, iterations: usize) {
         .build()
         .unwrap();
 
-    let js_code = include_str!("../tests/examples/example.js");
+    let js_code = samples::sample(LanguageType::Javascript);
 
     let duration = run_benchmark_iterations(
         processor,
@@ -267,6 +306,49 @@ fn benchmark_javascript_file(processor: &Codebook, iterations: usize) {
     }
 }
 
+/// Time the text benchmark with a one-thread rayon pool against the default
+/// pool, showing what parallel dictionary checking buys on a large file.
+#[cfg(not(target_os = "windows"))]
+fn benchmark_parallel_check(processor: &Codebook, iterations: usize) {
+    println!("\nRunning parallel dictionary check benchmark...");
+
+    let sample_text = samples::LARGE_TEXT;
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    let sequential = single_thread.install(|| {
+        run_benchmark_iterations(
+            processor,
+            sample_text,
+            LanguageType::Text,
+            iterations,
+            "Sequential",
+            Some(262),
+        )
+    });
+    let parallel = run_benchmark_iterations(
+        processor,
+        sample_text,
+        LanguageType::Text,
+        iterations,
+        "Parallel",
+        Some(262),
+    );
+
+    print_benchmark_results("Text file, 1 thread", iterations, sequential);
+    print_benchmark_results(
+        &format!("Text file, {} threads", rayon::current_num_threads()),
+        iterations,
+        parallel,
+    );
+    println!(
+        "Speedup: {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
+
 #[cfg(not(target_os = "windows"))]
 fn run_benchmark_iterations(
     processor: &Codebook,
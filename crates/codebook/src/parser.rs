@@ -1,7 +1,9 @@
-use crate::checker::WordCandidate;
+use crate::checker::{WordCandidate, WordKind};
+use crate::directives;
+use crate::gettext;
 use crate::queries::{LANGUAGE_SETTINGS, LanguageSetting, LanguageType, get_language_setting};
-use crate::splitter;
-use log::{debug, error};
+use crate::splitter::{self, SplitOptions, SplitRef};
+use log::{debug, error, warn};
 use regex::Regex;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
@@ -20,10 +22,10 @@ use unicode_segmentation::UnicodeSegmentation;
 static PARSER_CACHE: LazyLock<Mutex<HashMap<LanguageType, Option<Parser>>>> =
     LazyLock::new(|| Mutex::new(HashMap::new()));
 
-/// One `(#not-has-ancestor? @capture "kind" ...)` rule, pre-parsed so the
-/// hot path does no string scanning. Drops a match's capture when any
-/// ancestor of the captured node has a kind in `kinds`.
-struct NotHasAncestorRule {
+/// One `(#not-has-ancestor? @capture "kind" ...)` or
+/// `(#skip-children? @capture "kind" ...)` rule, pre-parsed so the hot path
+/// does no string scanning.
+struct KindRule {
     capture_index: u32,
     kinds: Vec<String>,
 }
@@ -34,13 +36,18 @@ struct CompiledQuery {
     capture_names: Vec<String>,
     /// Indexed by `pattern_index`. Empty inner vec = no filtering for that
     /// pattern, which is the common case across all .scm files.
-    not_has_ancestor: Vec<Vec<NotHasAncestorRule>>,
+    not_has_ancestor: Vec<Vec<KindRule>>,
+    /// Indexed by `pattern_index`, like `not_has_ancestor`.
+    skip_children: Vec<Vec<KindRule>>,
+    /// Indexed by `pattern_index`: captures marked `(#skip-jsdoc? @capture)`.
+    skip_jsdoc: Vec<Vec<u32>>,
 }
 
 /// All tree-sitter queries compiled eagerly at startup. Since queries come
-/// from static `include_str!` data, they never change at runtime. Compiling
-/// them once here means bad queries panic immediately rather than hiding
-/// until a user opens that file type.
+/// from static `include_str!` data, they never change at runtime, and
+/// `test_all_queries_are_valid` rejects bad ones in CI. A query that still
+/// fails to compile (e.g. against a mismatched grammar) is logged and its
+/// language checked as plain text, rather than panicking every check.
 static COMPILED_QUERIES: LazyLock<HashMap<LanguageType, CompiledQuery>> = LazyLock::new(|| {
     let mut map = HashMap::new();
     for setting in LANGUAGE_SETTINGS {
@@ -50,15 +57,29 @@ static COMPILED_QUERIES: LazyLock<HashMap<LanguageType, CompiledQuery>> = LazyLo
         if setting.query.is_empty() {
             continue;
         }
-        let query = Query::new(&lang, setting.query)
-            .unwrap_or_else(|e| panic!("Failed to compile query for {:?}: {e}", setting.type_));
+        let query = match Query::new(&lang, setting.query) {
+            Ok(query) => query,
+            Err(e) => {
+                error!(
+                    "Failed to compile query for {:?}, checking it as plain text: {e}",
+                    setting.type_
+                );
+                continue;
+            }
+        };
         let capture_names = query
             .capture_names()
             .iter()
             .map(|s| s.to_string())
             .collect();
         let not_has_ancestor = (0..query.pattern_count())
-            .map(|i| parse_not_has_ancestor(&query, i, setting.type_))
+            .map(|i| parse_kind_rules(&query, i, setting.type_, "not-has-ancestor?"))
+            .collect();
+        let skip_children = (0..query.pattern_count())
+            .map(|i| parse_kind_rules(&query, i, setting.type_, "skip-children?"))
+            .collect();
+        let skip_jsdoc = (0..query.pattern_count())
+            .map(|i| parse_capture_flags(&query, i, setting.type_, "skip-jsdoc?"))
             .collect();
         map.insert(
             setting.type_,
@@ -66,31 +87,34 @@ static COMPILED_QUERIES: LazyLock<HashMap<LanguageType, CompiledQuery>> = LazyLo
                 query,
                 capture_names,
                 not_has_ancestor,
+                skip_children,
+                skip_jsdoc,
             },
         );
     }
     map
 });
 
-/// Extract `(#not-has-ancestor? @cap "kind" ...)` rules from a pattern's
-/// general predicates. Other custom predicates pass through unchanged.
-/// Malformed predicates panic at startup so .scm authors get an immediate
-/// error rather than a silent no-op at runtime.
-fn parse_not_has_ancestor(
+/// Extract `(#{operator} @cap "kind" ...)` rules from a pattern's general
+/// predicates. Other custom predicates pass through unchanged. Malformed
+/// predicates panic at startup so .scm authors get an immediate error rather
+/// than a silent no-op at runtime.
+fn parse_kind_rules(
     query: &Query,
     pattern_index: usize,
     language: LanguageType,
-) -> Vec<NotHasAncestorRule> {
+    operator: &str,
+) -> Vec<KindRule> {
     let mut rules = Vec::new();
     for pred in query.general_predicates(pattern_index) {
-        if &*pred.operator != "not-has-ancestor?" {
+        if &*pred.operator != operator {
             continue;
         }
         let mut args = pred.args.iter();
         let capture_index = match args.next() {
             Some(QueryPredicateArg::Capture(i)) => *i,
             _ => panic!(
-                "{:?}: #not-has-ancestor? must take a capture as its first argument",
+                "{:?}: #{operator} must take a capture as its first argument",
                 language
             ),
         };
@@ -98,17 +122,17 @@ fn parse_not_has_ancestor(
             .map(|a| match a {
                 QueryPredicateArg::String(s) => s.to_string(),
                 QueryPredicateArg::Capture(_) => panic!(
-                    "{:?}: #not-has-ancestor? takes string node kinds after the capture",
+                    "{:?}: #{operator} takes string node kinds after the capture",
                     language
                 ),
             })
             .collect();
         assert!(
             !kinds.is_empty(),
-            "{:?}: #not-has-ancestor? needs at least one node kind",
+            "{:?}: #{operator} needs at least one node kind",
             language
         );
-        rules.push(NotHasAncestorRule {
+        rules.push(KindRule {
             capture_index,
             kinds,
         });
@@ -116,6 +140,72 @@ fn parse_not_has_ancestor(
     rules
 }
 
+/// The captures named by a pattern's `(#{operator} @cap)` predicates, which
+/// take no other arguments. Malformed ones panic, as in `parse_kind_rules`.
+fn parse_capture_flags(
+    query: &Query,
+    pattern_index: usize,
+    language: LanguageType,
+    operator: &str,
+) -> Vec<u32> {
+    query
+        .general_predicates(pattern_index)
+        .iter()
+        .filter(|pred| &*pred.operator == operator)
+        .map(|pred| match &*pred.args {
+            [QueryPredicateArg::Capture(i)] => *i,
+            _ => panic!("{language:?}: #{operator} takes exactly one capture"),
+        })
+        .collect()
+}
+
+/// Byte spans of a JSDoc comment (`/** ... */`) that aren't prose: block
+/// tags and inline tags (`@param`, `@link`) and `{...}` type expressions,
+/// including nested braces. Empty for any other comment.
+fn jsdoc_spans(text: &str) -> Vec<(usize, usize)> {
+    if !text.starts_with("/**") {
+        return Vec::new();
+    }
+    let mut spans = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    let mut prev = ' ';
+    while let Some((start, c)) = chars.next() {
+        if c == '{' {
+            let mut depth = 1;
+            let mut end = text.len();
+            for (i, c) in chars.by_ref() {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                if depth == 0 {
+                    end = i + 1;
+                    break;
+                }
+            }
+            spans.push((start, end));
+            prev = '}';
+            continue;
+        }
+        // Only an `@` that starts a token is a tag, not the one in an email
+        if c == '@' && (prev.is_whitespace() || prev == '*') {
+            let mut end = start + 1;
+            while let Some(&(i, c)) = chars.peek()
+                && c.is_alphanumeric()
+            {
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            spans.push((start, end));
+            prev = '@';
+            continue;
+        }
+        prev = c;
+    }
+    spans
+}
+
 /// Returns true if any ancestor of `node` has a kind in `kinds`.
 fn has_ancestor_kind(node: Node, kinds: &[String]) -> bool {
     let mut cur = node.parent();
@@ -137,6 +227,19 @@ pub struct TextRange {
     pub end_byte: usize,
 }
 
+impl TextRange {
+    /// The line of `source` containing the start of this range, without its
+    /// line terminator, so callers can render a preview of a finding.
+    pub fn line_text<'a>(&self, source: &'a str) -> &'a str {
+        let start = self.start_byte.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        source[line_start..line_end].trim_end_matches('\r')
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct SkipRange {
     start_byte: usize,
@@ -184,15 +287,47 @@ fn merge_overlapping_ranges(ranges: Vec<SkipRange>) -> Vec<SkipRange> {
     merged
 }
 
+/// Why a word is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IssueKind {
+    /// No dictionary knows the word, or it is in `flag_words`
+    #[default]
+    Misspelling,
+    /// The word mixes look-alike letters from different scripts (see
+    /// `flag_confusables`)
+    Confusable,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct WordLocation {
     pub word: String,
     pub locations: Vec<TextRange>,
+    /// The query capture name (e.g. `comment.line`, `identifier.function`)
+    /// the word's first occurrence came from, or `None` for plain text.
+    pub tag: Option<String>,
+    pub kind: IssueKind,
 }
 
 impl WordLocation {
     pub fn new(word: String, locations: Vec<TextRange>) -> Self {
-        Self { word, locations }
+        Self {
+            word,
+            locations,
+            tag: None,
+            kind: IssueKind::Misspelling,
+        }
+    }
+
+    /// Set why the word is reported.
+    pub fn with_kind(mut self, kind: IssueKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Set the capture tag the word was found under.
+    pub fn with_tag(mut self, tag: Option<&str>) -> Self {
+        self.tag = tag.map(str::to_string);
+        self
     }
 }
 
@@ -203,18 +338,53 @@ impl WordLocation {
 /// Extract all candidate words from a document, recursively following
 /// `@injection.*` captures in .scm query files to handle multi-language files.
 ///
+/// `tag_filter` is asked about each text capture with the language whose
+/// query produced it, which differs from `language` inside injections.
+///
 /// Returns the candidates and the set of all languages encountered (for
 /// dictionary loading).
 pub fn extract_all_words<'a>(
     document_text: &'a str,
     language: LanguageType,
-    tag_filter: &dyn Fn(&str) -> bool,
+    tag_filter: &dyn Fn(LanguageType, &str) -> bool,
     skip_patterns: &[Regex],
+) -> (Vec<WordCandidate<'a>>, HashSet<LanguageType>) {
+    extract_all_words_with(
+        document_text,
+        language,
+        tag_filter,
+        skip_patterns,
+        &[],
+        SplitOptions::default(),
+    )
+}
+
+/// `extract_all_words`, splitting tokens into words as `split` says. Each
+/// match of `keep_patterns` (`C\+\+`, `\.NET`) is one word, checked whole
+/// instead of being split or dropped at its punctuation.
+pub fn extract_all_words_with<'a>(
+    document_text: &'a str,
+    language: LanguageType,
+    tag_filter: &dyn Fn(LanguageType, &str) -> bool,
+    skip_patterns: &[Regex],
+    keep_patterns: &[Regex],
+    split: SplitOptions,
 ) -> (Vec<WordCandidate<'a>>, HashSet<LanguageType>) {
     let skip_ranges = find_skip_ranges(document_text, skip_patterns);
+    // Kept words are found the same way as skipped text, minus any that a
+    // skip pattern covers
+    let mut keep_ranges = find_skip_ranges(document_text, keep_patterns);
+    keep_ranges.retain(|r| !is_within_skip_range(r.start_byte, r.end_byte, &skip_ranges));
+    let ctx = ExtractContext {
+        tag_filter,
+        skip_ranges: &skip_ranges,
+        keep_ranges: &keep_ranges,
+        split,
+    };
     let mut result = ExtractionResult {
         candidates: Vec::new(),
         languages: HashSet::from([language]),
+        comments: Vec::new(),
     };
 
     extract_recursive(
@@ -222,18 +392,35 @@ pub fn extract_all_words<'a>(
         0,
         document_text.len(),
         language,
-        tag_filter,
-        &skip_ranges,
+        &ctx,
         &mut result,
     );
 
+    // `codebook:ignore` and friends drop every word on their lines
+    let suppressed = directives::suppressed_ranges(document_text, &result.comments);
+    if !suppressed.is_empty() {
+        result
+            .candidates
+            .retain(|c| !suppressed.iter().any(|r| r.contains(&c.start_byte)));
+    }
+
     (result.candidates, result.languages)
 }
 
+/// Settings shared by every level of a recursive extraction.
+struct ExtractContext<'f> {
+    tag_filter: &'f dyn Fn(LanguageType, &str) -> bool,
+    skip_ranges: &'f [SkipRange],
+    keep_ranges: &'f [SkipRange],
+    split: SplitOptions,
+}
+
 /// Accumulated output from recursive word extraction.
 struct ExtractionResult<'a> {
     candidates: Vec<WordCandidate<'a>>,
     languages: HashSet<LanguageType>,
+    /// Byte ranges of every comment capture, searched for directives
+    comments: Vec<(usize, usize)>,
 }
 
 /// Create a parser for a language's grammar, or None when the grammar can't
@@ -253,6 +440,84 @@ fn new_parser(setting: &LanguageSetting) -> Option<Parser> {
     }
 }
 
+/// Every capture `language`'s query makes in `text`, in match order: the
+/// capture name, its byte span and the captured text. Injections aren't
+/// followed and codebook's own predicates (`#not-has-ancestor?`,
+/// `#skip-children?`, `#skip-jsdoc?`) aren't applied; tree-sitter's standard
+/// ones are.
+/// Empty when the language has no grammar or query.
+pub(crate) fn query_captures(
+    text: &str,
+    language: LanguageType,
+) -> Vec<(&'static str, TextRange, &str)> {
+    let (Some(setting), Some(compiled)) = (
+        get_language_setting(language),
+        COMPILED_QUERIES.get(&language),
+    ) else {
+        return Vec::new();
+    };
+    // Parse under the global lock, as extract_recursive does
+    let tree = {
+        let mut cache = PARSER_CACHE.lock().unwrap();
+        let parser = match cache.entry(language) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(new_parser(setting)),
+        };
+        parser.as_mut().and_then(|parser| parser.parse(text, None))
+    };
+    let Some(tree) = tree else {
+        return Vec::new();
+    };
+
+    let mut captures = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&compiled.query, tree.root_node(), text.as_bytes());
+    while let Some(match_) = matches.next() {
+        for capture in match_.captures {
+            let node = capture.node;
+            captures.push((
+                compiled.capture_names[capture.index as usize].as_str(),
+                TextRange {
+                    start_byte: node.start_byte(),
+                    end_byte: node.end_byte(),
+                },
+                &text[node.start_byte()..node.end_byte()],
+            ));
+        }
+    }
+    captures
+}
+
+/// Extract the words of a gettext catalog's `msgid`/`msgstr` strings, which
+/// starts at `start_byte` of the document.
+fn extract_gettext<'a>(
+    catalog_text: &'a str,
+    start_byte: usize,
+    ctx: &ExtractContext,
+    result: &mut ExtractionResult<'a>,
+) {
+    let catalog = gettext::parse(catalog_text);
+    result.comments.extend(
+        catalog
+            .comments
+            .into_iter()
+            .map(|(start, end)| (start_byte + start, start_byte + end)),
+    );
+    for (start, end, tag) in catalog.strings {
+        if !(ctx.tag_filter)(LanguageType::Gettext, tag) {
+            continue;
+        }
+        extract_words_from_text(
+            &catalog_text[start..end],
+            start_byte + start,
+            WordKind::from_tag(tag),
+            Some(tag),
+            ctx,
+            &mut result.candidates,
+        );
+    }
+}
+
 /// Recursively extract words from a byte range of the document.
 ///
 /// For languages with a tree-sitter grammar and .scm query:
@@ -267,21 +532,33 @@ fn extract_recursive<'a>(
     start_byte: usize,
     end_byte: usize,
     language: LanguageType,
-    tag_filter: &dyn Fn(&str) -> bool,
-    skip_ranges: &[SkipRange],
+    ctx: &ExtractContext,
     result: &mut ExtractionResult<'a>,
 ) {
-    let language_setting = match get_language_setting(language) {
-        Some(s) => s,
-        None => {
-            // No grammar (e.g. Text): word-split the whole range
-            let text = &document_text[start_byte..end_byte];
-            extract_words_from_text(text, start_byte, skip_ranges, &mut result.candidates);
-            return;
-        }
+    let region_text = &document_text[start_byte..end_byte];
+    // Word-split the whole region, for languages without a grammar (e.g.
+    // Text) and as the fallback when parsing fails, so the user still gets
+    // results rather than none.
+    let extract_as_text = |result: &mut ExtractionResult<'a>| {
+        extract_words_from_text(
+            region_text,
+            start_byte,
+            WordKind::Text,
+            None,
+            ctx,
+            &mut result.candidates,
+        );
     };
 
-    let region_text = &document_text[start_byte..end_byte];
+    if language == LanguageType::Gettext {
+        extract_gettext(region_text, start_byte, ctx, result);
+        return;
+    }
+
+    let Some(language_setting) = get_language_setting(language) else {
+        extract_as_text(result);
+        return;
+    };
 
     // Parse under global lock. This block must not panic: a panic while
     // holding PARSER_CACHE poisons the mutex and fails every spell check
@@ -294,23 +571,34 @@ fn extract_recursive<'a>(
         };
         parser
             .as_mut()
-            .and_then(|parser| parser.parse(region_text, None))
+            .map(|parser| parser.parse(region_text, None))
     };
 
-    let Some(tree) = tree else {
-        // Grammar unavailable or parse failed: degrade to word-splitting the
-        // region as plain text so it is still checked. debug-level because
-        // this runs per check; the grammar failure itself was already logged
-        // once by new_parser.
-        debug!("Parsing {language:?} unavailable; checking region as plain text");
-        extract_words_from_text(region_text, start_byte, skip_ranges, &mut result.candidates);
+    // Without a tree or a query, degrade to checking the region as plain
+    // text.
+    let tree = match tree {
+        Some(Some(tree)) => tree,
+        Some(None) => {
+            warn!("Failed to parse {language:?}; checking region as plain text");
+            extract_as_text(result);
+            return;
+        }
+        None => {
+            // debug-level because this runs per check; the grammar failure
+            // itself was already logged once by new_parser.
+            debug!("Parsing {language:?} unavailable; checking region as plain text");
+            extract_as_text(result);
+            return;
+        }
+    };
+    let Some(compiled) = COMPILED_QUERIES.get(&language) else {
+        // Already logged once when the queries were compiled
+        debug!("No query for {language:?}; checking region as plain text");
+        extract_as_text(result);
         return;
     };
 
     let root_node = tree.root_node();
-    let compiled = COMPILED_QUERIES
-        .get(&language)
-        .expect("Language has a LanguageSetting but no compiled query; this should not happen");
     let mut cursor = QueryCursor::new();
     let provider = region_text.as_bytes();
     let mut matches_query = cursor.matches(&compiled.query, root_node, provider);
@@ -320,6 +608,7 @@ fn extract_recursive<'a>(
         // empty for every pattern in every .scm except the rare ones that
         // declare a rule, so we pay one `is_empty` check on the hot path.
         let ancestor_rules = &compiled.not_has_ancestor[match_.pattern_index];
+        let skip_rules = &compiled.skip_children[match_.pattern_index];
 
         // First pass: look for dynamic injection pairs in this match
         let mut injection_content: Option<tree_sitter::Node> = None;
@@ -351,8 +640,7 @@ fn extract_recursive<'a>(
                             child_start,
                             child_end,
                             child_lang,
-                            tag_filter,
-                            skip_ranges,
+                            ctx,
                             result,
                         );
                     }
@@ -372,7 +660,8 @@ fn extract_recursive<'a>(
                 continue;
             }
 
-            if tag == "language" || tag == "injection.language" {
+            // `@_name` captures only feed predicates such as `#any-of?`
+            if tag == "language" || tag == "injection.language" || tag.starts_with('_') {
                 continue;
             }
 
@@ -391,26 +680,74 @@ fn extract_recursive<'a>(
                     && child_lang != LanguageType::Text
                 {
                     result.languages.insert(child_lang);
-                    extract_recursive(
-                        document_text,
-                        node_start,
-                        node_end,
-                        child_lang,
-                        tag_filter,
-                        skip_ranges,
-                        result,
-                    );
+                    extract_recursive(document_text, node_start, node_end, child_lang, ctx, result);
                 }
                 continue;
             }
 
+            // Directives work even where comments themselves aren't checked
+            if tag.starts_with("comment") {
+                result.comments.push((node_start, node_end));
+            }
+
             // Normal text capture: extract words if tag passes filter
-            if !tag_filter(tag) {
+            if !(ctx.tag_filter)(language, tag) {
                 continue;
             }
 
-            let node_text = node.utf8_text(provider).unwrap();
-            extract_words_from_text(node_text, node_start, skip_ranges, &mut result.candidates);
+            let Ok(node_text) = node.utf8_text(provider) else {
+                continue;
+            };
+            let kind = WordKind::from_tag(tag);
+
+            // `#skip-children?`: check only the text between the listed
+            // children. `#skip-jsdoc?`: only the prose of JSDoc comments.
+            let skipped: Option<Vec<(usize, usize)>> = if let Some(rule) = skip_rules
+                .iter()
+                .find(|rule| rule.capture_index == capture.index)
+            {
+                let mut child_cursor = node.walk();
+                Some(
+                    node.children(&mut child_cursor)
+                        .filter(|child| rule.kinds.iter().any(|k| k == child.kind()))
+                        .map(|child| {
+                            (
+                                child.start_byte() - node.start_byte(),
+                                child.end_byte() - node.start_byte(),
+                            )
+                        })
+                        .collect(),
+                )
+            } else if compiled.skip_jsdoc[match_.pattern_index].contains(&capture.index) {
+                Some(jsdoc_spans(node_text))
+            } else {
+                None
+            };
+            if let Some(skipped) = skipped {
+                let mut gap_start = 0;
+                let end = node_text.len();
+                for (skip_start, skip_end) in skipped.into_iter().chain([(end, end)]) {
+                    extract_words_from_text(
+                        &node_text[gap_start..skip_start],
+                        node_start + gap_start,
+                        kind,
+                        Some(tag),
+                        ctx,
+                        &mut result.candidates,
+                    );
+                    gap_start = skip_end;
+                }
+                continue;
+            }
+
+            extract_words_from_text(
+                node_text,
+                node_start,
+                kind,
+                Some(tag),
+                ctx,
+                &mut result.candidates,
+            );
         }
     }
 }
@@ -422,11 +759,46 @@ fn extract_recursive<'a>(
 fn extract_words_from_text<'a>(
     text: &'a str,
     base_offset: usize,
-    skip_ranges: &[SkipRange],
+    kind: WordKind,
+    tag: Option<&'a str>,
+    ctx: &ExtractContext,
     candidates: &mut Vec<WordCandidate<'a>>,
 ) {
+    let skip_ranges = ctx.skip_ranges;
+    let text_end = base_offset + text.len();
+    let keep_ranges: Vec<SkipRange> = ctx
+        .keep_ranges
+        .iter()
+        .filter(|r| r.start_byte >= base_offset && r.end_byte <= text_end)
+        .copied()
+        .collect();
+    let mut next_keep = 0;
+    let mut push_kept = |upto: usize, candidates: &mut Vec<WordCandidate<'a>>| {
+        while let Some(range) = keep_ranges.get(next_keep)
+            && range.start_byte < upto
+        {
+            candidates.push(WordCandidate {
+                word: &text[range.start_byte - base_offset..range.end_byte - base_offset],
+                start_byte: range.start_byte,
+                end_byte: range.end_byte,
+                kind,
+                tag,
+                compound: None,
+            });
+            next_keep += 1;
+        }
+    };
     let mut split_buf = Vec::new();
-    for (token_offset, token) in text.split_word_bound_indices() {
+    let tokens: Vec<(usize, &str)> = text.split_word_bound_indices().collect();
+    let compounds = if kind == WordKind::Text && ctx.split.hyphenated_compounds {
+        hyphenated_compounds(text, &tokens)
+    } else {
+        Vec::new()
+    };
+    for (i, &(token_offset, token)) in tokens.iter().enumerate() {
+        let compound = compounds.get(i).copied().flatten();
+        // Kept words go in before the first token they start in, in order
+        push_kept(base_offset + token_offset + token.len(), candidates);
         // UAX #29 rules WB13a/WB13b keep tokens like "1000\u{202F}kWh" together
         // because NBSP/NNBSP are ExtendNumLet. The splitter assumes whitespace-
         // free input, so split each segmenter token on Unicode whitespace here.
@@ -444,23 +816,61 @@ fn extract_words_from_text<'a>(
                 continue;
             }
             splitter::split_into(word, &mut split_buf);
+            if kind == WordKind::Text && !ctx.split.camel_case {
+                splitter::join_case_splits(word, &mut split_buf);
+            }
             for split_word in &split_buf {
-                if is_numeric(split_word.word) {
+                let Some(checked) = checkable_part(split_word) else {
                     continue;
-                }
+                };
                 let word_start = global_offset + split_word.start_byte;
-                let word_end = word_start + split_word.word.len();
-                if is_within_skip_range(word_start, word_end, skip_ranges) {
+                let word_end = word_start + checked.len();
+                if is_within_skip_range(word_start, word_end, skip_ranges)
+                    || keep_ranges
+                        .iter()
+                        .any(|r| word_start < r.end_byte && r.start_byte < word_end)
+                {
                     continue;
                 }
                 candidates.push(WordCandidate {
-                    word: split_word.word,
+                    word: checked,
                     start_byte: word_start,
                     end_byte: word_end,
+                    kind,
+                    tag,
+                    compound,
                 });
             }
         }
     }
+    push_kept(usize::MAX, candidates);
+}
+
+/// For each segmenter token, the hyphenated compound it belongs to: `e`,
+/// `-` and `mail` all map to `e-mail`. Words must be joined by a single
+/// hyphen with nothing around it.
+fn hyphenated_compounds<'a>(text: &'a str, tokens: &[(usize, &str)]) -> Vec<Option<&'a str>> {
+    let is_part = |token: &str| is_alphabetic(token) && !token.chars().any(char::is_whitespace);
+    let mut compounds = vec![None; tokens.len()];
+    let mut i = 0;
+    while i < tokens.len() {
+        let mut last = i;
+        if is_part(tokens[i].1) {
+            while last + 2 < tokens.len()
+                && tokens[last + 1].1 == "-"
+                && is_part(tokens[last + 2].1)
+            {
+                last += 2;
+            }
+        }
+        if last > i {
+            let (end_offset, end_token) = tokens[last];
+            let compound = &text[tokens[i].0..end_offset + end_token.len()];
+            compounds[i..=last].fill(Some(compound));
+        }
+        i = last + 1;
+    }
+    compounds
 }
 
 /// Iterate maximal non-whitespace runs in `s` with their byte offset.
@@ -484,8 +894,13 @@ fn split_on_whitespace_indices(s: &str) -> impl Iterator<Item = (usize, &str)> {
     })
 }
 
-fn is_numeric(s: &str) -> bool {
-    s.chars().any(|c| c.is_numeric())
+/// The part of a split word that gets spell-checked, or None to skip it.
+/// Numbers are skipped, and so are the short letter runs the splitter keeps
+/// with their digits (`utf8`, `sha256`), even with letters after them as in
+/// `utf8string`. Longer runs are split off their digits, so the letters of
+/// `wrodd1def` are still checked.
+fn checkable_part<'a>(word: &SplitRef<'a>) -> Option<&'a str> {
+    (!word.word.chars().any(char::is_numeric)).then_some(word.word)
 }
 
 fn is_alphabetic(c: &str) -> bool {
@@ -531,7 +946,7 @@ mod tests {
     #[test]
     fn test_extract_words_plain_text() {
         let text = "HelloWorld calc_wrld";
-        let (words, langs) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, langs) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(word_strings.contains(&"Hello"));
         assert!(word_strings.contains(&"World"));
@@ -541,10 +956,47 @@ mod tests {
         assert!(langs.contains(&LanguageType::Text));
     }
 
+    #[test]
+    fn test_parse_failure_checks_region_as_text() {
+        // A parser without a grammar fails every parse, like one that gives
+        // up on its input; no grammar at all is the unavailable case. Odin
+        // isn't parsed by any other test here, so swapping its cache entry
+        // doesn't race them.
+        let text = "// a commment\nx := speling\n";
+        for parser in [Some(Parser::new()), None] {
+            PARSER_CACHE
+                .lock()
+                .unwrap()
+                .insert(LanguageType::Odin, parser);
+            let (words, _) = extract_all_words(text, LanguageType::Odin, &|_, _| true, &[]);
+            PARSER_CACHE.lock().unwrap().remove(&LanguageType::Odin);
+            let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
+            assert_eq!(word_strings, ["a", "commment", "x", "speling"]);
+            assert!(
+                words
+                    .iter()
+                    .all(|w| w.kind == WordKind::Text && w.tag.is_none())
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_words_letters_mixed_with_digits() {
+        let text = "wrodd1def v2api utf8 sha256 utf8string sha256sum config2 1024";
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
+        let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
+        assert_eq!(
+            word_strings,
+            ["wrodd", "def", "api", "string", "sum", "config"]
+        );
+        let wrodd = &words[0];
+        assert_eq!(&text[wrodd.start_byte..wrodd.end_byte], "wrodd");
+    }
+
     #[test]
     fn test_extract_words_contraction() {
         let text = "I'm a contraction, wouldn't you agree'?";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         let expected = ["I'm", "a", "contraction", "wouldn't", "you", "agree"];
         for e in &expected {
@@ -552,10 +1004,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_extract_words_trailing_apostrophe() {
+        // Trailing apostrophes are dropped the same way whether they're
+        // straight or curly, while inner ones stay part of the word
+        for text in ["y'all agree' it's o'clock'", "y’all agree’ it’s o’clock’"] {
+            let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
+            let word_strings: Vec<String> =
+                words.iter().map(|w| w.word.replace('’', "'")).collect();
+            assert_eq!(
+                word_strings,
+                ["y'all", "agree", "it's", "o'clock"],
+                "{text}"
+            );
+        }
+    }
+
     #[test]
     fn test_extract_words_code() {
         let text = "// a comment\nfn main() {}";
-        let (words, langs) = extract_all_words(text, LanguageType::Rust, &|_| true, &[]);
+        let (words, langs) = extract_all_words(text, LanguageType::Rust, &|_, _| true, &[]);
         assert!(!words.is_empty());
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(
@@ -565,13 +1033,68 @@ mod tests {
         assert!(langs.contains(&LanguageType::Rust));
     }
 
+    #[test]
+    fn test_hyphenated_compounds() {
+        let text = "an e-mail, a well-known-fact and a - b";
+        let tokens: Vec<(usize, &str)> = text.split_word_bound_indices().collect();
+        let compounds = hyphenated_compounds(text, &tokens);
+        let found: Vec<(&str, &str)> = tokens
+            .iter()
+            .zip(&compounds)
+            .filter_map(|(&(_, token), compound)| Some((token, (*compound)?)))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("e", "e-mail"),
+                ("-", "e-mail"),
+                ("mail", "e-mail"),
+                ("well", "well-known-fact"),
+                ("-", "well-known-fact"),
+                ("known", "well-known-fact"),
+                ("-", "well-known-fact"),
+                ("fact", "well-known-fact"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_line_text() {
+        let source = "first line\r\nsecond wrod here\nlast";
+        let start = source.find("wrod").unwrap();
+        let range = TextRange {
+            start_byte: start,
+            end_byte: start + 4,
+        };
+        assert_eq!(range.line_text(source), "second wrod here");
+        let first = TextRange {
+            start_byte: 0,
+            end_byte: 5,
+        };
+        assert_eq!(first.line_text(source), "first line");
+        let last = TextRange {
+            start_byte: source.len() - 4,
+            end_byte: source.len(),
+        };
+        assert_eq!(last.line_text(source), "last");
+    }
+
+    #[test]
+    fn test_extract_words_kind() {
+        let text = "// a comment\nfn main() {}";
+        let (words, _) = extract_all_words(text, LanguageType::Rust, &|_, _| true, &[]);
+        let kind_of = |word: &str| words.iter().find(|w| w.word == word).map(|w| w.kind);
+        assert_eq!(kind_of("comment"), Some(WordKind::Text));
+        assert_eq!(kind_of("main"), Some(WordKind::Code));
+    }
+
     #[test]
     fn test_extract_words_tag_filter() {
         let text = "// comment\nlet x = \"string value\";";
         let (words, _) = extract_all_words(
             text,
             LanguageType::Rust,
-            &|tag| tag.starts_with("comment"),
+            &|_, tag| tag.starts_with("comment"),
             &[],
         );
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
@@ -580,11 +1103,24 @@ mod tests {
         assert!(!word_strings.contains(&"value"));
     }
 
+    #[test]
+    fn test_jsdoc_spans() {
+        let text = "/** See {@link A}. @param {{a: B}} x a@b.c */";
+        let spans: Vec<&str> = jsdoc_spans(text)
+            .into_iter()
+            .map(|(start, end)| &text[start..end])
+            .collect();
+        assert_eq!(spans, vec!["{@link A}", "@param", "{{a: B}}"]);
+        assert!(jsdoc_spans("/* @param {x} */").is_empty());
+        // An unclosed type expression runs to the end
+        assert_eq!(jsdoc_spans("/** {a"), vec![(4, 6)]);
+    }
+
     #[test]
     fn test_extract_words_with_skip_patterns() {
         let text = "check https://example.com this";
         let url_pattern = Regex::new(r"https?://[^\s]+").unwrap();
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[url_pattern]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[url_pattern]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(word_strings.contains(&"check"));
         assert!(word_strings.contains(&"this"));
@@ -592,6 +1128,34 @@ mod tests {
         assert!(!word_strings.contains(&"example"));
     }
 
+    #[test]
+    fn test_extract_words_with_keep_patterns() {
+        let text = "write C++ and .NET code, not C# yet";
+        let keep = [Regex::new(r"\bC\+\+|\.NET\b").unwrap()];
+        let (words, _) = extract_all_words_with(
+            text,
+            LanguageType::Text,
+            &|_, _| true,
+            &[],
+            &keep,
+            SplitOptions::default(),
+        );
+        let spans: Vec<(&str, usize)> = words.iter().map(|w| (w.word, w.start_byte)).collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("write", 0),
+                ("C++", 6),
+                ("and", 10),
+                (".NET", 14),
+                ("code", 19),
+                ("not", 25),
+                ("C", 29),
+                ("yet", 32)
+            ]
+        );
+    }
+
     #[test]
     fn test_extract_words_nbsp_inside_token() {
         // U+00A0 NO-BREAK SPACE between letters. The Unicode word segmenter
@@ -599,7 +1163,7 @@ mod tests {
         // we must split on the NBSP so the splitter sees clean words and
         // diagnostic spans line up with the original text.
         let text = "x foo\u{00A0}bar y";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(strings.contains(&"foo"), "got {strings:?}");
         assert!(strings.contains(&"bar"), "got {strings:?}");
@@ -616,7 +1180,7 @@ mod tests {
         // U+202F NARROW NO-BREAK SPACE between a number and its unit
         // (e.g. French "1000 kWh"). Must not panic and must yield the unit.
         let text = "use 1000\u{202F}kWh today";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(strings.contains(&"k"), "got {strings:?}");
         assert!(strings.contains(&"Wh"), "got {strings:?}");
@@ -631,7 +1195,7 @@ mod tests {
         // U+3000 IDEOGRAPHIC SPACE — Unicode White_Space, must act as a
         // boundary like ASCII space.
         let text = "hello\u{3000}world";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(strings.contains(&"hello"), "got {strings:?}");
         assert!(strings.contains(&"world"), "got {strings:?}");
@@ -640,7 +1204,7 @@ mod tests {
     #[test]
     fn test_extract_words_code_duplicates() {
         let text = "// wrld foo wrld";
-        let (words, _) = extract_all_words(text, LanguageType::Rust, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Rust, &|_, _| true, &[]);
         let wrld_words: Vec<_> = words.iter().filter(|w| w.word == "wrld").collect();
         assert_eq!(wrld_words.len(), 2, "Expected two occurrences of 'wrld'");
     }
@@ -649,7 +1213,7 @@ mod tests {
     fn test_markdown_injection_discovers_languages() {
         let text =
             "# Hello\n\nSome text.\n\n```python\ndef foo(): pass\n```\n\n```bash\necho hi\n```\n";
-        let (_, langs) = extract_all_words(text, LanguageType::Markdown, &|_| true, &[]);
+        let (_, langs) = extract_all_words(text, LanguageType::Markdown, &|_, _| true, &[]);
         assert!(langs.contains(&LanguageType::Markdown));
         assert!(langs.contains(&LanguageType::Python));
         assert!(langs.contains(&LanguageType::Bash));
@@ -658,7 +1222,7 @@ mod tests {
     #[test]
     fn test_markdown_injection_extracts_code_words() {
         let text = "# Hello\n\n```python\ndef some_functin(): pass\n```\n";
-        let (words, _) = extract_all_words(text, LanguageType::Markdown, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Markdown, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(word_strings.contains(&"functin"));
         assert!(word_strings.contains(&"Hello"));
@@ -667,7 +1231,7 @@ mod tests {
     #[test]
     fn test_markdown_unknown_language_skipped() {
         let text = "# Hello\n\n```unknownlang\nbadwwword\n```\n";
-        let (words, _) = extract_all_words(text, LanguageType::Markdown, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Markdown, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(!word_strings.contains(&"badwwword"));
     }
@@ -675,7 +1239,7 @@ mod tests {
     #[test]
     fn test_markdown_html_block_injection() {
         let text = "# Hello\n\n<div>\n  <p>A misspeled word</p>\n</div>\n\nMore text.\n";
-        let (words, langs) = extract_all_words(text, LanguageType::Markdown, &|_| true, &[]);
+        let (words, langs) = extract_all_words(text, LanguageType::Markdown, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(langs.contains(&LanguageType::HTML));
         assert!(word_strings.contains(&"misspeled"));
@@ -700,7 +1264,7 @@ mod tests {
     fn test_unicode_character_handling() {
         crate::logging::init_test_logging();
         let text = "©<div>badword</div>";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let bad_word = words.iter().find(|w| w.word == "badword");
         assert!(bad_word.is_some(), "Expected 'badword' to be found");
         let bw = bad_word.unwrap();
@@ -734,7 +1298,7 @@ mod tests {
     #[test]
     fn test_extract_words_skips_unsupported_scripts() {
         let text = "// 简体中文\n// 繁體中文\n// にほんご\n// ภาษาไทย\n// 한국어\n// hello world\n";
-        let (words, _) = extract_all_words(text, LanguageType::Rust, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Rust, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
 
         // None of the CJK/Thai/Hangul tokens should appear as candidates.
@@ -758,7 +1322,7 @@ mod tests {
         // Latin diacritics, Cyrillic, and Vietnamese should still be extracted
         // for downstream dictionary lookup.
         let text = "café München Tiếng Привет";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(word_strings.contains(&"café"), "got {word_strings:?}");
         assert!(word_strings.contains(&"München"), "got {word_strings:?}");
@@ -772,7 +1336,7 @@ mod tests {
         // and "nchen" — both garbage. We extract the whole token so the
         // German Hunspell dict can resolve it.
         let text = "Die Stadt München liegt in Bayern.";
-        let (words, _) = extract_all_words(text, LanguageType::Text, &|_| true, &[]);
+        let (words, _) = extract_all_words(text, LanguageType::Text, &|_, _| true, &[]);
         let word_strings: Vec<&str> = words.iter().map(|w| w.word).collect();
         assert!(word_strings.contains(&"München"), "got {word_strings:?}");
         assert!(!word_strings.contains(&"M"));
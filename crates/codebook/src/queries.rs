@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::sync::LazyLock;
 
 use tree_sitter::Language;
 
@@ -12,7 +13,9 @@ pub enum LanguageType {
     Dart,
     Elixir,
     Erlang,
+    Gettext,
     Go,
+    Graphql,
     HTML,
     Haskell,
     Java,
@@ -58,7 +61,35 @@ impl FromStr for LanguageType {
     }
 }
 
+/// Every language in `LANGUAGE_SETTINGS`, in table order.
+static ALL_LANGUAGES: LazyLock<Vec<LanguageType>> =
+    LazyLock::new(|| LANGUAGE_SETTINGS.iter().map(|s| s.type_).collect());
+
 impl LanguageType {
+    /// Every language Codebook can parse. `Text`, the fallback for
+    /// unrecognized files, is not included.
+    pub fn all() -> &'static [LanguageType] {
+        &ALL_LANGUAGES
+    }
+
+    /// File extensions, and extensionless file names like `justfile`, that
+    /// are detected as this language.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        LANGUAGE_SETTINGS
+            .iter()
+            .find(|setting| setting.type_ == *self)
+            .map_or(&[], |setting| setting.extensions)
+    }
+
+    /// Language IDs (as sent by editors, e.g. `typescriptreact`) for this
+    /// language.
+    pub fn ids(&self) -> &'static [&'static str] {
+        LANGUAGE_SETTINGS
+            .iter()
+            .find(|setting| setting.type_ == *self)
+            .map_or(&[], |setting| setting.ids)
+    }
+
     pub fn dictionary_ids(&self) -> Vec<String> {
         for language in LANGUAGE_SETTINGS.iter() {
             if self == &language.type_ {
@@ -199,6 +230,13 @@ pub static LANGUAGE_SETTINGS: &[LanguageSetting] = &[
         query: include_str!("queries/go.scm"),
         extensions: &["go"],
     },
+    LanguageSetting {
+        type_: LanguageType::Graphql,
+        ids: &["graphql"],
+        dictionary_ids: &[],
+        query: include_str!("queries/graphql.scm"),
+        extensions: &["graphql", "gql"],
+    },
     LanguageSetting {
         type_: LanguageType::Swift,
         ids: &["swift"],
@@ -305,6 +343,14 @@ pub static LANGUAGE_SETTINGS: &[LanguageSetting] = &[
         query: include_str!("queries/typst.scm"),
         extensions: &["typ"],
     },
+    LanguageSetting {
+        type_: LanguageType::Gettext,
+        ids: &["po"],
+        dictionary_ids: &[],
+        // No grammar: parser.rs reads catalogs with the gettext module
+        query: "",
+        extensions: &["po", "pot"],
+    },
     LanguageSetting {
         type_: LanguageType::VHDL,
         ids: &["vhdl"],
@@ -335,7 +381,9 @@ impl LanguageSetting {
             LanguageType::Dart => Some(tree_sitter_dart::LANGUAGE.into()),
             LanguageType::Elixir => Some(tree_sitter_elixir::LANGUAGE.into()),
             LanguageType::Erlang => Some(tree_sitter_erlang::LANGUAGE.into()),
+            LanguageType::Gettext => None,
             LanguageType::Go => Some(tree_sitter_go::LANGUAGE.into()),
+            LanguageType::Graphql => Some(tree_sitter_graphql::LANGUAGE.into()),
             LanguageType::HTML => Some(tree_sitter_html::LANGUAGE.into()),
             LanguageType::Haskell => Some(tree_sitter_haskell::LANGUAGE.into()),
             LanguageType::Java => Some(tree_sitter_java::LANGUAGE.into()),
@@ -364,6 +412,16 @@ impl LanguageSetting {
     }
 }
 
+/// Run `language`'s query over `text` and return each raw capture as
+/// `(capture name, span, text)`, without extracting or checking words. For
+/// writing and debugging `.scm` queries; see queries/README.md.
+pub fn debug_captures(
+    text: &str,
+    language: LanguageType,
+) -> Vec<(&'static str, crate::parser::TextRange, &str)> {
+    crate::parser::query_captures(text, language)
+}
+
 pub fn get_language_setting(language_type: LanguageType) -> Option<&'static LanguageSetting> {
     LANGUAGE_SETTINGS
         .iter()
@@ -374,15 +432,49 @@ pub fn get_language_name_from_filename(filename: &str) -> LanguageType {
     // Strip any directory components so extensionless file names like
     // "justfile" still match when a full path is passed in.
     let basename = filename.rsplit(['/', '\\']).next().unwrap_or(filename);
-    let extension = basename.split('.').next_back().unwrap();
-    for setting in LANGUAGE_SETTINGS {
-        for ext in setting.extensions {
-            if ext == &extension {
-                return setting.type_;
-            }
-        }
+    // Try compound extensions longest first ("d.ts" before "ts") so a
+    // language can claim a more specific suffix. Names without a dot, like
+    // "justfile", are matched whole.
+    let candidates: Vec<&str> = if basename.contains('.') {
+        basename
+            .match_indices('.')
+            .map(|(i, _)| &basename[i + 1..])
+            .filter(|ext| !ext.is_empty())
+            .collect()
+    } else {
+        vec![basename]
+    };
+    let find = |matches: &dyn Fn(&str) -> bool| {
+        LanguageType::all()
+            .iter()
+            .copied()
+            .find(|language| language.extensions().iter().any(|ext| matches(ext)))
+    };
+    candidates
+        .into_iter()
+        .find_map(|candidate| {
+            // An exact match wins, so a language can claim a case variant
+            find(&|ext| ext == candidate)
+                .or_else(|| find(&|ext| ext.eq_ignore_ascii_case(candidate)))
+        })
+        .unwrap_or(LanguageType::Text)
+}
+
+/// The language named by a `#!` line at the start of `text`, such as
+/// `#!/bin/bash` or `#!/usr/bin/env -S python3 -u`. The interpreter is
+/// matched against language ids, so `sh`, `zsh`, `python3` and `node` work.
+pub fn get_language_from_shebang(text: &str) -> Option<LanguageType> {
+    let line = text.strip_prefix("#!")?.lines().next()?;
+    let mut words = line.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        // Skip env's flags and VAR=value assignments
+        interpreter = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+    }
+    match LanguageType::from_str(interpreter) {
+        Ok(LanguageType::Text) | Err(()) => None,
+        Ok(language) => Some(language),
     }
-    LanguageType::Text
 }
 
 #[cfg(test)]
@@ -390,11 +482,96 @@ mod tests {
     use super::*;
     use tree_sitter::Query;
 
+    #[test]
+    fn test_all_and_extensions() {
+        let all = LanguageType::all();
+        assert!(all.contains(&LanguageType::Rust));
+        assert!(!all.contains(&LanguageType::Text));
+        assert_eq!(all.len(), LANGUAGE_SETTINGS.len());
+        assert!(LanguageType::Rust.extensions().contains(&"rs"));
+        assert!(LanguageType::Text.extensions().is_empty());
+        // Every advertised extension round-trips through file name detection
+        for language in all {
+            for ext in language.extensions() {
+                assert_eq!(
+                    get_language_name_from_filename(&format!("file.{ext}")),
+                    *language,
+                    "extension {ext}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_debug_captures() {
+        let text = "// a note\nfn main() { let answer = \"yes\"; }\n";
+        let captures = debug_captures(text, LanguageType::Rust);
+        let find = |name: &str| captures.iter().find(|(n, _, _)| *n == name);
+
+        let (_, span, comment) = find("comment.line").unwrap();
+        assert!(comment.starts_with("// a note"));
+        assert_eq!(&text[span.start_byte..span.end_byte], *comment);
+        assert_eq!(find("identifier.function").unwrap().2, "main");
+        assert_eq!(find("identifier.variable").unwrap().2, "answer");
+        assert_eq!(find("string").unwrap().2, "yes");
+
+        assert!(debug_captures("some words", LanguageType::Text).is_empty());
+    }
+
+    #[test]
+    fn test_language_from_filename_case_and_compound() {
+        let cases = [
+            ("Main.RS", LanguageType::Rust),
+            ("src/Lib.Rs", LanguageType::Rust),
+            ("README.MD", LanguageType::Markdown),
+            ("JUSTFILE", LanguageType::Just),
+            ("types.d.ts", LanguageType::Typescript),
+            ("INDEX.D.TS", LanguageType::Typescript),
+            ("app.spec.ts", LanguageType::Typescript),
+            ("shapes.mli", LanguageType::OCaml),
+            ("backup.tar", LanguageType::Text),
+            ("backup.tar.gz", LanguageType::Text),
+            ("notes.", LanguageType::Text),
+            (".hidden", LanguageType::Text),
+        ];
+        for (filename, expected) in cases {
+            assert_eq!(
+                get_language_name_from_filename(filename),
+                expected,
+                "{filename}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_language_from_shebang() {
+        let cases = [
+            ("#!/bin/bash\necho hi", Some(LanguageType::Bash)),
+            ("#!/bin/sh", Some(LanguageType::Bash)),
+            ("#! /usr/bin/env zsh\n", Some(LanguageType::Bash)),
+            ("#!/usr/bin/env python3\n", Some(LanguageType::Python)),
+            (
+                "#!/usr/bin/env -S LANG=C node --harmony\n",
+                Some(LanguageType::Javascript),
+            ),
+            ("#!/usr/bin/perl\n", None),
+            ("#!/usr/bin/env\n", None),
+            ("echo hi\n#!/bin/bash", None),
+            ("", None),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(get_language_from_shebang(text), expected, "{text}");
+        }
+    }
+
     #[test]
     fn test_all_queries_are_valid() {
         for language_setting in LANGUAGE_SETTINGS {
-            // Skip testing Text since it doesn't have a language or query
-            if language_setting.type_ == LanguageType::Text {
+            // Skip testing the languages without a grammar or query
+            if matches!(
+                language_setting.type_,
+                LanguageType::Text | LanguageType::Gettext
+            ) {
                 continue;
             }
 
@@ -437,13 +614,17 @@ mod tests {
         "identifier.variable",
         "identifier.constant",
         "identifier.module",
+        "identifier.key",
         "language",
     ];
 
     #[test]
     fn test_all_capture_names_use_allowed_tags() {
         for language_setting in LANGUAGE_SETTINGS {
-            if language_setting.type_ == LanguageType::Text {
+            if matches!(
+                language_setting.type_,
+                LanguageType::Text | LanguageType::Gettext
+            ) {
                 continue;
             }
 
@@ -459,11 +640,13 @@ mod tests {
             });
 
             for name in query.capture_names() {
-                let is_allowed = ALLOWED_TAGS.contains(name) || name.starts_with("injection.");
+                let is_allowed = ALLOWED_TAGS.contains(name)
+                    || name.starts_with("injection.")
+                    || name.starts_with('_');
                 assert!(
                     is_allowed,
                     "Language {:?} uses unknown capture tag @{name}. \
-                     Allowed tags: {ALLOWED_TAGS:?} (plus injection.* tags)",
+                     Allowed tags: {ALLOWED_TAGS:?} (plus injection.* and _helper tags)",
                     language_setting.type_,
                 );
             }
@@ -2,6 +2,8 @@
 (string_content) @string
 (function_definition
     name: (word) @identifier.function)
-(heredoc_body) @string.heredoc
+; Heredoc text, minus the $VAR, ${...} and $(...) expansions inside it
+((heredoc_body) @string.heredoc
+  (#skip-children? @string.heredoc "simple_expansion" "expansion" "command_substitution"))
 (variable_assignment
     name: (variable_name) @identifier.variable)
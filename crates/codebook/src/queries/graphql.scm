@@ -0,0 +1,5 @@
+; Comments and descriptions are prose. Type, field and argument names are
+; usually dictated by the API, so they aren't checked.
+(comment) @comment.line
+(description
+    (string_value) @string)
@@ -1,5 +1,16 @@
 (text) @string
 (comment) @comment
-(quoted_attribute_value) @string
+; Only attributes that hold prose; class names, ids, URLs and the like are
+; machine-readable and skipped.
+(attribute
+  (attribute_name) @_name
+  [
+    (attribute_value) @string
+    (quoted_attribute_value (attribute_value) @string)
+  ]
+  (#any-of? @_name
+    "alt" "title" "placeholder" "label" "summary"
+    "aria-label" "aria-description" "aria-placeholder"
+    "aria-roledescription" "aria-valuetext"))
 (script_element (raw_text) @injection.typescript)
 (style_element (raw_text) @injection.css)
@@ -1,4 +1,5 @@
-(comment) @comment
+((comment) @comment
+  (#skip-jsdoc? @comment))
 (string_fragment) @string
 (variable_declarator
     name: (identifier) @identifier.variable)
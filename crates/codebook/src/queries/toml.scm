@@ -1,2 +1,6 @@
 (string) @string
 (comment) @comment
+
+; Keys are opt-in: only checked when include_tags names identifier.key
+(bare_key) @identifier.key
+(quoted_key) @identifier.key
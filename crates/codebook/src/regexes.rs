@@ -46,6 +46,17 @@ static DEFAULT_SKIP_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
         // from prose like and/or by shape alone; the quotes provide the
         // context that makes it a path.
         Regex::new(r#""[^"\s]*/[^"\s]*"|'[^'\s]*/[^'\s]*'"#).expect("Valid quoted path regex"),
+        // Shebang line (`#!/usr/bin/env python`). \A anchors it to the very
+        // start of the file, so only the first line can be one.
+        Regex::new(r"\A#![^\n]*").expect("Valid shebang regex"),
+        // Magic comments that are nothing but a declaration: Emacs-style
+        // `-*- coding: utf-8 -*-`, PEP 263 coding lines, and Ruby's
+        // `frozen_string_literal`-style pragmas. The names must be exact, so
+        // a typo such as `frozen_string_lteral` is still flagged.
+        Regex::new(
+            r"(?m)^[ \t]*#[ \t]*(?:-\*-[^\n]*-\*-|(?:vim:[^\n]*file)?(?:en)?coding[:=][ \t]*[-\w.]+[^\n]*|(?:frozen_string_literal|warn_indent|shareable_constant_value|encoding):[ \t]*\w+)[ \t]*$",
+        )
+        .expect("Valid magic comment regex"),
     ]
 });
 
@@ -142,6 +153,37 @@ mod tests {
         assert!(!quoted_pattern.is_match("and/or"));
     }
 
+    #[test]
+    fn test_shebang_pattern() {
+        let patterns = get_default_skip_patterns();
+        let shebang_pattern = &patterns[10];
+
+        let m = shebang_pattern
+            .find("#!/usr/bin/env python\nprint('hi')")
+            .unwrap();
+        assert_eq!(m.as_str(), "#!/usr/bin/env python");
+        // Only the first line of the file can be a shebang
+        assert!(!shebang_pattern.is_match("x = 1\n#!/usr/bin/env python"));
+        assert!(!shebang_pattern.is_match("# not a shebang"));
+    }
+
+    #[test]
+    fn test_magic_comment_pattern() {
+        let patterns = get_default_skip_patterns();
+        let magic_pattern = &patterns[11];
+
+        assert!(magic_pattern.is_match("# -*- coding: utf-8 -*-"));
+        assert!(magic_pattern.is_match("#!/usr/bin/env python\n# coding=latin-1"));
+        assert!(magic_pattern.is_match("# vim: set fileencoding=utf-8 :"));
+        assert!(magic_pattern.is_match("# frozen_string_literal: true"));
+        assert!(magic_pattern.is_match("  # encoding: ascii-8bit"));
+
+        // Ordinary comments mentioning the words are not declarations
+        assert!(!magic_pattern.is_match("# Decoding: handled below"));
+        assert!(!magic_pattern.is_match("# frozen_string_lteral: true"));
+        assert!(!magic_pattern.is_match("# the encoding: utf-8 is assumed here"));
+    }
+
     #[test]
     fn test_git_hash_pattern() {
         let patterns = get_default_skip_patterns();
@@ -0,0 +1,77 @@
+//! Cheap content checks for files not worth spell-checking.
+
+/// How much of the start of a file is searched for a NUL byte, as git does.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Files at least this long are checked for minification.
+const MINIFIED_MIN_LEN: usize = 4096;
+
+/// An average line longer than this marks a file as minified. Prose with
+/// unwrapped paragraphs stays well below it once blank lines are counted.
+const MINIFIED_AVG_LINE_LEN: usize = 1000;
+
+/// Whether `text` looks like binary data: a NUL byte near the start.
+pub(crate) fn looks_binary(text: &str) -> bool {
+    text.as_bytes()
+        .iter()
+        .take(BINARY_SNIFF_LEN)
+        .any(|&b| b == 0)
+}
+
+/// Whether `text` looks minified (bundled JavaScript, CSS and the like):
+/// long enough to matter, with very few line breaks.
+pub(crate) fn looks_minified(text: &str) -> bool {
+    if text.len() < MINIFIED_MIN_LEN {
+        return false;
+    }
+    let lines = text.bytes().filter(|&b| b == b'\n').count() + 1;
+    text.len() / lines > MINIFIED_AVG_LINE_LEN
+}
+
+/// Whether the first non-empty line of `text` contains `marker`, as in
+/// `// codebook: off` or `<!-- codebook: off -->`.
+pub(crate) fn has_disable_marker(text: &str, marker: &str) -> bool {
+    text.trim_start_matches('\u{feff}')
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .is_some_and(|line| line.contains(marker))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_binary() {
+        assert!(looks_binary("PK\u{3}\u{4}\0\0data"));
+        assert!(!looks_binary("plain text\nwith lines\n"));
+        // Only the start of the file is searched
+        let late_nul = format!("{}\0", "a".repeat(BINARY_SNIFF_LEN));
+        assert!(!looks_binary(&late_nul));
+    }
+
+    #[test]
+    fn test_looks_minified() {
+        let bundle = "var a=1;".repeat(1000);
+        assert!(looks_minified(&bundle));
+        // Short one-liners and ordinary code aren't minified
+        assert!(!looks_minified("let x = 1;"));
+        let code = "function add(a, b) { return a + b; }\n".repeat(500);
+        assert!(!looks_minified(&code));
+    }
+
+    #[test]
+    fn test_has_disable_marker() {
+        let marker = "codebook: off";
+        assert!(has_disable_marker("// codebook: off\nfn main() {}", marker));
+        assert!(has_disable_marker("\n  \n# codebook: off\nx = 1", marker));
+        assert!(has_disable_marker("\u{feff}<!-- codebook: off -->", marker));
+        // Only the first non-empty line counts
+        assert!(!has_disable_marker(
+            "fn main() {}\n// codebook: off",
+            marker
+        ));
+        assert!(!has_disable_marker("// codebook: on\n", marker));
+        assert!(!has_disable_marker("", marker));
+    }
+}
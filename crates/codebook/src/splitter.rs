@@ -1,11 +1,47 @@
+/// Characters that always split words and are dropped: `snake_case`,
+/// `a.b` and `a::b` paths, `kebab-case` (Lisp names, CSS custom properties)
+/// and `/` paths.
+const SEPARATORS: [char; 5] = ['_', '.', ':', '-', '/'];
+
 #[derive(PartialEq)]
 enum CharType {
     Lower,
     Upper,
     Digit,
-    Underscore,
-    Period,
-    Colon,
+    Separator,
+}
+
+/// Letter runs up to this long stay attached to the digits after them, so
+/// tokens like `utf8`, `sha256` and `x86` aren't split into fragments
+/// (`utf`, `sha`) that would then be checked as words on their own.
+const MAX_DIGIT_PREFIX_LEN: usize = 4;
+
+/// Whether `run` is a short all-lowercase or all-uppercase letter run that
+/// belongs with the digits following it. Title-case runs like the `Age` in
+/// `userAge10` are words in their own right and still split.
+fn is_digit_prefix(run: &str) -> bool {
+    run.chars().count() <= MAX_DIGIT_PREFIX_LEN
+        && (run.chars().all(char::is_lowercase) || run.chars().all(char::is_uppercase))
+}
+
+/// How the parser splits tokens into words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitOptions {
+    /// Split camelCase in comments, strings and prose. When false, `macOS`
+    /// and `iPhone` are checked whole there; identifiers always split.
+    pub camel_case: bool,
+    /// Mark hyphen-joined words in comments, strings and prose (`e-mail`)
+    /// so the checker can try the whole compound before its parts.
+    pub hyphenated_compounds: bool,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            camel_case: true,
+            hyphenated_compounds: false,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -45,9 +81,7 @@ pub fn split_into<'a>(s: &'a str, result: &mut Vec<SplitRef<'a>>) {
         let char_type = match c {
             ch if ch.is_uppercase() => CharType::Upper,
             ch if ch.is_ascii_digit() => CharType::Digit,
-            '_' => CharType::Underscore,
-            '.' => CharType::Period,
-            ':' => CharType::Colon,
+            ch if SEPARATORS.contains(&ch) => CharType::Separator,
             _ => CharType::Lower,
         };
 
@@ -57,21 +91,21 @@ pub fn split_into<'a>(s: &'a str, result: &mut Vec<SplitRef<'a>>) {
                 .peek()
                 .map(|(_, next_c)| next_c.is_ascii_lowercase())
                 .unwrap_or(false),
+            Some(CharType::Lower | CharType::Upper) if char_type == CharType::Digit => {
+                !is_digit_prefix(&s[word_start_byte..byte_pos])
+            }
             Some(prev)
                 if (prev != CharType::Digit && char_type == CharType::Digit)
                     || (prev == CharType::Digit && char_type != CharType::Digit) =>
             {
                 true
             }
-            _ => matches!(
-                char_type,
-                CharType::Underscore | CharType::Period | CharType::Colon
-            ),
+            _ => char_type == CharType::Separator,
         };
 
         if should_split && byte_pos > word_start_byte {
             let word_slice = &s[word_start_byte..byte_pos];
-            if !word_slice.is_empty() && !word_slice.chars().all(|c| matches!(c, '_' | '.' | ':')) {
+            if !word_slice.is_empty() && !word_slice.chars().all(|c| SEPARATORS.contains(&c)) {
                 result.push(SplitRef {
                     word: word_slice,
                     start_byte: word_start_byte,
@@ -80,10 +114,7 @@ pub fn split_into<'a>(s: &'a str, result: &mut Vec<SplitRef<'a>>) {
             word_start_byte = byte_pos;
         }
 
-        if matches!(
-            char_type,
-            CharType::Underscore | CharType::Period | CharType::Colon
-        ) {
+        if char_type == CharType::Separator {
             if let Some((next_byte_pos, _)) = char_iter.peek() {
                 word_start_byte = *next_byte_pos;
             } else {
@@ -97,7 +128,7 @@ pub fn split_into<'a>(s: &'a str, result: &mut Vec<SplitRef<'a>>) {
     // Handle final word
     if word_start_byte < s.len() {
         let word_slice = &s[word_start_byte..];
-        if !word_slice.is_empty() && !word_slice.chars().all(|c| matches!(c, '_' | '.' | ':')) {
+        if !word_slice.is_empty() && !word_slice.chars().all(|c| SEPARATORS.contains(&c)) {
             result.push(SplitRef {
                 word: word_slice,
                 start_byte: word_start_byte,
@@ -106,10 +137,54 @@ pub fn split_into<'a>(s: &'a str, result: &mut Vec<SplitRef<'a>>) {
     }
 }
 
+/// Rejoin the sub-words of `s` that `split_into` separated only at a case
+/// change (`mac` + `OS` back into `macOS`), keeping splits at digits and
+/// separators.
+pub fn join_case_splits<'a>(s: &'a str, result: &mut Vec<SplitRef<'a>>) {
+    let mut kept = 0;
+    for i in 0..result.len() {
+        if kept > 0 {
+            let prev = &result[kept - 1];
+            let prev_end = prev.start_byte + prev.word.len();
+            let part = &result[i];
+            if prev_end == part.start_byte
+                && prev
+                    .word
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_alphabetic)
+                && part.word.chars().next().is_some_and(char::is_uppercase)
+            {
+                let start = prev.start_byte;
+                result[kept - 1].word = &s[start..part.start_byte + part.word.len()];
+                continue;
+            }
+        }
+        result.swap(kept, i);
+        kept += 1;
+    }
+    result.truncate(kept);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_join_case_splits() {
+        let joined = |s| {
+            let mut parts = split(s);
+            join_case_splits(s, &mut parts);
+            parts.into_iter().map(|p| p.word).collect::<Vec<_>>()
+        };
+        assert_eq!(joined("macOS"), vec!["macOS"]);
+        assert_eq!(joined("iPhone"), vec!["iPhone"]);
+        assert_eq!(joined("HTTPServer"), vec!["HTTPServer"]);
+        // Separators and digits still split
+        assert_eq!(joined("user_Name"), vec!["user", "Name"]);
+        assert_eq!(joined("userAge10"), vec!["userAge", "10"]);
+    }
+
     #[test]
     fn test_camel_case_splitting() {
         let words: Vec<&str> = split("calculateUserAge")
@@ -225,6 +300,20 @@ mod tests {
         assert_eq!(words, vec!["user", "Age", "10"]);
     }
 
+    #[test]
+    fn test_short_prefix_stays_with_digits() {
+        let words = |s| split(s).into_iter().map(|s| s.word).collect::<Vec<_>>();
+        assert_eq!(words("utf8"), vec!["utf8"]);
+        assert_eq!(words("sha256"), vec!["sha256"]);
+        assert_eq!(words("x86_64"), vec!["x86", "64"]);
+        assert_eq!(words("base64Encode"), vec!["base64", "Encode"]);
+        assert_eq!(words("parseUTF8"), vec!["parse", "UTF8"]);
+        assert_eq!(words("utf8string"), vec!["utf8", "string"]);
+        // Longer runs and title-case words are still split off
+        assert_eq!(words("version2"), vec!["version", "2"]);
+        assert_eq!(words("getUtf8"), vec!["get", "Utf", "8"]);
+    }
+
     #[test]
     fn test_uppercase() {
         let words: Vec<&str> = split("EXAMPLE").into_iter().map(|s| s.word).collect();
@@ -291,6 +380,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_kebab_case_and_path_splitting() {
+        let words = |s| split(s).into_iter().map(|p| p.word).collect::<Vec<_>>();
+        assert_eq!(words("my-custom-prop"), vec!["my", "custom", "prop"]);
+        assert_eq!(words("--my-custom-prop"), vec!["my", "custom", "prop"]);
+        assert_eq!(words("src/userName"), vec!["src", "user", "Name"]);
+        assert!(split("-/-").is_empty());
+        let last = split("my-custom-prop").pop().unwrap();
+        assert_eq!(last.start_byte, 10);
+    }
+
     #[test]
     fn test_split_ref_xml_case() {
         let words = split("XMLHttpRequest");
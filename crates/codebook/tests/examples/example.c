@@ -0,0 +1,16 @@
+#include <stdio.h>
+
+/* Compute the averge of the values in the buffer */
+double compute_average(const int *values, int count) {
+    int totl = 0;
+    for (int i = 0; i < count; i++) {
+        totl += values[i];
+    }
+    // Guard against divsion by zero
+    return count ? (double)totl / count : 0.0;
+}
+
+int main(void) {
+    printf("The resut is %f\n", compute_average(NULL, 0));
+    return 0;
+}
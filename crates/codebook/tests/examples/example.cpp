@@ -0,0 +1,22 @@
+#include <string>
+#include <vector>
+
+// A simple inventry of named items
+class Inventory {
+public:
+    void add_item(const std::string& itme_name) {
+        items.push_back(itme_name);
+    }
+
+    /// Returns the number of itmes stored
+    size_t size() const { return items.size(); }
+
+private:
+    std::vector<std::string> items;
+};
+
+int main() {
+    Inventory inventory;
+    inventory.add_item("hammmer");
+    return 0;
+}
@@ -0,0 +1,16 @@
+using System;
+
+namespace Example
+{
+    // Represents a customr account
+    public class Account
+    {
+        public string OwnerNmae { get; set; }
+
+        /// <summary>Deposit the given amout into the account.</summary>
+        public void Deposit(decimal amount)
+        {
+            Console.WriteLine("Depositing fnds into the account");
+        }
+    }
+}
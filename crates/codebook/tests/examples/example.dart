@@ -0,0 +1,13 @@
+// Fetches the user profle from the server
+class ProfileService {
+  final String baseUrl;
+
+  ProfileService(this.baseUrl);
+
+  /// Loads the profile for the givn user id
+  Future<String> loadProfile(int userId) async {
+    final mesage = 'Loading profile for $userId';
+    print(mesage);
+    return 'profile';
+  }
+}
@@ -0,0 +1,15 @@
+-module(counter).
+-export([start/0, incremnt/1]).
+
+%% Start the countr process
+start() ->
+    spawn(fun() -> loop(0) end).
+
+%% Send an incremnt mesage
+incremnt(Pid) ->
+    Pid ! increment.
+
+loop(Count) ->
+    receive
+        increment -> loop(Count + 1)
+    end.
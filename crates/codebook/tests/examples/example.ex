@@ -0,0 +1,10 @@
+defmodule Greeter do
+  @moduledoc """
+  Sends greetngs to new users.
+  """
+
+  # Build the welcom message
+  def welcome(name) do
+    "Hello, #{name}! Welcom aboard."
+  end
+end
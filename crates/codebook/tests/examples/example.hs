@@ -0,0 +1,10 @@
+module Main where
+
+-- | Calculate the factorail of a number
+factorial :: Integer -> Integer
+factorial 0 = 1
+factorial n = n * factorial (n - 1)
+
+{- The main entry pont -}
+main :: IO ()
+main = putStrLn ("Resullt: " ++ show (factorial 5))
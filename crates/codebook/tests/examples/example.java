@@ -0,0 +1,17 @@
+package com.example;
+
+/**
+ * Keeps track of the ordres placed by a customer.
+ */
+public class OrderHistory {
+    private final int maxOrdrs;
+
+    public OrderHistory(int maxOrdrs) {
+        this.maxOrdrs = maxOrdrs;
+    }
+
+    // Print a sumary of the history
+    public void printSummary() {
+        System.out.println("Order histroy summary");
+    }
+}
@@ -0,0 +1,8 @@
+(* Compute the lenght of a list *)
+let rec length lst =
+  match lst with
+  | [] -> 0
+  | _ :: rest -> 1 + length rest
+
+(* Print a greting *)
+let () = print_endline "Helo from OCaml"
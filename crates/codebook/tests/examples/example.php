@@ -0,0 +1,11 @@
+<?php
+// Handles incomming requests for the API
+class RequestHandler
+{
+    /** Return the respnse body for a path */
+    public function handle(string $path): string
+    {
+        $mesage = "Handling reqest for " . $path;
+        return $mesage;
+    }
+}
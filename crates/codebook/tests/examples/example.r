@@ -0,0 +1,10 @@
+# Load the datset and compute summary statistics
+load_data <- function(path) {
+  read.csv(path)
+}
+
+# Plot the distrbution of values
+summary_stats <- function(values) {
+  print("Computing statistcs")
+  mean(values)
+}
@@ -0,0 +1,12 @@
+# Manages the shoping cart for a session
+class Cart
+  def initialize
+    @itmes = []
+  end
+
+  # Add a product to the cart
+  def add(product)
+    puts "Adding prodct to the cart"
+    @itmes << product
+  end
+end
@@ -0,0 +1,13 @@
+#!/usr/bin/env bash
+# Deploy the aplication to the staging servr
+set -euo pipefail
+
+TARGET_DIR="/opt/releases"
+# Copy the artifcats and restart the service
+deploy_release() {
+    local release_name="$1"
+    echo "Deploying relase $release_name to $TARGET_DIR"
+    cp -r build/ "$TARGET_DIR/$release_name"
+}
+
+deploy_release "nightly"
@@ -0,0 +1,11 @@
+import Foundation
+
+/// A simple temprature converter
+struct Converter {
+    // Convert celsius to farenheit
+    func toFahrenheit(_ celsius: Double) -> Double {
+        let mesage = "Converting temperture"
+        print(mesage)
+        return celsius * 9 / 5 + 32
+    }
+}
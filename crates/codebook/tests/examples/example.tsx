@@ -0,0 +1,7 @@
+import React from "react";
+
+// Renders the user gretting banner
+export function Banner({ userName }: { userName: string }) {
+  const mesage = `Welcom back, ${userName}`;
+  return <div className="banner">{mesage}</div>;
+}
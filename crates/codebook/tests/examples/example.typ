@@ -0,0 +1,7 @@
+= Introducton
+
+This documnt explains how the system handles requets.
+
+// A comment about the formating
+== Details
+The sytem is designed to be simple.
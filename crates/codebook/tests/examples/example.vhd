@@ -0,0 +1,15 @@
+-- A simple countr with an enable input
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity counter is
+    port (
+        clk    : in std_logic;
+        enable : in std_logic
+    );
+end entity counter;
+
+-- Behavorial architecture
+architecture rtl of counter is
+begin
+end architecture rtl;
@@ -0,0 +1,12 @@
+const std = @import("std");
+
+// Compute the sum of a slise of numbers
+fn sum(values: []const i32) i32 {
+    var totl: i32 = 0;
+    for (values) |v| totl += v;
+    return totl;
+}
+
+pub fn main() void {
+    std.debug.print("The resut is {d}\n", .{sum(&[_]i32{ 1, 2, 3 })});
+}
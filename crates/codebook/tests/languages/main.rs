@@ -1,16 +1,23 @@
+#[path = "../../benches/samples/mod.rs"]
+mod samples;
 mod utils;
 
+mod test_bash;
 mod test_c;
 mod test_config;
 mod test_cpp;
 mod test_csharp;
 mod test_css;
 mod test_dart;
+mod test_directives;
 mod test_elixir;
 mod test_erlang;
 mod test_files;
+mod test_gettext;
 mod test_go;
+mod test_graphql;
 mod test_haskell;
+mod test_html;
 mod test_java;
 mod test_javascript;
 mod test_just;
@@ -34,5 +41,6 @@ mod test_toml;
 mod test_typescript;
 mod test_typst;
 mod test_vhdl;
+mod test_vue;
 mod test_yaml;
 mod test_zig;
@@ -0,0 +1,40 @@
+use codebook::queries::LanguageType;
+
+use super::utils::{assert_spelling, get_processor};
+
+#[test]
+fn test_bash_comments_strings_and_heredocs() {
+    let sample_text = r#"#!/bin/bash
+# Deploy the aplication to the server
+deploy_releese() {
+    if [ -z "$TARGT_DIR" ]; then
+        echo "Missing targt directory for $USRNAME"
+        exit 1
+    fi
+    cat <<EOF
+Thank you for waiting, the depoyment is done.
+Logs are stord in $LOGDIRR
+EOF
+}
+"#;
+    assert_spelling(
+        LanguageType::Bash,
+        sample_text,
+        &["aplication", "releese", "targt", "depoyment", "stord"],
+        &["echo", "fi", "TARGT", "USRNAME", "LOGDIRR", "bin"],
+    );
+}
+
+#[test]
+fn test_bash_detected_from_shebang() {
+    let processor = get_processor();
+    let text = "#!/usr/bin/env bash\n# A helpfull script\nmkdir -p builddir\n";
+    // No extension, so the shebang picks the grammar and only the comment
+    // is checked
+    let words: Vec<String> = processor
+        .spell_check(text, None, Some("scripts/bootstrap"))
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    assert_eq!(words, vec!["helpfull"]);
+}
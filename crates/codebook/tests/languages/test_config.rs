@@ -1,4 +1,4 @@
-use codebook::{Codebook, queries::LanguageType};
+use codebook::{Codebook, parser::IssueKind, queries::LanguageType};
 use codebook_config::{CodebookConfig, CodebookConfigMemory};
 use std::sync::Arc;
 
@@ -27,3 +27,359 @@ fn test_custom_words() {
         &["testword"],
     );
 }
+
+#[test]
+fn test_min_word_length_text_catches_short_comment_typos() {
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        min_word_length_text: Some(2),
+        ..Default::default()
+    }));
+    let sample_text = r#"
+// Open the qx connection
+fn db(zz: i32) {}
+"#;
+    // Two-letter words are checked in comments but still skipped in code.
+    super::utils::assert_spelling_with(
+        &super::utils::make_codebook(config),
+        LanguageType::Rust,
+        sample_text,
+        &["qx"],
+        &["db", "zz"],
+    );
+}
+
+#[test]
+fn test_custom_hunspell_dictionary_by_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let aff_path = dir.path().join("team.aff");
+    let dic_path = dir.path().join("team.dic");
+    std::fs::write(&aff_path, "SET UTF-8\n").unwrap();
+    std::fs::write(&dic_path, "2\nfrobnicate\nwidgetry\n").unwrap();
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        custom_dictionaries: vec![codebook_config::CustomDictionary {
+            name: "team".to_string(),
+            aff_path,
+            dic_path,
+        }],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::Text,
+        "frobnicate the widgetry with blorf",
+        &["blorf"],
+        &["frobnicate", "widgetry"],
+    );
+}
+
+#[test]
+fn test_custom_dictionary_reloads_when_file_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let aff_path = dir.path().join("team.aff");
+    let dic_path = dir.path().join("team.dic");
+    std::fs::write(&aff_path, "SET UTF-8\n").unwrap();
+    std::fs::write(&dic_path, "1\nfrobnicate\n").unwrap();
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        custom_dictionaries: vec![codebook_config::CustomDictionary {
+            name: "team".to_string(),
+            aff_path,
+            dic_path: dic_path.clone(),
+        }],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    let flagged = |text: &str| -> Vec<String> {
+        processor
+            .spell_check(text, Some(LanguageType::Text), None)
+            .into_iter()
+            .map(|r| r.word)
+            .collect()
+    };
+    assert_eq!(flagged("frobnicate the widgetry"), vec!["widgetry"]);
+    // The size changes too, so this holds even with coarse mtimes
+    std::fs::write(&dic_path, "2\nfrobnicate\nwidgetry\n").unwrap();
+    assert!(flagged("frobnicate the widgetry").is_empty());
+}
+
+#[test]
+fn test_missing_custom_dictionary_reports_load_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        custom_dictionaries: vec![codebook_config::CustomDictionary {
+            name: "team".to_string(),
+            aff_path: dir.path().join("missing.aff"),
+            dic_path: dir.path().join("missing.dic"),
+        }],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    processor.spell_check("frobnicate", Some(LanguageType::Text), None);
+    let errors = processor.dictionary_load_errors();
+    assert!(
+        errors
+            .iter()
+            .any(|e| e.contains("team") && e.contains("missing.aff")),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn test_is_flagged_word() {
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        flag_words: vec!["todo".to_string()],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    assert!(processor.is_flagged_word("todo", None));
+    assert!(processor.is_flagged_word("TODO", Some("src/main.rs")));
+    assert!(!processor.is_flagged_word("wrod", None));
+}
+
+#[test]
+fn test_split_camel_case_off_checks_whole_words() {
+    // Made-up brand names whose halves are ordinary words
+    let text = "Updated SunFlow on the rainBook";
+    let flagged = |processor: &codebook::Codebook| -> Vec<String> {
+        processor
+            .spell_check(text, Some(LanguageType::Text), None)
+            .into_iter()
+            .map(|r| r.word)
+            .collect()
+    };
+    // Split by default, and each part is a known word
+    let processor = super::utils::make_codebook(Arc::new(CodebookConfigMemory::default()));
+    assert!(flagged(&processor).is_empty());
+
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        split_camel_case: Some(false),
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config.clone());
+    assert_eq!(flagged(&processor), vec!["SunFlow", "rainBook"]);
+
+    // The whole word can then be added to the dictionary
+    config.add_word("rainBook");
+    assert_eq!(flagged(&processor), vec!["SunFlow"]);
+
+    // Identifiers still split
+    let code = "fn update_rainBook() {}";
+    assert!(
+        processor
+            .spell_check(code, Some(LanguageType::Rust), None)
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_hyphenated_compounds_check_whole_word_first() {
+    let text = "Send an e-mail about the wrong-wrod";
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        hyphenated_compounds: Some(true),
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config.clone());
+    // The dictionary knows "e-mail" whole; the compound "wrong-wrod" isn't
+    // known, so its parts are checked and only the bad half is flagged
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::Text,
+        text,
+        &["wrod"],
+        &["e", "mail", "wrong"],
+    );
+
+    // An allowed compound hides its parts too
+    config.add_word("wrong-wrod");
+    assert!(
+        processor
+            .spell_check(text, Some(LanguageType::Text), None)
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_hyphenated_compounds_off_checks_parts() {
+    let config = Arc::new(CodebookConfigMemory::default());
+    config.add_word("wrong-wrod");
+    let processor = super::utils::make_codebook(config);
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::Text,
+        "the wrong-wrod",
+        &["wrod"],
+        &["wrong"],
+    );
+}
+
+#[test]
+fn test_keep_together_checks_matches_whole() {
+    let pattern = |p| regex::Regex::new(p).unwrap();
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        keep_together: vec![
+            pattern(r"\b[A-Z]\+\+"),
+            pattern(r"\.NET\b"),
+            pattern(r"\bF#"),
+        ],
+        min_word_length: Some(2),
+        ..Default::default()
+    }));
+    for word in ["c++", ".net", "f#"] {
+        config.add_word(word);
+    }
+    let processor = super::utils::make_codebook(config);
+    // Kept words are looked up whole: the allowed ones pass, and an unknown
+    // one is flagged as a single word rather than as its letters
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::Text,
+        "Port the F# and C++ code to .NET, not X++ yet",
+        &["X++"],
+        &["F", "C", "NET", "X"],
+    );
+}
+
+#[test]
+fn test_learn_project_symbols() {
+    let defined = "fn frobnicate_gizmo() {}\n// Calls frobnicate on every gizmo\n";
+    let used = "// The frobnicate step runs after the splork step\n";
+
+    // Off by default: the name and its mentions are flagged
+    let processor = super::utils::get_processor();
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::Rust,
+        used,
+        &["frobnicate", "splork"],
+        &[],
+    );
+
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        learn_project_symbols: Some(true),
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    // Only the definition is reported, not the mention in its doc comment
+    let results = processor.spell_check(defined, Some(LanguageType::Rust), None);
+    assert_eq!(results.len(), 1, "{results:?}");
+    assert_eq!(results[0].word, "frobnicate");
+    assert_eq!(results[0].locations.len(), 1);
+    assert_eq!(
+        results[0].locations[0].start_byte,
+        defined.find("frobnicate").unwrap()
+    );
+    assert_eq!(results[0].tag.as_deref(), Some("identifier.function"));
+    // Learned for the session, so other files know the name too
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::Rust,
+        used,
+        &["splork"],
+        &["frobnicate"],
+    );
+
+    // Flagged words are still reported
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        learn_project_symbols: Some(true),
+        flag_words: vec!["frobnicate".to_string()],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    let flagged: Vec<_> = processor
+        .spell_check(defined, Some(LanguageType::Rust), None)
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    assert_eq!(flagged, vec!["frobnicate"]);
+}
+
+#[test]
+fn test_project_symbols_by_file() {
+    let defined = "fn frobnicate_gizmo() {}\n";
+    let used = "// Calls frobnicate on every gizmo\n";
+    let config = Arc::new(CodebookConfigMemory::new(codebook_config::ConfigSettings {
+        learn_project_symbols: Some(true),
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    let mentions = |processor: &codebook::Codebook| {
+        processor
+            .spell_check(used, Some(LanguageType::Rust), Some("used.rs"))
+            .into_iter()
+            .map(|r| r.word)
+            .collect::<Vec<_>>()
+    };
+
+    // Learned up front, a name is known in files checked before its definition
+    assert_eq!(mentions(&processor), vec!["frobnicate"]);
+    processor.learn_symbols(defined, Some(LanguageType::Rust), Some("defined.rs"));
+    assert!(mentions(&processor).is_empty());
+
+    // Renaming the definition forgets the old name
+    processor.spell_check(
+        "fn frobulate_gizmo() {}\n",
+        Some(LanguageType::Rust),
+        Some("defined.rs"),
+    );
+    assert_eq!(mentions(&processor), vec!["frobnicate"]);
+}
+
+#[test]
+fn test_flag_confusables() {
+    // `userName` with a Cyrillic е (U+0435)
+    let sample = "fn check(us\u{435}rName: &str) -> bool {\n    true\n}\n";
+    let issues = |config: codebook_config::ConfigSettings| {
+        let processor = super::utils::make_codebook(Arc::new(CodebookConfigMemory::new(config)));
+        processor
+            .spell_check(sample, Some(LanguageType::Rust), None)
+            .into_iter()
+            .map(|r| (r.word, r.kind, r.locations[0].start_byte))
+            .collect::<Vec<_>>()
+    };
+
+    // Off by default: only reported as an unknown word
+    assert_eq!(
+        issues(Default::default()),
+        vec![("us\u{435}r".to_string(), IssueKind::Misspelling, 9)]
+    );
+    let confusables = codebook_config::ConfigSettings {
+        flag_confusables: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        issues(confusables.clone()),
+        vec![("us\u{435}r".to_string(), IssueKind::Confusable, 9)]
+    );
+    // Allowed words aren't reported at all
+    assert!(
+        issues(codebook_config::ConfigSettings {
+            words: vec!["us\u{435}r".to_string()],
+            ..confusables
+        })
+        .is_empty()
+    );
+}
+
+#[test]
+fn test_allowed_contractions() {
+    let sample = "Y'all meet at ten o'clock, or ten o’clock for y’all. C'mon, g'day, ba'al!";
+    let flagged = |allowed_contractions: &[&str]| {
+        let config = codebook_config::ConfigSettings {
+            allowed_contractions: allowed_contractions.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        };
+        let processor = super::utils::make_codebook(Arc::new(CodebookConfigMemory::new(config)));
+        let mut words = processor
+            .spell_check(sample, Some(LanguageType::Text), None)
+            .into_iter()
+            .map(|r| r.word)
+            .collect::<Vec<_>>();
+        words.sort();
+        words
+    };
+
+    // `y'all` and `o'clock` are dictionary words with either apostrophe
+    assert_eq!(flagged(&[]), vec!["C'mon", "ba'al", "g'day"]);
+    // Case and the kind of apostrophe don't matter
+    assert_eq!(flagged(&["c’mon", "G'DAY", "y'all"]), vec!["ba'al"]);
+}
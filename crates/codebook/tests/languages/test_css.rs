@@ -14,3 +14,21 @@ fn test_css_location() {
 "#;
     assert_spelling(LanguageType::Css, sample_css, &["testz"], &[]);
 }
+
+#[test]
+fn test_css_kebab_case_names_split() {
+    let sample_css = r#"
+        :root {
+            --brand-primry-color: #336699;
+        }
+        .card-titel {
+            border-color: var(--brand-accent);
+        }
+"#;
+    assert_spelling(
+        LanguageType::Css,
+        sample_css,
+        &["titel", "primry"],
+        &["brand", "color", "card"],
+    );
+}
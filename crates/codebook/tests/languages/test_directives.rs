@@ -0,0 +1,87 @@
+use codebook::queries::LanguageType;
+
+use super::utils::get_processor;
+
+fn flagged(language: LanguageType, text: &str) -> Vec<String> {
+    let mut words: Vec<String> = get_processor()
+        .spell_check(text, Some(language), None)
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    words.sort();
+    words
+}
+
+#[test]
+fn test_ignore_line_directives() {
+    let cases = [
+        (
+            LanguageType::Rust,
+            "let s = \"wrongg\"; // codebook:ignore\n// codebook:ignore-next-line\nlet t = \"baddd\";\nlet u = \"missd\";\n",
+        ),
+        (
+            LanguageType::Python,
+            "s = \"wrongg\"  # codebook:ignore\n# spellcheck:disable-next-line\nt = \"baddd\"\nu = \"missd\"\n",
+        ),
+        (
+            LanguageType::Javascript,
+            "const s = \"wrongg\"; /* spellcheck:disable-line */\n// codebook:ignore-next-line\nconst t = \"baddd\";\nconst u = \"missd\";\n",
+        ),
+        (
+            LanguageType::Go,
+            "var s = \"wrongg\" // codebook:ignore\n// codebook:ignore-next-line\nvar t = \"baddd\"\nvar u = \"missd\"\n",
+        ),
+        (
+            LanguageType::Bash,
+            "echo \"wrongg\" # codebook:ignore\n# codebook:ignore-next-line\necho \"baddd\"\necho \"missd\"\n",
+        ),
+        (
+            LanguageType::HTML,
+            "<p>wrongg</p> <!-- codebook:ignore -->\n<!-- codebook:ignore-next-line -->\n<p>baddd</p>\n<p>missd</p>\n",
+        ),
+        (
+            LanguageType::Markdown,
+            "Some wrongg text.\n\n<!-- codebook:ignore-next-line -->\nSome baddd text.\n\nSome missd text.\n",
+        ),
+    ];
+    for (language, text) in cases {
+        let expected: &[&str] = if language == LanguageType::Markdown {
+            // No comment can share a line with Markdown prose
+            &["missd", "wrongg"]
+        } else {
+            &["missd"]
+        };
+        assert_eq!(flagged(language, text), expected, "{language:?}");
+    }
+}
+
+#[test]
+fn test_directive_outside_comment_is_ignored() {
+    let text = "let s = \"wrongg codebook:ignore\";\n";
+    assert_eq!(flagged(LanguageType::Rust, text), vec!["wrongg"]);
+}
+
+#[test]
+fn test_disabled_blocks() {
+    let text = r#"let a = "wrongg";
+// codebook:disable genrated tabls below
+let b = "baddd";
+// codebook:disable
+let c = "missd"; // codebook:ignore
+// codebook:enable
+let d = "typoo";
+/* codebook:enable */
+let e = "erorr";
+# codebook:disable
+"#;
+    // Only the lines outside the first block are checked, and the stray
+    // trailing comment isn't a directive (it's not a Rust comment)
+    assert_eq!(
+        flagged(LanguageType::Rust, text),
+        vec!["erorr", "typoo", "wrongg"]
+    );
+
+    let text = "s = 'wrongg'\n# codebook:disable\nt = 'baddd'\nu = 'missd'\n";
+    // An unterminated block runs to the end of the file
+    assert_eq!(flagged(LanguageType::Python, text), vec!["wrongg"]);
+}
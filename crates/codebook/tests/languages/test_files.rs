@@ -1,4 +1,6 @@
-use codebook::{parser::TextRange, queries::LanguageType};
+use codebook::{CodebookError, parser::TextRange, queries::LanguageType};
+use codebook_config::{CodebookConfigMemory, ConfigSettings};
+use std::sync::Arc;
 
 /// Path relative to the crate directory, which is the cwd when cargo runs tests.
 fn example_file_path(file: &str) -> String {
@@ -198,3 +200,221 @@ fn test_example_files() {
         assert_eq!(&misspelled, expected, "flagged words in {file}");
     }
 }
+
+fn get_processor_with_language_override(pattern: &str, language: &str) -> codebook::Codebook {
+    let mut settings = codebook_config::ConfigSettings::default();
+    settings
+        .language_overrides
+        .insert(pattern.to_string(), language.to_string());
+    let config = std::sync::Arc::new(codebook_config::CodebookConfigMemory::new(settings));
+    super::utils::make_codebook(config)
+}
+
+#[test]
+fn test_language_override_routes_tpl_to_html() {
+    // As plain text, the misspelled tag and attribute names would be
+    // flagged; parsed as HTML only text content and comments are checked.
+    let text = r#"<sectionn clss="main"><!-- a commennt -->Wolrd</sectionn>"#;
+    let processor = get_processor_with_language_override("*.tpl", "html");
+
+    let mut words: Vec<String> = processor
+        .spell_check(text, None, Some("templates/page.tpl"))
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    words.sort();
+    assert_eq!(words, vec!["Wolrd", "commennt"]);
+
+    // The override beats a language supplied by the caller, e.g. an editor
+    // that only knows the file by its extension
+    let mut words: Vec<String> = processor
+        .spell_check(text, Some(LanguageType::Text), Some("page.tpl"))
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    words.sort();
+    assert_eq!(words, vec!["Wolrd", "commennt"]);
+
+    // Files not matching the glob keep extension-based detection
+    let words: Vec<String> = processor
+        .spell_check(text, None, Some("page.txt"))
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    assert!(words.contains(&"sectionn".to_string()));
+    assert!(words.contains(&"clss".to_string()));
+}
+
+#[test]
+fn test_language_override_forces_text() {
+    // Parsed as Rust, a call using existing names isn't checked
+    // (only definitions are); forced to text, every word is.
+    let text = "fooo(quxx);";
+    let processor = super::utils::get_processor();
+    assert!(
+        processor
+            .spell_check(text, None, Some("src/main.rs"))
+            .is_empty()
+    );
+
+    let processor = get_processor_with_language_override("**/*.rs", "text");
+    let mut words: Vec<String> = processor
+        .spell_check(text, None, Some("src/main.rs"))
+        .into_iter()
+        .map(|r| r.word)
+        .collect();
+    words.sort();
+    assert_eq!(words, vec!["fooo", "quxx"]);
+}
+
+#[test]
+fn test_benchmark_samples_cover_every_language() {
+    let processor = super::utils::get_processor();
+    for language in LanguageType::all().iter().chain([&LanguageType::Text]) {
+        let text = super::samples::sample(*language);
+        assert!(
+            !processor
+                .spell_check(text, Some(*language), None)
+                .is_empty(),
+            "{language:?} sample has no misspellings"
+        );
+    }
+}
+
+#[test]
+fn test_spell_check_reader() {
+    let processor = super::utils::get_processor();
+    let results = processor
+        .spell_check_reader(&b"# a comentt\n"[..], Some(LanguageType::Python), None)
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].word, "comentt");
+
+    // Invalid UTF-8 is an error, not a panic
+    let err = processor
+        .spell_check_reader(&b"wrod \xff\xfe"[..], None, None)
+        .unwrap_err();
+    assert!(
+        matches!(&err, CodebookError::Io(e) if e.kind() == std::io::ErrorKind::InvalidData),
+        "{err:?}"
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let binary = dir.path().join("blob.txt");
+    std::fs::write(&binary, [0x89, b'P', b'N', b'G', 0xff, 0x00]).unwrap();
+    let err = processor
+        .spell_check_file(binary.to_str().unwrap())
+        .unwrap_err();
+    assert!(
+        matches!(&err, CodebookError::ReadFile { path, source }
+            if *path == binary && source.kind() == std::io::ErrorKind::InvalidData),
+        "{err:?}"
+    );
+    let err = processor
+        .spell_check_file("tests/examples/missing.txt")
+        .unwrap_err();
+    assert!(
+        matches!(&err, CodebookError::ReadFile { source, .. }
+            if source.kind() == std::io::ErrorKind::NotFound),
+        "{err:?}"
+    );
+    assert!(err.to_string().contains("missing.txt"));
+}
+
+#[test]
+fn test_binary_and_minified_files_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let binary = dir.path().join("blob.dat");
+    std::fs::write(&binary, "GIF89a\0\0\u{1} wrodd actualbad").unwrap();
+    let minified = dir.path().join("bundle.min.js");
+    let bundle = "var actualbad=function(){return\"wrodd\"};".repeat(200);
+    std::fs::write(&minified, &bundle).unwrap();
+
+    let processor = super::utils::get_processor();
+    for path in [&binary, &minified] {
+        let results = processor.spell_check_file(path.to_str().unwrap()).unwrap();
+        assert!(results.is_empty(), "{path:?} should be skipped");
+    }
+
+    // Both heuristics can be turned off
+    let config = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+        skip_binary: Some(false),
+        skip_minified: Some(false),
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    for path in [&binary, &minified] {
+        let results = processor.spell_check_file(path.to_str().unwrap()).unwrap();
+        assert!(!results.is_empty(), "{path:?} should be checked");
+    }
+}
+
+#[test]
+fn test_disable_file_marker() {
+    let marked = "\n// codebook: off\nlet actualbad = \"wrodd\";\n";
+    let unmarked = "let actualbad = \"wrodd\";\n// codebook: off\n";
+    let processor = super::utils::get_processor();
+    assert!(
+        processor
+            .spell_check(marked, Some(LanguageType::Rust), None)
+            .is_empty()
+    );
+    assert!(
+        !processor
+            .spell_check(unmarked, Some(LanguageType::Rust), None)
+            .is_empty()
+    );
+
+    // The marker is configurable, and an empty one turns the check off
+    let custom = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+        disable_file_marker: Some("@generated".to_string()),
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(custom);
+    assert!(processor.skips_file("# @generated by protoc\nwrodd", None));
+    assert!(!processor.skips_file(marked, None));
+    let off = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+        disable_file_marker: Some(String::new()),
+        ..Default::default()
+    }));
+    assert!(!super::utils::make_codebook(off).skips_file(marked, None));
+}
+
+#[test]
+fn test_skips_file() {
+    let config = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+        ignore_paths: vec!["generated/**".to_string()],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    assert!(processor.skips_file("", Some("generated/api.rs")));
+    assert!(processor.skips_file("PK\0\0", Some("src/data.bin")));
+    assert!(!processor.skips_file("fn main() {}", Some("src/main.rs")));
+    assert!(!processor.skips_file("plain text", None));
+}
+
+#[test]
+fn test_spell_check_ignoring_paths() {
+    let config = Arc::new(CodebookConfigMemory::new(ConfigSettings {
+        ignore_paths: vec!["generated/**".to_string()],
+        include_paths: vec!["generated/**".to_string(), "src/**".to_string()],
+        ..Default::default()
+    }));
+    let processor = super::utils::make_codebook(config);
+    let text = "// Generatd code\n";
+    for path in ["generated/api.rs", "docs/notes.rs"] {
+        assert!(processor.spell_check(text, None, Some(path)).is_empty());
+        let words: Vec<_> = processor
+            .spell_check_ignoring_paths(text, None, Some(path))
+            .into_iter()
+            .map(|r| r.word)
+            .collect();
+        assert_eq!(words, ["Generatd"], "{path}");
+    }
+    // Content checks still apply
+    assert!(
+        processor
+            .spell_check_ignoring_paths("PK\0\0 Generatd", None, Some("generated/api.bin"))
+            .is_empty()
+    );
+}
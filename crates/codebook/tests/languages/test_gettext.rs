@@ -0,0 +1,80 @@
+use codebook::queries::{LanguageType, get_language_name_from_filename};
+
+use super::utils::{assert_spelling, assert_spelling_with, get_processor_with_tags};
+
+#[test]
+fn test_gettext_routing() {
+    assert_eq!(
+        get_language_name_from_filename("locale/fr/LC_MESSAGES/app.po"),
+        LanguageType::Gettext
+    );
+    assert_eq!(
+        get_language_name_from_filename("app.pot"),
+        LanguageType::Gettext
+    );
+}
+
+#[test]
+fn test_gettext_msgstr_typo_not_placeholders() {
+    let sample = r#"# Translatr notes aren't checked
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"Plural-Forms: nplurals=2; plural=(n != 1);\n"
+
+#: src/app.py:10
+#, python-format
+msgctxt "greetng"
+msgid "Hello %(usrname)s, you have %d new messages"
+msgstr "Hello %(usrname)s, you have %d new mesages"
+"\nSee {linkk}"
+"#;
+    assert_spelling(
+        LanguageType::Gettext,
+        sample,
+        &["mesages"],
+        &[
+            "Translatr",
+            "nplurals",
+            "greetng",
+            "usrname",
+            "linkk",
+            "nSee",
+        ],
+    );
+}
+
+#[test]
+fn test_gettext_plurals_and_tags() {
+    let sample = r#"msgid "One tabel"
+msgid_plural "%d chiars"
+msgstr[0] "One tablle"
+msgstr[1] "%d seatz"
+"#;
+    assert_spelling(
+        LanguageType::Gettext,
+        sample,
+        &["tabel", "chiars", "tablle", "seatz"],
+        &[],
+    );
+    // The source strings can be left to whoever writes them
+    assert_spelling_with(
+        &get_processor_with_tags(vec![], vec!["string.msgid"]),
+        LanguageType::Gettext,
+        sample,
+        &["tablle", "seatz"],
+        &["tabel", "chiars"],
+    );
+}
+
+#[test]
+fn test_gettext_directives() {
+    let sample = r#"# codebook:ignore-next-line
+msgid "Brandnaem"
+msgstr "Brand"
+
+msgid "Anothr"
+msgstr "Other"
+"#;
+    assert_spelling(LanguageType::Gettext, sample, &["Anothr"], &["Brandnaem"]);
+}
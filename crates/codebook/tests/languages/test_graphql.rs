@@ -0,0 +1,60 @@
+use codebook::queries::LanguageType;
+
+use super::utils::assert_spelling;
+
+#[test]
+fn test_graphql_descriptions_and_comments() {
+    let sample_text = r#"
+# Shared by the storefrnt and admin apps
+"""
+A customer purchase, with its line items.
+"""
+type Ordr {
+  "When it was plased"
+  placedAt: DateTim
+  custmer(id: ID, note: String = "not chekced"): Custmer
+}
+
+type Query {
+  ordrs: [Ordr!]!
+}
+"#;
+    // Type, field and argument names are the API's business, as are
+    // default values; only comments and descriptions are prose.
+    assert_spelling(
+        LanguageType::Graphql,
+        sample_text,
+        &["storefrnt", "plased"],
+        &["Custmer", "custmer", "DateTim", "chekced", "ordrs"],
+    );
+}
+
+#[test]
+fn test_graphql_block_description() {
+    let sample_text = r#"
+"""
+Fetches the curent user.
+
+Returns null when nobody is loged in.
+"""
+type Query {
+  me: User
+}
+"#;
+    assert_spelling(
+        LanguageType::Graphql,
+        sample_text,
+        &["curent", "loged"],
+        &["Query"],
+    );
+}
+
+#[test]
+fn test_graphql_routing() {
+    for file in ["schema.graphql", "queries/user.gql"] {
+        assert_eq!(
+            codebook::queries::get_language_name_from_filename(file),
+            LanguageType::Graphql
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use codebook::queries::LanguageType;
+
+use super::utils::assert_spelling;
+
+#[test]
+fn test_html_text_comments_and_prose_attributes() {
+    let sample_text = r#"<!DOCTYPE html>
+<html lang="en">
+<head><title>Welcom page</title></head>
+<body class="mainly-contnt" id="pg-wrappr">
+<!-- Navigaton bar -->
+<img src="imgs/lgo.png" alt="Compny logo">
+<input placeholder="Serch here" type="text" name="qury">
+<p>Helo&nbsp;wrld &amp; frends</p>
+<a href="https://exampel.com/pth" title='Visitt the site'>link</a>
+</body>
+</html>
+"#;
+    assert_spelling(
+        LanguageType::HTML,
+        sample_text,
+        &[
+            "Welcom",
+            "Navigaton",
+            "Compny",
+            "Serch",
+            "Helo",
+            "wrld",
+            "frends",
+            "Visitt",
+        ],
+        &[
+            "DOCTYPE", "contnt", "wrappr", "imgs", "lgo", "qury", "nbsp", "exampel", "pth",
+        ],
+    );
+}
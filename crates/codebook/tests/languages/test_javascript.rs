@@ -1,6 +1,6 @@
 use codebook::queries::LanguageType;
 
-use super::utils::assert_spelling_at;
+use super::utils::{assert_spelling, assert_spelling_at};
 
 #[test]
 fn test_javascript_location() {
@@ -47,3 +47,23 @@ fn test_javascript_location() {
         ],
     );
 }
+
+#[test]
+fn test_javascript_jsdoc_tags() {
+    let sample_text = r#"
+    /**
+     * Retruns the widget, see {@link Widgett}.
+     * @param {Widgett|Gadgett} value the inptu
+     * @returns {Array<{namme: string}>}
+     * @typedef
+     */
+    function get(value) {}
+
+    /* @tagg is only a tag in JSDoc, user@domainn is never one */"#;
+    assert_spelling(
+        LanguageType::Javascript,
+        sample_text,
+        &["Retruns", "inptu", "tagg", "domainn"],
+        &["Widgett", "Gadgett", "namme", "typedef", "param", "returns"],
+    );
+}
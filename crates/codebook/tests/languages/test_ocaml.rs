@@ -1,6 +1,6 @@
 use codebook::queries::LanguageType;
 
-use super::utils::assert_spelling_at;
+use super::utils::{assert_spelling, assert_spelling_at};
 
 #[test]
 fn test_ocaml_location() {
@@ -40,3 +40,24 @@ let () =
         ],
     );
 }
+
+#[test]
+fn test_ocaml_comments_and_strings_not_keywords() {
+    let sample_text = r#"
+module Shapes = struct
+  (* Aproximate area of a shape *)
+  let area shape =
+    match shape with
+    | Some r -> 3.14 *. r *. r
+    | None -> failwith "no shaep given"
+end
+"#;
+    assert_spelling(
+        LanguageType::OCaml,
+        sample_text,
+        &["Aproximate", "shaep"],
+        &[
+            "module", "struct", "let", "match", "with", "end", "failwith",
+        ],
+    );
+}
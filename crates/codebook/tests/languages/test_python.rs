@@ -102,6 +102,43 @@ simple = f'check these wordz {but} {not} {the} {variables}'
     );
 }
 
+fn get_processor_with_check_strings(check_strings: bool) -> codebook::Codebook {
+    let mut settings = codebook_config::ConfigSettings::default();
+    settings.languages.insert(
+        "python".to_string(),
+        codebook_config::LanguageSettings {
+            check_strings: Some(check_strings),
+        },
+    );
+    super::utils::make_codebook(std::sync::Arc::new(
+        codebook_config::CodebookConfigMemory::new(settings),
+    ))
+}
+
+#[test]
+fn test_python_check_strings_toggle() {
+    let sample_text = r#"
+# A coment about the query
+query = f"SELEC naem FROM {tabel} WHERE idd = 1"
+def fetch_recrds(): pass
+    "#;
+    // Strings off: f-string text is skipped, comments and identifiers aren't
+    assert_spelling_with(
+        &get_processor_with_check_strings(false),
+        LanguageType::Python,
+        sample_text,
+        &["coment", "recrds"],
+        &["SELEC", "naem", "idd", "tabel"],
+    );
+    assert_spelling_with(
+        &get_processor_with_check_strings(true),
+        LanguageType::Python,
+        sample_text,
+        &["coment", "recrds", "SELEC", "naem", "idd"],
+        &["tabel"],
+    );
+}
+
 #[test]
 fn test_python_import_statements() {
     let sample_text = r#"
@@ -242,3 +279,16 @@ def simple_wrngfunction[Wrgtemplate](wrngparam: str, correct: Wrngtype[Wrngtempl
         ],
     );
 }
+
+#[test]
+fn test_python_shebang_and_coding_declaration() {
+    // The interpreter path and encoding name aren't words; only the
+    // ordinary comment below them is checked.
+    let sample_text = "#!/usr/bin/env pythn3\n# -*- coding: utf8 -*-\n# An examle comment\nx = 1\n";
+    assert_spelling(
+        LanguageType::Python,
+        sample_text,
+        &["examle"],
+        &["pythn", "utf8"],
+    );
+}
@@ -183,3 +183,35 @@ fn test_pattern_matching_against_full_source() {
         &["showmode", "relativenumber"],
     );
 }
+
+#[test]
+fn test_pattern_suppresses_every_subword_of_a_match() {
+    // ignore_patterns are skip ranges over the source, not per-word checks:
+    // a match covering a camelCase token hides all of its subwords, even
+    // ones the pattern never names on their own.
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_content = r#"
+        ignore_patterns = [
+            "\\bSKU-\\d+-[A-Za-z]+\\b",
+            "\\bvendrApiRespnse\\w*"
+        ]
+    "#;
+    std::fs::write(temp_dir.path().join("codebook.toml"), config_content).unwrap();
+    let config = std::sync::Arc::new(
+        codebook_config::CodebookConfigFile::load(Some(temp_dir.path())).unwrap(),
+    );
+    let processor = super::utils::make_codebook(config);
+
+    let sample_text = r#"
+order_code = "SKU-1234-bluWidgt"
+vendrApiRespnseCachd = fetch(order_code)
+speling = vendrApiRespnseCachd
+"#;
+    assert_spelling_with(
+        &processor,
+        LanguageType::Python,
+        sample_text,
+        &["speling"],
+        &["blu", "Widgt", "vendr", "Respnse", "Cachd"],
+    );
+}
@@ -113,3 +113,80 @@ fn test_rust_regular_impl_function_names_checked() {
     // function.
     assert_spelling_at(LanguageType::Rust, sample_text, &[("erorr", &[0, 1])]);
 }
+
+#[test]
+fn test_rust_short_prefixes_stay_with_digits() {
+    // `mkv4` and `zfs2` would otherwise split into the unknown fragments
+    // `mkv` and `zfs`; kept whole, tokens with digits aren't checked, even
+    // with more letters straight after them.
+    let sample_text = r#"
+        fn decode_mkv4(sha256: &str, zfs2_pool: u8, x86_64: bool) {
+            let versoin2 = 1;
+            let mkv4stream = zfs2pool;
+        }
+    "#;
+    super::utils::assert_spelling_with(
+        &super::utils::get_processor(),
+        LanguageType::Rust,
+        sample_text,
+        &["versoin"],
+        &["mkv", "zfs", "sha"],
+    );
+}
+
+#[test]
+fn test_rust_letters_glued_to_digits_still_checked() {
+    // Runs too long to stay with their digits are split off them and checked,
+    // as are the letters after the digits.
+    let sample_text = r#"
+        fn parse_wrodd2name(v2api: u8, abc1def: u8) {}
+    "#;
+    super::utils::assert_spelling_with(
+        &super::utils::get_processor(),
+        LanguageType::Rust,
+        sample_text,
+        &["wrodd"],
+        &["parse", "name", "api", "abc", "def"],
+    );
+}
+
+#[test]
+fn test_rust_broken_code_still_checked() {
+    // Unbalanced delimiters and an unterminated string: tree-sitter recovers
+    // with error nodes, and what it can still recognize is checked.
+    let sample_text = r#"
+        // A coment before the damage
+        fn brokn_function( {
+            let mesage = "unterminatd string;
+        impl {{{ ]]] => ;;
+        /* anothr comment */
+    "#;
+    assert_spelling(
+        LanguageType::Rust,
+        sample_text,
+        &["coment", "mesage", "anothr"],
+        &[],
+    );
+}
+
+#[test]
+fn test_rust_raw_identifiers_and_lifetimes() {
+    // The `r#` prefix isn't part of the word, and lifetimes aren't
+    // identifiers to check; the apostrophe doesn't leak into words either.
+    let sample_text = r#"
+        struct Parsr<'input> {
+            r#type: &'input str,
+        }
+        fn parse_token<'lifetmie>(r#async: &'lifetmie str, r#valu: bool) -> &'static str {
+            // Borrowed for 'input, the lifetime of the struct
+            let r#tokn = r#async;
+            "done"
+        }
+    "#;
+    assert_spelling(
+        LanguageType::Rust,
+        sample_text,
+        &["Parsr", "valu", "tokn"],
+        &["type", "async", "lifetmie", "input", "static", "r"],
+    );
+}
@@ -1,6 +1,290 @@
+use codebook::queries::LanguageType;
+
 #[test]
 fn test_suggestions() {
     let processor = super::utils::get_processor();
     let suggestions = processor.get_suggestions("testz");
     assert!(!suggestions.unwrap().is_empty());
 }
+
+#[test]
+fn test_identifier_suggestion_fixes_every_subword() {
+    let processor = super::utils::get_processor();
+    assert_eq!(
+        processor.get_identifier_suggestion("calculaateScoore"),
+        Some("calculateScore".to_string())
+    );
+    assert_eq!(
+        processor.get_identifier_suggestion("MAXX_VALEU"),
+        Some("MAX_VALUE".to_string())
+    );
+}
+
+#[test]
+fn test_identifier_suggestion_needs_two_fixes() {
+    let processor = super::utils::get_processor();
+    // One misspelled subword is covered by the subword's own suggestions
+    assert_eq!(processor.get_identifier_suggestion("calculaateScore"), None);
+    assert_eq!(processor.get_identifier_suggestion("calculateScore"), None);
+}
+
+#[test]
+fn test_autofix_applies_confident_suggestions() {
+    let processor = super::utils::get_processor();
+    let text = "// Recieve the functon result\nfn calulate_total() {}\n";
+    let result = processor.autofix(text, Some(LanguageType::Rust), None);
+    assert_eq!(
+        result.text,
+        "// Receive the function result\nfn calculate_total() {}\n"
+    );
+    assert_eq!(
+        result.fixed,
+        vec![
+            ("Recieve".to_string(), "Receive".to_string()),
+            ("functon".to_string(), "function".to_string()),
+            ("calulate".to_string(), "calculate".to_string()),
+        ]
+    );
+    assert!(result.unfixed.is_empty());
+}
+
+#[test]
+fn test_autofix_renames_uses_of_a_fixed_identifier() {
+    let processor = super::utils::get_processor();
+    // The query captures only the definition; the call must follow it
+    let text = "fn calulate_total() {}\n\nfn main() {\n    let t = calulate_total();\n}\n";
+    let result = processor.autofix(text, Some(LanguageType::Rust), None);
+    assert_eq!(
+        result.text,
+        "fn calculate_total() {}\n\nfn main() {\n    let t = calculate_total();\n}\n"
+    );
+    assert_eq!(
+        result.fixed,
+        vec![("calulate".to_string(), "calculate".to_string())]
+    );
+}
+
+#[test]
+fn test_autofix_leaves_words_without_a_clear_fix() {
+    let processor = super::utils::get_processor();
+    let text = "the xyzzyq is fine";
+    let result = processor.autofix(text, Some(LanguageType::Text), None);
+    assert_eq!(result.text, text);
+    assert!(result.fixed.is_empty());
+    assert_eq!(result.unfixed, vec!["xyzzyq".to_string()]);
+}
+
+#[test]
+fn test_preload_dictionaries() {
+    let processor = super::utils::get_processor();
+    // en_us plus the default word lists
+    assert!(processor.preload_dictionaries() >= 2);
+    super::utils::assert_spelling_with(&processor, LanguageType::Text, "a wrod", &["wrod"], &[]);
+}
+
+#[test]
+fn test_frequency_list_ranks_suggestions() {
+    let baseline = super::utils::get_processor()
+        .get_suggestions("testz")
+        .unwrap();
+    assert!(baseline.len() > 1, "{baseline:?}");
+    let rare = baseline.last().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let frequency_list = dir.path().join("frequencies.tsv");
+    std::fs::write(&frequency_list, format!("{rare}\t1000000\n")).unwrap();
+    let config = std::sync::Arc::new(codebook_config::CodebookConfigMemory::new(
+        codebook_config::ConfigSettings {
+            frequency_list: Some(frequency_list.clone()),
+            ..Default::default()
+        },
+    ));
+    let processor = super::utils::make_codebook(config);
+    let ranked = processor.get_suggestions("testz").unwrap();
+    assert_eq!(&ranked[0], rare);
+    // Unlisted words keep their relative order
+    let rest: Vec<&String> = baseline.iter().filter(|w| *w != rare).collect();
+    assert_eq!(ranked[1..].iter().collect::<Vec<_>>(), rest);
+
+    // Editing the list re-ranks, rather than reusing cached suggestions
+    let other = &baseline[baseline.len() - 2];
+    std::fs::write(&frequency_list, format!("{other}\t5000000\n{rare}\t1\n")).unwrap();
+    let reranked = processor.get_suggestions("testz").unwrap();
+    assert_eq!(&reranked[0], other);
+}
+
+#[test]
+fn test_guess_languages() {
+    // The fixtures plus a tiny stand-in French word list
+    let dir = tempfile::tempdir().unwrap();
+    let fixtures =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dictionaries");
+    for entry in std::fs::read_dir(fixtures).unwrap() {
+        let path = entry.unwrap().path();
+        std::fs::copy(&path, dir.path().join(path.file_name().unwrap())).unwrap();
+    }
+    std::fs::write(dir.path().join("fr.txt"), "bonjour\nmerci\n").unwrap();
+
+    // Off unless detect_languages is set
+    let processor = codebook::Codebook::with_dictionary_dir(
+        std::sync::Arc::new(codebook_config::CodebookConfigMemory::default()),
+        Some(dir.path().to_path_buf()),
+    );
+    assert!(processor.guess_languages("bonjour").is_empty());
+
+    let config = std::sync::Arc::new(codebook_config::CodebookConfigMemory::new(
+        codebook_config::ConfigSettings {
+            detect_languages: vec!["fr".to_string()],
+            ..Default::default()
+        },
+    ));
+    let processor = codebook::Codebook::with_dictionary_dir(config, Some(dir.path().to_path_buf()));
+    // Still reported as a misspelling; the guess is only a hint
+    let results = processor.spell_check("bonjour wrodd", Some(LanguageType::Text), None);
+    let words: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+    assert_eq!(words, vec!["bonjour", "wrodd"]);
+    assert_eq!(processor.guess_languages("bonjour"), vec!["French"]);
+    assert!(processor.guess_languages("wrodd").is_empty());
+}
+
+#[test]
+fn test_far_off_suggestions_are_dropped() {
+    // Hunspell's only ideas for these are "overstuff" and "djibouti"
+    let processor = super::utils::get_processor();
+    for word in ["qrstuvwx", "kwyjibo"] {
+        assert_eq!(processor.get_suggestions(word), Some(vec![]), "{word}");
+    }
+    // Close corrections are kept
+    let suggestions = processor.get_suggestions("recieve").unwrap();
+    assert!(suggestions.contains(&"receive".to_string()));
+
+    let config = std::sync::Arc::new(codebook_config::CodebookConfigMemory::new(
+        codebook_config::ConfigSettings {
+            max_suggestion_distance: Some(10),
+            ..Default::default()
+        },
+    ));
+    let processor = super::utils::make_codebook(config);
+    assert_eq!(
+        processor.get_suggestions("kwyjibo"),
+        Some(vec!["djibouti".to_string()])
+    );
+}
+
+#[test]
+fn test_suggest_limited() {
+    use codebook::dictionaries::dictionary::{Dictionary, HunspellDictionary};
+    let dictionary = HunspellDictionary::new(
+        "tests/fixtures/dictionaries/en_us.aff",
+        "tests/fixtures/dictionaries/en_us.dic",
+    )
+    .unwrap();
+    let full = dictionary.suggest("wrod");
+    assert_eq!(full.len(), 5);
+    let limited = dictionary.suggest_limited("wrod", 2);
+    assert_eq!(limited, full[..2]);
+    // Rejected candidates don't use up the limit
+    let filtered = dictionary.suggest_filtered("wrod", 2, &|s| s != full[0]);
+    assert_eq!(filtered, full[1..3]);
+    // When edits find too few, the slower search fills in the rest
+    assert!(dictionary.suggest_limited("internationalizaton", 5).len() > 1);
+
+    // Long mangled words have no close edits, and are too long for the
+    // slow search; keep them well under what a code action can wait for
+    let processor = super::utils::get_processor();
+    let start = std::time::Instant::now();
+    let suggestions = processor.get_suggestions("qwxzjkvbnmplqwxzjkvbnmplqwxzjkvbnmpl");
+    assert_eq!(suggestions, Some(vec![]));
+    assert!(
+        start.elapsed() < std::time::Duration::from_secs(10),
+        "took {:?}",
+        start.elapsed()
+    );
+}
+
+#[test]
+fn test_sentence_case_suggestions() {
+    let text = "// Parse the input. Recieve it, then we recieve more\n";
+    let start = text.find("Recieve").unwrap();
+    let middle = text.find("recieve").unwrap();
+
+    // Off by default: suggestions keep Hunspell's casing
+    let processor = super::utils::get_processor();
+    let suggestions = processor
+        .get_suggestions_in_context("recieve", text, start, Some("comment"))
+        .unwrap();
+    assert!(
+        suggestions.contains(&"receive".to_string()),
+        "{suggestions:?}"
+    );
+
+    let config = std::sync::Arc::new(codebook_config::CodebookConfigMemory::new(
+        codebook_config::ConfigSettings {
+            sentence_case_suggestions: Some(true),
+            ..Default::default()
+        },
+    ));
+    let processor = super::utils::make_codebook(config);
+    // Sentence-initial: capitalized, whatever the misspelling's case
+    for word in ["Recieve", "recieve"] {
+        let suggestions = processor
+            .get_suggestions_in_context(word, text, start, Some("comment"))
+            .unwrap();
+        assert_eq!(suggestions[0], "Receive", "{suggestions:?}");
+    }
+    // Mid-sentence: lowercase
+    for word in ["Recieve", "recieve"] {
+        let suggestions = processor
+            .get_suggestions_in_context(word, text, middle, Some("comment"))
+            .unwrap();
+        assert_eq!(suggestions[0], "receive", "{suggestions:?}");
+    }
+    // Right after the comment marker is a sentence start too
+    let suggestions = processor
+        .get_suggestions_in_context("recieve", "x = 1 # recieve it", 8, Some("comment"))
+        .unwrap();
+    assert_eq!(suggestions[0], "Receive", "{suggestions:?}");
+    // Plain text too; mixed-case names keep their casing
+    let suggestions = processor
+        .get_suggestions_in_context("Pythn", "we use Pythn", 7, None)
+        .unwrap();
+    assert_eq!(suggestions, vec!["python"]);
+    let suggestions = processor
+        .get_suggestions_in_context("Githb", "hosted on Githb", 10, None)
+        .unwrap();
+    assert!(
+        suggestions.contains(&"GitHub".to_string()),
+        "{suggestions:?}"
+    );
+    // Words the dictionaries have capitalized stay that way mid-sentence,
+    // and get capitalized when suggested for a lowercase misspelling
+    let in_middle = |word: &str| {
+        let text = format!("then {word} said");
+        processor
+            .get_suggestions_in_context(word, &text, 5, Some("comment"))
+            .unwrap()
+    };
+    let suggestions = in_middle("Ij");
+    assert!(suggestions.contains(&"I".to_string()), "{suggestions:?}");
+    assert!(!suggestions.contains(&"i".to_string()), "{suggestions:?}");
+    for word in ["Pariss", "pariss"] {
+        let suggestions = in_middle(word);
+        assert!(
+            suggestions.contains(&"Paris".to_string()),
+            "{suggestions:?}"
+        );
+        assert!(
+            !suggestions.contains(&"paris".to_string()),
+            "{suggestions:?}"
+        );
+    }
+    assert_eq!(in_middle("Mondy")[0], "Monday");
+    // Identifiers aren't prose
+    let suggestions = processor
+        .get_suggestions_in_context("Recieve", "let x = Recieve;", 8, Some("identifier"))
+        .unwrap();
+    assert!(
+        suggestions.contains(&"Receive".to_string()),
+        "{suggestions:?}"
+    );
+}
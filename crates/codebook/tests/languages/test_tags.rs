@@ -171,3 +171,104 @@ fn test_text_language_ignores_tags() {
         &[],
     );
 }
+
+/// Ruby with a plain string and a heredoc (tagged `string.heredoc`).
+const RUBY_HEREDOC_SAMPLE: &str = r#"
+# A commet about this
+greting = "Helo there"
+long_text = <<~TEXT
+  The documantation goes here.
+TEXT
+"#;
+
+#[test]
+fn test_ruby_exclude_heredoc() {
+    assert_spelling_with(
+        &get_processor_with_tags(vec!["comment", "string"], vec!["string.heredoc"]),
+        LanguageType::Ruby,
+        RUBY_HEREDOC_SAMPLE,
+        &["commet", "Helo"],
+        // Heredoc bodies and identifiers are filtered out.
+        &["documantation", "greting"],
+    );
+}
+
+#[test]
+fn test_ruby_include_heredoc_only() {
+    assert_spelling_with(
+        &get_processor_with_tags(vec!["string.heredoc"], vec![]),
+        LanguageType::Ruby,
+        RUBY_HEREDOC_SAMPLE,
+        &["documantation"],
+        &["commet", "Helo", "greting"],
+    );
+}
+
+#[test]
+fn test_check_scope_comments_only() {
+    let settings = codebook_config::ConfigSettings {
+        check_scope: Some(vec![codebook_config::CheckScope::Comments]),
+        ..Default::default()
+    };
+    let processor = super::utils::make_codebook(std::sync::Arc::new(
+        codebook_config::CodebookConfigMemory::new(settings),
+    ));
+    let sample_text = r#"
+    /// A persn with an emial
+    struct Person {
+        adresss: String,
+        nmber: u32,
+    }
+"#;
+    assert_spelling_with(
+        &processor,
+        LanguageType::Rust,
+        sample_text,
+        &["persn", "emial"],
+        &["adresss", "nmber"],
+    );
+}
+
+#[test]
+fn test_check_scope_combined() {
+    let settings = codebook_config::ConfigSettings {
+        check_scope: Some(vec![
+            codebook_config::CheckScope::Strings,
+            codebook_config::CheckScope::Identifiers,
+        ]),
+        ..Default::default()
+    };
+    let processor = super::utils::make_codebook(std::sync::Arc::new(
+        codebook_config::CodebookConfigMemory::new(settings),
+    ));
+    assert_spelling_with(
+        &processor,
+        LanguageType::Rust,
+        RUST_SAMPLE,
+        &["calculat", "nmber", "strng"],
+        &["commet"],
+    );
+}
+
+#[test]
+fn test_results_carry_capture_tags() {
+    let processor = get_processor_with_tags(vec![], vec![]);
+    let tags: Vec<(String, Option<String>)> = processor
+        .spell_check(RUST_SAMPLE, Some(LanguageType::Rust), None)
+        .into_iter()
+        .map(|r| (r.word, r.tag))
+        .collect();
+    let tag_of = |word: &str| {
+        tags.iter()
+            .find(|(w, _)| w == word)
+            .and_then(|(_, tag)| tag.as_deref())
+    };
+    assert_eq!(tag_of("commet"), Some("comment.line"));
+    assert_eq!(tag_of("calculat"), Some("identifier.function"));
+    assert_eq!(tag_of("nmber"), Some("identifier.variable"));
+    assert_eq!(tag_of("strng"), Some("string"));
+
+    // Plain text has no captures
+    let results = processor.spell_check("a commet", Some(LanguageType::Text), None);
+    assert_eq!(results[0].tag, None);
+}
@@ -46,3 +46,29 @@ fn test_text_no_false_positives_after_emoji() {
     );
     assert!(results.is_empty(), "unexpected flags: {results:?}");
 }
+
+/// Windows line endings: the `\r` before each `\n` must not end up in a
+/// word or shift the reported byte ranges, whatever the grammar.
+#[test]
+fn test_crlf_line_endings() {
+    assert_spelling_at(
+        LanguageType::Text,
+        "first wrold\r\nsecond wrold\r\n\r\nthrid line\r\n",
+        &[("thrid", &[0]), ("wrold", &[0, 1])],
+    );
+    assert_spelling_at(
+        LanguageType::Rust,
+        "// a wrold\r\nfn main() {\r\n    let wrold = \"thrid\";\r\n}\r\n",
+        &[("thrid", &[0]), ("wrold", &[0, 1])],
+    );
+    assert_spelling_at(
+        LanguageType::Markdown,
+        "# Titel\r\n\r\nSome wrold text\r\n- thrid item\r\n",
+        &[("Titel", &[0]), ("thrid", &[0]), ("wrold", &[0])],
+    );
+    assert_spelling_at(
+        LanguageType::Python,
+        "# wrold\r\ndef main():\r\n    return \"thrid\"\r\n",
+        &[("thrid", &[0]), ("wrold", &[0])],
+    );
+}
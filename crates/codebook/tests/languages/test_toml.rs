@@ -1,6 +1,6 @@
 use codebook::queries::LanguageType;
 
-use super::utils::assert_spelling;
+use super::utils::{assert_spelling, assert_spelling_with, get_processor_with_tags};
 
 #[test]
 fn test_toml_location() {
@@ -13,3 +13,34 @@ fn test_toml_location() {
     // Dependency keys ("testz") are not spell-checked; string values are.
     assert_spelling(LanguageType::TOML, sample_toml, &["testx"], &["testz"]);
 }
+
+#[test]
+fn test_toml_values_checked_not_keys_or_numbers() {
+    let sample_toml = r#"
+        [packge]
+        descripshun = "A tool for chekking words"
+        versoin = 12
+        "quoted_kee" = "fine"
+"#;
+    assert_spelling(
+        LanguageType::TOML,
+        sample_toml,
+        &["chekking"],
+        &["packge", "descripshun", "versoin", "kee"],
+    );
+}
+
+#[test]
+fn test_toml_keys_opt_in() {
+    let sample_toml = r#"
+        [packge]
+        descripshun = "A tool for chekking words"
+"#;
+    assert_spelling_with(
+        &get_processor_with_tags(vec!["string", "identifier.key"], vec![]),
+        LanguageType::TOML,
+        sample_toml,
+        &["packge", "descripshun", "chekking"],
+        &[],
+    );
+}
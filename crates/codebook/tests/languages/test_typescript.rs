@@ -1,6 +1,6 @@
 use codebook::queries::LanguageType;
 
-use super::utils::assert_spelling_at;
+use super::utils::{assert_spelling, assert_spelling_at};
 
 #[test]
 fn test_typescript_location() {
@@ -59,3 +59,16 @@ fn test_typescript_location() {
         ],
     );
 }
+
+#[test]
+fn test_typescript_jsdoc_tags() {
+    let sample_text = r#"
+    /** Formatt a user. @param {Userr} user @deprecated */
+    export function format(user: User): string { return ""; }"#;
+    assert_spelling(
+        LanguageType::Typescript,
+        sample_text,
+        &["Formatt"],
+        &["Userr", "param", "deprecated"],
+    );
+}
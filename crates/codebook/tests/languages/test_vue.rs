@@ -0,0 +1,40 @@
+use codebook::queries::LanguageType;
+
+/// Vue single-file components go through the HTML pipeline: the
+/// `<template>` markup is HTML, `<script>` is injected as TypeScript and
+/// `<style>` as CSS.
+#[test]
+fn test_vue_single_file_component() {
+    let sample_text = r#"<template>
+  <div class="greeting">
+    <h1>Welcom back</h1>
+    <p :title="message">{{ message }}</p>
+  </div>
+</template>
+
+<script setup lang="ts">
+// Shows a frendly greeting
+const message = "Hello from the compnent";
+</script>
+
+<style scoped>
+.greeting {
+  color: green;
+}
+</style>
+"#;
+    let processor = super::utils::get_processor();
+    super::utils::assert_spelling_with(
+        &processor,
+        LanguageType::HTML,
+        sample_text,
+        &["Welcom", "frendly", "compnent"],
+        &["greeting", "message", "scoped"],
+    );
+
+    // Detected from a .vue path too
+    let results = processor.spell_check(sample_text, None, Some("src/Greeting.vue"));
+    let mut misspelled: Vec<&str> = results.iter().map(|r| r.word.as_str()).collect();
+    misspelled.sort_unstable();
+    assert_eq!(misspelled, ["Welcom", "compnent", "frendly"]);
+}
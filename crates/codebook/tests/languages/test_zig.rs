@@ -41,3 +41,32 @@ fn addNumberrs(firstt: i32, seconnd: i32) i32 {
         ],
     );
 }
+
+#[test]
+fn test_zig_doc_comments_and_builtins() {
+    let sample_text = r#"
+const std = @import("std");
+const builtin = @import("builtin");
+
+/// Retuns the larger of two numbrs.
+pub fn maxOf(comptime T: type, a: T, b: T) T {
+    // Compair without branching
+    return if (a > b) a else b;
+}
+
+pub fn main() !void {
+    const stdout = std.io.getStdOut().writer();
+    const bigest = maxOf(u8, @intCast(3), 4);
+    try stdout.print("{d}\n", .{@as(u8, 0)});
+    defer std.debug.print("finished\n", .{});
+}
+"#;
+    assert_spelling(
+        LanguageType::Zig,
+        sample_text,
+        &["Retuns", "numbrs", "Compair", "bigest"],
+        &[
+            "import", "builtin", "comptime", "intCast", "defer", "stdout",
+        ],
+    );
+}
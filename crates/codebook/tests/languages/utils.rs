@@ -194,5 +194,5 @@ fn network_guard_active_in_test_builds() {
     use codebook::dictionaries::manager::DictionaryManager;
     let temp_cache = tempfile::tempdir().unwrap();
     let manager = DictionaryManager::new(&temp_cache.path().to_path_buf());
-    let _ = manager.get_dictionary("en_gb");
+    let _ = manager.get_dictionary("en_gb", false);
 }
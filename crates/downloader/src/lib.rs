@@ -1,7 +1,7 @@
 use anyhow::Result;
 use base16ct::lower;
 use chrono::{DateTime, Utc};
-use log::{error, info};
+use log::{error, info, warn};
 use reqwest::blocking::Client;
 use reqwest::header::{IF_MODIFIED_SINCE, LAST_MODIFIED};
 use rustls::ClientConfig;
@@ -14,10 +14,30 @@ use std::fs::{self, File};
 use std::io::{BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
 use tempfile::NamedTempFile;
 
 const METADATA_FILE: &str = "_metadata.json";
 const TWO_WEEKS: u64 = 14 * 24 * 3600;
+/// Retries after a failed download, with a growing pause between attempts.
+const MAX_RETRIES: u32 = 2;
+/// How long a download may go without progress, unless overridden by
+/// `CODEBOOK_DOWNLOAD_TIMEOUT_SECS`: connecting, waiting for the response and
+/// each read of the body get this long, not the whole download, so a large
+/// Hunspell file on a slow but steady link still arrives. Short enough that
+/// an unresponsive host doesn't stall the LSP indefinitely.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The download timeout: `CODEBOOK_DOWNLOAD_TIMEOUT_SECS` if set to a
+/// positive number of seconds, otherwise [`DEFAULT_TIMEOUT`].
+pub fn download_timeout() -> Duration {
+    std::env::var("CODEBOOK_DOWNLOAD_TIMEOUT_SECS")
+        .ok()
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
 
 /// A definitive HTTP client-error response (4xx, e.g. 404). The server
 /// answered; retrying won't change the result. Downloads fail fast on these,
@@ -41,11 +61,28 @@ impl std::fmt::Display for PermanentHttpError {
 
 impl std::error::Error for PermanentHttpError {}
 
+/// A file was requested in offline mode and isn't in the cache.
+/// Retrieve via `err.downcast_ref::<OfflineError>()`.
+#[derive(Debug)]
+pub struct OfflineError {
+    pub url: String,
+}
+
+impl std::fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is not cached and offline mode is on", self.url)
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
 /// A response from an [`HttpTransport`]: status code, the Last-Modified
-/// header if present, and a streaming body.
+/// and Content-Length headers if present, and a streaming body.
 pub struct TransportResponse {
     pub status: u16,
     pub last_modified: Option<String>,
+    /// A body that ends short of this is a truncated download.
+    pub content_length: Option<u64>,
     pub body: Box<dyn Read>,
 }
 
@@ -57,14 +94,21 @@ pub trait HttpTransport: Send + Sync {
     fn get(&self, url: &str, if_modified_since: Option<&str>) -> Result<TransportResponse>;
 }
 
-/// Test-only network guard, in the style of pytest-socket: any request
-/// through the real transport panics. Compiled in for this crate's own unit
+/// Test-only network guard, in the style of pytest-socket: any non-loopback
+/// request through the real transport panics. Compiled in for this crate's own unit
 /// tests and whenever the `deny-network` feature is enabled — workspace
 /// crates enable that feature from their dev-dependencies, so every
 /// `cargo test` build denies network access while release builds carry no
 /// guard at all.
 #[cfg(any(test, feature = "deny-network"))]
 fn assert_network_allowed(url: &str) {
+    // Loopback is allowed so tests can run a local mock server
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(String::from));
+    if matches!(host.as_deref(), Some("127.0.0.1" | "localhost" | "[::1]")) {
+        return;
+    }
     panic!(
         "Blocked network request to {url}: tests run with networking denied \
          (deny-network feature). Inject a fake HttpTransport via \
@@ -80,12 +124,21 @@ fn assert_network_allowed(_url: &str) {}
 /// platform-verified TLS (bundled Mozilla roots as fallback).
 pub struct ReqwestTransport {
     client: OnceLock<Client>,
+    timeout: Duration,
 }
 
 impl ReqwestTransport {
     pub fn new() -> Self {
+        Self::with_timeout(download_timeout())
+    }
+
+    /// A transport whose connects and reads fail after `timeout` without
+    /// progress, so a stalled host surfaces as an error instead of hanging.
+    /// The blocking client applies its timeout to each body read.
+    pub fn with_timeout(timeout: Duration) -> Self {
         Self {
             client: OnceLock::new(),
+            timeout,
         }
     }
 
@@ -96,6 +149,8 @@ impl ReqwestTransport {
             let config = Self::build_tls_config(arc_crypto_provider);
             reqwest::blocking::Client::builder()
                 .use_preconfigured_tls(config)
+                .connect_timeout(self.timeout)
+                .timeout(self.timeout)
                 .build()
                 .expect("codebook: failed to build HTTP client")
         })
@@ -159,9 +214,11 @@ impl HttpTransport for ReqwestTransport {
             .get(LAST_MODIFIED)
             .and_then(|hv| hv.to_str().ok())
             .map(String::from);
+        let content_length = response.content_length();
         Ok(TransportResponse {
             status,
             last_modified,
+            content_length,
             body: Box::new(response),
         })
     }
@@ -208,6 +265,35 @@ impl Downloader {
         }
     }
 
+    /// For offline use: the cached copy of `url` as-is, even if due for
+    /// revalidation, or [`OfflineError`] when there's no intact one, so
+    /// locked-down environments behave deterministically. Never touches the
+    /// network.
+    pub fn get_cached(&self, url: &str) -> Result<PathBuf> {
+        let entry = {
+            let metadata = self.metadata().read().unwrap();
+            metadata.files.get(url).cloned()
+        };
+        entry
+            .filter(|entry| entry.path.exists() && self.cached_file_valid(entry))
+            .map(|entry| entry.path)
+            .ok_or_else(|| {
+                anyhow::Error::new(OfflineError {
+                    url: url.to_string(),
+                })
+            })
+    }
+
+    /// Path of the cached copy of `url`, if one is on disk.
+    pub fn cached_path(&self, url: &str) -> Option<PathBuf> {
+        let metadata = self.metadata().read().unwrap();
+        metadata
+            .files
+            .get(url)
+            .map(|entry| entry.path.clone())
+            .filter(|path| path.exists())
+    }
+
     fn metadata(&self) -> &RwLock<Metadata> {
         let metadata_path = self.metadata_path.clone();
         let cache_dir = self.cache_dir.clone();
@@ -289,6 +375,11 @@ impl Downloader {
         }
     }
 
+    /// The cached copy of `url`, downloading it first when it's missing and
+    /// revalidating it when it's old. A download that ends short of its
+    /// Content-Length is retried and then rejected instead of cached, and a
+    /// cached copy that no longer hashes to what was downloaded (changed or
+    /// damaged on disk) is re-downloaded.
     pub fn get(&self, url: &str) -> Result<PathBuf> {
         let entry = {
             let metadata = self.metadata().read().unwrap();
@@ -300,6 +391,10 @@ impl Downloader {
                 if !entry.path.exists() {
                     self.purge_stale_entry(url, &entry.path);
                     self.download_new(url)
+                } else if !self.cached_file_valid(&entry) {
+                    self.purge_stale_entry(url, &entry.path);
+                    remove_cached_file(&entry.path);
+                    self.download_new(url)
                 } else {
                     let needs_update =
                         entry.last_checked.timestamp() + TWO_WEEKS as i64 <= Utc::now().timestamp();
@@ -334,6 +429,38 @@ impl Downloader {
         })
     }
 
+    /// Whether a cached file still hashes to the hash in its `.sha256`
+    /// sidecar, or else to the one recorded at download time. The sidecar
+    /// wins over the metadata because another process may have replaced the
+    /// file since this one loaded it. A matching file without a sidecar
+    /// (e.g. a cache written before sidecars existed) gets one.
+    fn cached_file_valid(&self, entry: &FileEntry) -> bool {
+        let recorded = fs::read_to_string(sidecar_path(&entry.path)).ok();
+        let has_sidecar = recorded.is_some();
+        let expected = recorded
+            .map(|h| h.trim().to_string())
+            .unwrap_or_else(|| entry.content_hash.clone());
+        match compute_file_hash(&entry.path) {
+            Ok(actual) if actual.eq_ignore_ascii_case(&expected) => {
+                if !has_sidecar {
+                    write_sidecar(&entry.path, &actual);
+                }
+                true
+            }
+            Ok(actual) => {
+                warn!(
+                    "Cached file {} has sha256 {actual}, expected {expected}",
+                    entry.path.display()
+                );
+                false
+            }
+            Err(e) => {
+                warn!("Failed to hash cached file {}: {e}", entry.path.display());
+                false
+            }
+        }
+    }
+
     fn try_update(&self, url: &str) -> Result<PathBuf> {
         // Get last modified time with read lock
         let last_modified = {
@@ -360,7 +487,7 @@ impl Downloader {
 
     fn handle_updated_response(&self, url: &str, response: TransportResponse) -> Result<PathBuf> {
         let last_modified = parse_last_modified(response.last_modified.as_deref());
-        let temp_file = self.download_to_temp(response.body)?;
+        let temp_file = self.download_to_temp(url, response.body, response.content_length)?;
         let new_hash = compute_file_hash(temp_file.path())?;
         // The entry can disappear concurrently (another thread purging a
         // stale path); a missing entry just means "changed".
@@ -379,11 +506,10 @@ impl Downloader {
     }
 
     fn download_new(&self, url: &str) -> Result<PathBuf> {
-        let max_retries = 2;
         let mut last_err = None;
-        for attempt in 0..=max_retries {
+        for attempt in 0..=MAX_RETRIES {
             if attempt > 0 {
-                std::thread::sleep(std::time::Duration::from_millis(500 * attempt as u64));
+                std::thread::sleep(Duration::from_millis(500 * attempt as u64));
             }
             match self.try_download_new(url) {
                 Ok(path) => return Ok(path),
@@ -395,7 +521,7 @@ impl Downloader {
                     log::warn!(
                         "Download attempt {}/{} failed for {url}: {e}",
                         attempt + 1,
-                        max_retries + 1
+                        MAX_RETRIES + 1
                     );
                     last_err = Some(e);
                 }
@@ -419,14 +545,28 @@ impl Downloader {
             ));
         }
         let last_modified = parse_last_modified(response.last_modified.as_deref());
-        let temp_file = self.download_to_temp(response.body)?;
+        let temp_file = self.download_to_temp(url, response.body, response.content_length)?;
         let new_hash = compute_file_hash(temp_file.path())?;
         self.store_new_file(url, temp_file, last_modified, new_hash)
     }
 
-    fn download_to_temp(&self, mut body: Box<dyn Read>) -> Result<NamedTempFile> {
+    /// Stream `body` into a temp file in the cache directory, failing when
+    /// it doesn't match the Content-Length (a connection dropped mid-body).
+    fn download_to_temp(
+        &self,
+        url: &str,
+        mut body: Box<dyn Read>,
+        content_length: Option<u64>,
+    ) -> Result<NamedTempFile> {
         let mut temp_file = NamedTempFile::new_in(&self.cache_dir)?;
-        std::io::copy(&mut body, &mut temp_file)?;
+        let written = std::io::copy(&mut body, &mut temp_file)?;
+        if let Some(expected) = content_length
+            && written != expected
+        {
+            return Err(anyhow::anyhow!(
+                "Truncated download for {url}: got {written} of {expected} bytes"
+            ));
+        }
         Ok(temp_file)
     }
 
@@ -440,6 +580,7 @@ impl Downloader {
         let filename = hash_url(url);
         let path = self.cache_dir.join(filename);
         temp_file.persist(&path)?;
+        write_sidecar(&path, &content_hash);
 
         let entry = FileEntry {
             path: path.clone(),
@@ -470,13 +611,15 @@ impl Downloader {
 
             new_path = self.cache_dir.join(hash_url(url));
             temp_file.persist(&new_path)?;
+            write_sidecar(&new_path, &content_hash);
 
             // Remove old file if it's different
             if let Some(old_path) = old_path
                 && old_path != new_path
                 && old_path.exists()
             {
-                fs::remove_file(old_path)?;
+                fs::remove_file(&old_path)?;
+                let _ = fs::remove_file(sidecar_path(&old_path));
             }
 
             let entry = FileEntry {
@@ -530,6 +673,23 @@ fn compute_file_hash(path: &Path) -> Result<String> {
     Ok(lower::encode_string(&hasher.finalize()))
 }
 
+/// The `.sha256` file next to a cached file, holding its hash as downloaded.
+fn sidecar_path(path: &Path) -> PathBuf {
+    path.with_extension("sha256")
+}
+
+/// Best-effort: a missing sidecar only means the file is rehashed next time.
+fn write_sidecar(path: &Path, hash: &str) {
+    if let Err(e) = fs::write(sidecar_path(path), hash) {
+        warn!("Failed to write checksum for {}: {e}", path.display());
+    }
+}
+
+fn remove_cached_file(path: &Path) {
+    let _ = fs::remove_file(path);
+    let _ = fs::remove_file(sidecar_path(path));
+}
+
 fn parse_last_modified(header: Option<&str>) -> Option<DateTime<Utc>> {
     header
         .and_then(|s| DateTime::parse_from_rfc2822(s).ok())
@@ -539,7 +699,6 @@ fn parse_last_modified(header: Option<&str>) -> Option<DateTime<Utc>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Duration;
     use std::collections::VecDeque;
     use std::io::Cursor;
     use std::sync::Mutex;
@@ -551,6 +710,7 @@ mod tests {
         status: u16,
         body: &'static str,
         last_modified: Option<&'static str>,
+        content_length: Option<u64>,
     }
 
     fn ok(status: u16, body: &'static str, last_modified: Option<&'static str>) -> ScriptedResult {
@@ -558,6 +718,17 @@ mod tests {
             status,
             body,
             last_modified,
+            content_length: None,
+        })
+    }
+
+    /// A 200 whose body stops short of its Content-Length.
+    fn truncated(body: &'static str, content_length: u64) -> ScriptedResult {
+        Ok(FakeResponse {
+            status: 200,
+            body,
+            last_modified: None,
+            content_length: Some(content_length),
         })
     }
 
@@ -607,6 +778,7 @@ mod tests {
             next.map(|r| TransportResponse {
                 status: r.status,
                 last_modified: r.last_modified.map(String::from),
+                content_length: r.content_length,
                 body: Box::new(Cursor::new(r.body.as_bytes().to_vec())),
             })
         }
@@ -643,6 +815,83 @@ mod tests {
         let _ = downloader.get("https://example.invalid/dict.txt");
     }
 
+    #[test]
+    fn test_stalled_server_times_out() {
+        // Accepts connections but never answers, like an overloaded host
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut held = Vec::new();
+            for stream in listener.incoming() {
+                held.push(stream);
+            }
+        });
+
+        let transport = ReqwestTransport::with_timeout(Duration::from_millis(200));
+        let started = std::time::Instant::now();
+        let result = transport.get(&format!("http://{addr}/dict.txt"), None);
+
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    /// Serves one response, sending the head and then each body chunk after
+    /// a `pause`. With `stall`, stops sending before the last chunk.
+    fn serve_slowly(chunks: &'static [&'static str], pause: Duration, stall: bool) -> String {
+        use std::io::{BufRead, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Read the request up to the blank line ending its head
+            let mut request = std::io::BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while request.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let length: usize = chunks.iter().map(|c| c.len()).sum();
+            let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {length}\r\n\r\n");
+            stream.write_all(head.as_bytes()).unwrap();
+            for (i, chunk) in chunks.iter().enumerate() {
+                std::thread::sleep(pause);
+                if stall && i == chunks.len() - 1 {
+                    std::thread::sleep(pause * 20);
+                    return;
+                }
+                stream.write_all(chunk.as_bytes()).unwrap();
+                stream.flush().unwrap();
+            }
+        });
+        format!("http://{addr}/dict.txt")
+    }
+
+    #[test]
+    fn test_timeout_is_per_read_not_overall() {
+        const CHUNKS: &[&str] = &["alpha\n", "beta\n", "gamma\n", "delta\n", "epsilon\n"];
+        let transport = ReqwestTransport::with_timeout(Duration::from_millis(500));
+
+        // A steady download longer than the timeout completes
+        let url = serve_slowly(CHUNKS, Duration::from_millis(200), false);
+        let mut body = String::new();
+        transport
+            .get(&url, None)
+            .unwrap()
+            .body
+            .read_to_string(&mut body)
+            .unwrap();
+        assert_eq!(body, CHUNKS.concat());
+
+        // One that stops making progress fails
+        let url = serve_slowly(CHUNKS, Duration::from_millis(200), true);
+        let mut body = String::new();
+        let read = transport
+            .get(&url, None)
+            .unwrap()
+            .body
+            .read_to_string(&mut body);
+        assert!(read.is_err());
+    }
+
     #[test]
     fn test_404_fails_fast_without_retries() {
         let transport = FakeTransport::new(vec![ok(404, "", None)]);
@@ -676,6 +925,28 @@ mod tests {
         assert!(err.downcast_ref::<PermanentHttpError>().is_none());
     }
 
+    #[test]
+    fn test_offline_uses_cache_and_never_requests() {
+        let transport = FakeTransport::new(vec![ok(200, "cached content", None)]);
+        let temp_dir = tempdir().unwrap();
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+        let path = downloader.get(URL).unwrap();
+
+        // Make the entry due for revalidation; offline must skip it anyway
+        {
+            let mut metadata = downloader.metadata().write().unwrap();
+            let entry = metadata.files.get_mut(URL).unwrap();
+            entry.last_checked = Utc::now() - chrono::Duration::days(30);
+        }
+        assert_eq!(downloader.get_cached(URL).unwrap(), path);
+
+        let err = downloader
+            .get_cached("https://example.com/missing.txt")
+            .unwrap_err();
+        assert!(err.downcast_ref::<OfflineError>().is_some());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
     #[test]
     fn test_returns_cached_file_within_two_weeks_without_request() {
         let transport = FakeTransport::new(vec![ok(200, "cached content", None)]);
@@ -714,7 +985,7 @@ mod tests {
         {
             let mut metadata = downloader.metadata().write().unwrap();
             let entry = metadata.files.get_mut(URL).unwrap();
-            entry.last_checked = stored_last_modified.unwrap() - Duration::weeks(3);
+            entry.last_checked = stored_last_modified.unwrap() - chrono::Duration::weeks(3);
         }
 
         transport.push(ok(200, "v2", Some("Fri, 23 Oct 2020 07:28:00 GMT")));
@@ -742,7 +1013,7 @@ mod tests {
         {
             let mut metadata = downloader.metadata().write().unwrap();
             let entry = metadata.files.get_mut(URL).unwrap();
-            entry.last_checked = Utc::now() - Duration::seconds(TWO_WEEKS as i64 * 2);
+            entry.last_checked = Utc::now() - chrono::Duration::seconds(TWO_WEEKS as i64 * 2);
         }
         transport.push(connection_error());
 
@@ -793,7 +1064,7 @@ mod tests {
         assert_eq!(original_path, cached_path);
         let metadata = downloader.metadata().read().unwrap();
         let entry = metadata.files.get(URL).unwrap();
-        assert!(entry.last_checked > Utc::now() - Duration::seconds(1));
+        assert!(entry.last_checked > Utc::now() - chrono::Duration::seconds(1));
     }
 
     #[test]
@@ -831,6 +1102,97 @@ mod tests {
         assert_eq!(hash_url(same_url), hash_url(same_url));
     }
 
+    #[test]
+    fn test_truncated_download_is_never_cached() {
+        let transport = FakeTransport::new(vec![
+            truncated("cont", 7),
+            truncated("cont", 7),
+            truncated("cont", 7),
+        ]);
+        let temp_dir = tempdir().unwrap();
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+
+        // No expected hash: the Content-Length alone must catch it
+        let err = downloader.get(URL).unwrap_err();
+
+        assert_eq!(transport.requests().len(), 3);
+        assert!(err.to_string().contains("Truncated download"));
+        assert!(downloader.cached_path(URL).is_none());
+    }
+
+    #[test]
+    fn test_truncated_download_is_refetched() {
+        let transport = FakeTransport::new(vec![
+            truncated("cont", 7),
+            Ok(FakeResponse {
+                status: 200,
+                body: "content",
+                last_modified: None,
+                content_length: Some(7),
+            }),
+        ]);
+        let temp_dir = tempdir().unwrap();
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+
+        let path = downloader.get(URL).unwrap();
+
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "content");
+    }
+
+    #[test]
+    fn test_corrupt_cache_with_sidecar_is_redownloaded() {
+        let transport = FakeTransport::new(vec![ok(200, "content", None)]);
+        let temp_dir = tempdir().unwrap();
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+        let path = downloader.get(URL).unwrap();
+
+        // The sidecar still vouches for the original bytes
+        std::fs::write(&path, "cont").unwrap();
+
+        let transport = FakeTransport::new(vec![ok(200, "content", None)]);
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+        let path = downloader.get(URL).unwrap();
+
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "content");
+    }
+
+    #[test]
+    fn test_corrupt_cache_without_sidecar_is_redownloaded() {
+        let transport = FakeTransport::new(vec![ok(200, "content", None)]);
+        let temp_dir = tempdir().unwrap();
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+        let path = downloader.get(URL).unwrap();
+
+        // Corrupt the cached copy and drop its sidecar, as an older cache
+        // (or a crash mid-write) would leave it
+        std::fs::write(&path, "cont").unwrap();
+        std::fs::remove_file(sidecar_path(&path)).unwrap();
+
+        let transport = FakeTransport::new(vec![ok(200, "content", None)]);
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+        let path = downloader.get(URL).unwrap();
+
+        assert_eq!(transport.requests().len(), 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "content");
+        assert!(sidecar_path(&path).exists());
+    }
+
+    #[test]
+    fn test_valid_cache_without_sidecar_gets_one() {
+        let transport = FakeTransport::new(vec![ok(200, "content", None)]);
+        let temp_dir = tempdir().unwrap();
+        let downloader = Downloader::with_transport(temp_dir.path(), transport.clone());
+        let path = downloader.get(URL).unwrap();
+        std::fs::remove_file(sidecar_path(&path)).unwrap();
+
+        // Rehashing an intact file must not trigger a request
+        let downloader = Downloader::with_transport(temp_dir.path(), FakeTransport::new(vec![]));
+        assert_eq!(downloader.get(URL).unwrap(), path);
+        assert!(sidecar_path(&path).exists());
+    }
+
     #[test]
     fn test_redownloads_when_file_missing() {
         let transport = FakeTransport::new(vec![ok(200, "content", None)]);
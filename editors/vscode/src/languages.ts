@@ -7,6 +7,7 @@ export const SUPPORTED_LANGUAGES = [
   "elixir",
   "erlang",
   "go",
+  "graphql",
   "haskell",
   "html",
   "java",